@@ -0,0 +1,187 @@
+//! Derive macros for `common-utils-rs`.
+//!
+//! Enable through the `derive` feature of the main crate rather than
+//! depending on this crate directly.
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    Data, DeriveInput, Fields, GenericArgument, Lit, LitStr, PathArguments, Type, parse_macro_input,
+};
+
+/// Generates a `from_env()` constructor that reads every field from the
+/// process environment.
+///
+/// Container attributes:
+/// - `#[env(prefix = "APP_")]` – prepended to every variable name
+///
+/// Field attributes:
+/// - `#[env(name = "DB_URL")]` – variable name (defaults to the upper-cased field name)
+/// - `#[env(default = "...")]` – value used when the variable is unset
+/// - `#[env(required)]` – fail when unset, even for `Option` fields
+///
+/// Fields of type `Option<T>` are `None` when unset. All other fields are
+/// required unless a default is given. Every missing or malformed variable is
+/// reported at once.
+#[proc_macro_derive(FromEnv, attributes(env))]
+pub fn derive_from_env(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+struct FieldAttrs {
+    name: Option<String>,
+    default: Option<String>,
+    required: bool,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut prefix = String::new();
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("env")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("prefix") {
+                prefix = meta.value()?.parse::<LitStr>()?.value();
+                Ok(())
+            } else {
+                Err(meta.error("unsupported container attribute, expected `prefix`"))
+            }
+        })?;
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    "FromEnv can only be derived for structs with named fields",
+                ));
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "FromEnv can only be derived for structs",
+            ));
+        }
+    };
+
+    let mut reads = Vec::new();
+    let mut inits = Vec::new();
+
+    for field in fields {
+        let field_ident = field.ident.as_ref().expect("named field");
+        let attrs = parse_field_attrs(field)?;
+
+        let key = format!(
+            "{}{}",
+            prefix,
+            attrs
+                .name
+                .unwrap_or_else(|| field_ident.to_string().to_uppercase())
+        );
+        let default = match attrs.default {
+            Some(value) => quote!(::std::option::Option::Some(#value)),
+            None => quote!(::std::option::Option::None),
+        };
+
+        let optional = option_inner(&field.ty);
+        let parsed_ty = optional.unwrap_or(&field.ty);
+        let required = attrs.required || optional.is_none();
+
+        reads.push(quote! {
+            let #field_ident: ::std::option::Option<#parsed_ty> =
+                match ::common_utils_rs::env::__private::read_field::<#parsed_ty>(#key, #default) {
+                    ::std::result::Result::Ok(::std::option::Option::None) if #required => {
+                        __errors.push(::common_utils_rs::env::EnvError::Missing(#key.to_string()));
+                        ::std::option::Option::None
+                    }
+                    ::std::result::Result::Ok(value) => value,
+                    ::std::result::Result::Err(error) => {
+                        __errors.push(error);
+                        ::std::option::Option::None
+                    }
+                };
+        });
+
+        inits.push(if optional.is_some() {
+            quote!(#field_ident: #field_ident)
+        } else {
+            quote!(#field_ident: #field_ident.expect("checked above"))
+        });
+    }
+
+    Ok(quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// Build this struct from environment variables.
+            pub fn from_env() -> ::std::result::Result<Self, ::common_utils_rs::env::EnvError> {
+                let mut __errors = ::std::vec::Vec::new();
+                #(#reads)*
+                ::common_utils_rs::env::EnvError::aggregate(__errors)?;
+                ::std::result::Result::Ok(Self { #(#inits),* })
+            }
+        }
+    })
+}
+
+fn parse_field_attrs(field: &syn::Field) -> syn::Result<FieldAttrs> {
+    let mut attrs = FieldAttrs {
+        name: None,
+        default: None,
+        required: false,
+    };
+
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("env")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                attrs.name = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("default") {
+                attrs.default = Some(match meta.value()?.parse::<Lit>()? {
+                    Lit::Str(s) => s.value(),
+                    Lit::Int(i) => i.base10_digits().to_string(),
+                    Lit::Float(f) => f.base10_digits().to_string(),
+                    Lit::Bool(b) => b.value.to_string(),
+                    Lit::Char(c) => c.value().to_string(),
+                    other => {
+                        return Err(syn::Error::new_spanned(
+                            other,
+                            "unsupported default literal",
+                        ));
+                    }
+                });
+            } else if meta.path.is_ident("required") {
+                attrs.required = true;
+            } else {
+                return Err(meta.error(
+                    "unsupported field attribute, expected `name`, `default` or `required`",
+                ));
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(attrs)
+}
+
+/// Returns `T` when `ty` is `Option<T>`.
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        GenericArgument::Type(inner) => Some(inner),
+        _ => None,
+    }
+}
@@ -0,0 +1,139 @@
+//! An optional log of which variables the application reads.
+//!
+//! While [`record_access`] is on, every lookup in the process environment
+//! (through the free getters, or an [`EnvReader`](super::EnvReader) backed by
+//! it) is recorded once per key, in the order keys were first read. The
+//! defaulting getters such as [`get_or_default`](super::get_or_default) also
+//! record their fallback. [`access_log`] returns the records, e.g. to print
+//! a startup summary or generate documentation of the configuration an
+//! application actually uses.
+//!
+//! Values are not recorded, so the log never holds secrets.
+//!
+//! Example:
+//! ```
+//! use common_utils_rs::env::{access_log, get_bool, get_or_default, record_access};
+//!
+//! record_access(true);
+//! let host = get_or_default("ACCESS_DOC_HOST", "127.0.0.1");
+//! let debug = get_bool("ACCESS_DOC_DEBUG", false);
+//! record_access(false);
+//!
+//! for record in access_log() {
+//!     println!("{} present={} default={:?}", record.key, record.present, record.default);
+//! }
+//! ```
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+static RECORDING: AtomicBool = AtomicBool::new(false);
+static LOG: Mutex<Vec<AccessRecord>> = Mutex::new(Vec::new());
+
+/// One variable read while recording, returned by [`access_log`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessRecord {
+    pub key: String,
+    /// Whether the variable was set the last time it was read.
+    pub present: bool,
+    /// The fallback given to a defaulting getter, if any. Getters whose
+    /// default can't be displayed, such as
+    /// [`get_parsed_or_default`](super::get_parsed_or_default), leave this
+    /// empty.
+    pub default: Option<String>,
+}
+
+/// Start or stop recording reads of the process environment. Records made
+/// so far are kept either way.
+pub fn record_access(enabled: bool) {
+    RECORDING.store(enabled, Ordering::Relaxed);
+}
+
+/// The variables read while recording, in the order they were first read.
+pub fn access_log() -> Vec<AccessRecord> {
+    log().clone()
+}
+
+/// Forget every record.
+pub fn clear_access_log() {
+    log().clear();
+}
+
+/// Note a read of `key`, which was set if `present`.
+pub(crate) fn record(key: &str, present: bool) {
+    if !RECORDING.load(Ordering::Relaxed) {
+        return;
+    }
+    let mut log = log();
+    match log.iter_mut().find(|record| record.key == key) {
+        Some(record) => record.present = present,
+        None => log.push(AccessRecord {
+            key: key.to_string(),
+            present,
+            default: None,
+        }),
+    }
+}
+
+/// Note the fallback of a defaulting getter that just read `key`.
+pub(crate) fn record_default(key: &str, default: impl FnOnce() -> String) {
+    if !RECORDING.load(Ordering::Relaxed) {
+        return;
+    }
+    if let Some(record) = log().iter_mut().find(|record| record.key == key) {
+        record.default = Some(default());
+    }
+}
+
+// Recording only appends or updates whole records, so a poisoned lock is
+// still consistent.
+fn log() -> MutexGuard<'static, Vec<AccessRecord>> {
+    LOG.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test::with_vars;
+    use crate::env::{get_bool, get_duration_or_default, get_or_default, get_required};
+    use std::time::Duration;
+
+    fn records(prefix: &str) -> Vec<AccessRecord> {
+        access_log()
+            .into_iter()
+            .filter(|record| record.key.starts_with(prefix))
+            .collect()
+    }
+
+    fn record(key: &str, present: bool, default: Option<&str>) -> AccessRecord {
+        AccessRecord {
+            key: key.to_string(),
+            present,
+            default: default.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_access_log_records_reads_and_defaults() {
+        with_vars(&[("ACCESS_T_HOST", "db")], || {
+            get_bool("ACCESS_T_IGNORED", true);
+
+            record_access(true);
+            get_or_default("ACCESS_T_HOST", "localhost");
+            get_bool("ACCESS_T_DEBUG", false);
+            let _ = get_required("ACCESS_T_HOST");
+            get_duration_or_default("ACCESS_T_TIMEOUT", Duration::from_secs(30));
+            record_access(false);
+
+            get_bool("ACCESS_T_AFTER", true);
+        });
+
+        assert_eq!(
+            records("ACCESS_T_"),
+            [
+                record("ACCESS_T_HOST", true, Some("localhost")),
+                record("ACCESS_T_DEBUG", false, Some("false")),
+                record("ACCESS_T_TIMEOUT", false, Some("30s")),
+            ]
+        );
+    }
+}
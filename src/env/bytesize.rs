@@ -0,0 +1,210 @@
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, Mul, Sub, SubAssign};
+use std::str::FromStr;
+
+use super::env::{EnvError, parse_memory_size};
+
+const UNITS: [&str; 6] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB"];
+
+/// A size in bytes, parsed from strings like `512KB` or `1GB` and displayed
+/// with binary units (`1.5 GiB`).
+///
+/// Example:
+/// ```
+/// use common_utils_rs::env::ByteSize;
+/// let size: ByteSize = "1536MB".parse().unwrap();
+/// assert_eq!(size, ByteSize::mib(1536));
+/// assert_eq!(size.to_string(), "1.5 GiB");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ByteSize(pub u64);
+
+impl ByteSize {
+    pub const fn b(bytes: u64) -> Self {
+        Self(bytes)
+    }
+
+    pub const fn kib(n: u64) -> Self {
+        Self(n * 1024)
+    }
+
+    pub const fn mib(n: u64) -> Self {
+        Self(n * 1024 * 1024)
+    }
+
+    pub const fn gib(n: u64) -> Self {
+        Self(n * 1024 * 1024 * 1024)
+    }
+
+    pub const fn tib(n: u64) -> Self {
+        Self(n * 1024 * 1024 * 1024 * 1024)
+    }
+
+    pub const fn as_u64(self) -> u64 {
+        self.0
+    }
+
+    pub fn as_kib(self) -> f64 {
+        self.0 as f64 / 1024.0
+    }
+
+    pub fn as_mib(self) -> f64 {
+        self.0 as f64 / (1024.0 * 1024.0)
+    }
+
+    pub fn as_gib(self) -> f64 {
+        self.0 as f64 / (1024.0 * 1024.0 * 1024.0)
+    }
+
+    pub const fn checked_add(self, other: Self) -> Option<Self> {
+        match self.0.checked_add(other.0) {
+            Some(n) => Some(Self(n)),
+            None => None,
+        }
+    }
+
+    pub const fn checked_sub(self, other: Self) -> Option<Self> {
+        match self.0.checked_sub(other.0) {
+            Some(n) => Some(Self(n)),
+            None => None,
+        }
+    }
+}
+
+impl fmt::Display for ByteSize {
+    /// Formats with the largest binary unit that keeps the value at least 1,
+    /// one decimal place by default (`{:.2}` for more) and no trailing `.0`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut value = self.0 as f64;
+        let mut unit = 0;
+        while value >= 1024.0 && unit < UNITS.len() - 1 {
+            value /= 1024.0;
+            unit += 1;
+        }
+
+        if unit == 0 {
+            return write!(f, "{} B", self.0);
+        }
+
+        let precision = f.precision().unwrap_or(1);
+        let formatted = format!("{:.*}", precision, value);
+        let formatted = if formatted.contains('.') {
+            formatted.trim_end_matches('0').trim_end_matches('.')
+        } else {
+            &formatted
+        };
+        write!(f, "{} {}", formatted, UNITS[unit])
+    }
+}
+
+impl FromStr for ByteSize {
+    type Err = EnvError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_memory_size(s)
+    }
+}
+
+impl From<u64> for ByteSize {
+    fn from(bytes: u64) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<ByteSize> for u64 {
+    fn from(size: ByteSize) -> Self {
+        size.0
+    }
+}
+
+impl Add for ByteSize {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+impl AddAssign for ByteSize {
+    fn add_assign(&mut self, other: Self) {
+        self.0 += other.0;
+    }
+}
+
+impl Sub for ByteSize {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 - other.0)
+    }
+}
+
+impl SubAssign for ByteSize {
+    fn sub_assign(&mut self, other: Self) {
+        self.0 -= other.0;
+    }
+}
+
+impl Mul<u64> for ByteSize {
+    type Output = Self;
+
+    fn mul(self, factor: u64) -> Self {
+        Self(self.0 * factor)
+    }
+}
+
+impl Div<u64> for ByteSize {
+    type Output = Self;
+
+    fn div(self, divisor: u64) -> Self {
+        Self(self.0 / divisor)
+    }
+}
+
+impl Sum for ByteSize {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self(0), Add::add)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bytesize_display() {
+        assert_eq!(ByteSize::b(0).to_string(), "0 B");
+        assert_eq!(ByteSize::b(1023).to_string(), "1023 B");
+        assert_eq!(ByteSize::kib(1).to_string(), "1 KiB");
+        assert_eq!(ByteSize::mib(1536).to_string(), "1.5 GiB");
+        assert_eq!(ByteSize::b(1_234_567).to_string(), "1.2 MiB");
+        assert_eq!(format!("{:.2}", ByteSize::b(1_234_567)), "1.18 MiB");
+        assert_eq!(ByteSize::tib(3).to_string(), "3 TiB");
+    }
+
+    #[test]
+    fn test_bytesize_from_str() {
+        assert_eq!("512KB".parse::<ByteSize>().unwrap(), ByteSize::kib(512));
+        assert_eq!(" 2gb ".parse::<ByteSize>().unwrap(), ByteSize::gib(2));
+        assert!("lots".parse::<ByteSize>().is_err());
+    }
+
+    #[test]
+    fn test_bytesize_arithmetic_and_conversions() {
+        let mut size = ByteSize::mib(1) + ByteSize::kib(512);
+        assert_eq!(size.as_u64(), 1_572_864);
+        assert_eq!(size.as_mib(), 1.5);
+        size -= ByteSize::kib(512);
+        assert_eq!(size, ByteSize::mib(1));
+        assert_eq!(size * 4 / 2, ByteSize::mib(2));
+        size += ByteSize::b(1);
+        assert_eq!(u64::from(size), 1_048_577);
+        assert_eq!(ByteSize::from(10u64), ByteSize::b(10));
+        assert_eq!(ByteSize::b(1).checked_sub(ByteSize::b(2)), None);
+        assert_eq!(ByteSize::b(u64::MAX).checked_add(ByteSize::b(1)), None);
+        let total: ByteSize = [ByteSize::kib(1), ByteSize::kib(3)].into_iter().sum();
+        assert_eq!(total, ByteSize::kib(4));
+        assert!(ByteSize::gib(1) > ByteSize::mib(1023));
+    }
+}
@@ -0,0 +1,209 @@
+//! `clap` arguments backed by this crate's parsers (`clap` feature).
+//!
+//! [`env_arg`] lets an argument fall back to an environment variable, and
+//! [`env_parser`] parses both sources the same way as the matching getter,
+//! so `--timeout 1h30m` and `TIMEOUT=1h30m` always agree. With clap's derive
+//! API, use `#[arg(long, env = "TIMEOUT", value_parser = env_parser::<Duration>())]`;
+//! clap then reads the variable itself, so [`overrides`](super::overrides)
+//! don't apply and the read isn't recorded by
+//! [`record_access`](super::record_access).
+//!
+//! For lists, add `value_delimiter(',')`: clap splits both the command line
+//! and the environment value before each item is parsed.
+//!
+//! Example:
+//! ```
+//! use std::time::Duration;
+//! use clap::{Arg, Command};
+//! use common_utils_rs::env::{env_arg, ByteSize, test::with_vars};
+//!
+//! with_vars(&[("CLI_DOC_TIMEOUT", "1h30m")], || {
+//!     let command = Command::new("app")
+//!         .arg(env_arg::<Duration>(Arg::new("timeout").long("timeout"), "CLI_DOC_TIMEOUT"))
+//!         .arg(env_arg::<ByteSize>(Arg::new("cache").long("cache-size"), "CLI_DOC_CACHE_SIZE"));
+//!     let matches = command.get_matches_from(["app", "--cache-size", "512MB"]);
+//!     assert_eq!(matches.get_one::<Duration>("timeout"), Some(&Duration::from_secs(5400)));
+//!     assert_eq!(matches.get_one::<ByteSize>("cache"), Some(&ByteSize(512 * 1024 * 1024)));
+//! });
+//! ```
+use std::time::{Duration, SystemTime};
+
+use clap::Arg;
+
+use super::bytesize::ByteSize;
+use super::datetime::parse_datetime;
+use super::duration::parse_duration;
+use super::env::{BOOL_VALUES, EnvError, parse_bool_strict, parse_memory_size};
+use super::source::{EnvSource, ProcessEnv};
+
+/// A type read the same way from the command line as from the environment.
+pub trait EnvValue: Sized + Clone + Send + Sync + 'static {
+    fn parse_env_value(value: &str) -> Result<Self, EnvError>;
+}
+
+impl EnvValue for Duration {
+    /// As [`get_duration`](super::duration::get_duration), e.g. `30s` or `1h30m`.
+    fn parse_env_value(value: &str) -> Result<Self, EnvError> {
+        parse_duration(value)
+    }
+}
+
+impl EnvValue for ByteSize {
+    /// As [`get_memory_size`](super::env::get_memory_size), e.g. `512MB`.
+    fn parse_env_value(value: &str) -> Result<Self, EnvError> {
+        parse_memory_size(value)
+    }
+}
+
+impl EnvValue for SystemTime {
+    /// As [`get_datetime`](super::datetime::get_datetime), an RFC 3339
+    /// timestamp.
+    fn parse_env_value(value: &str) -> Result<Self, EnvError> {
+        parse_datetime(value)
+    }
+}
+
+impl EnvValue for bool {
+    /// As [`get_bool_strict`](super::env::get_bool_strict), e.g. `yes` or `off`.
+    fn parse_env_value(value: &str) -> Result<Self, EnvError> {
+        parse_bool_strict(value).ok_or_else(|| {
+            EnvError::parse_error("bool", value)
+                .with_expected("bool")
+                .with_source(format!("one of {}", BOOL_VALUES))
+        })
+    }
+}
+
+/// A clap value parser for `T`, for `Arg::value_parser` or
+/// `#[arg(value_parser = ...)]`.
+///
+/// Errors describe the expected value rather than a variable name, since the
+/// value may have come from the command line.
+pub fn env_parser<T: EnvValue>()
+-> impl Fn(&str) -> Result<T, String> + Clone + Send + Sync + 'static {
+    |value| T::parse_env_value(value).map_err(describe)
+}
+
+/// Read `arg` from `key` when it isn't given on the command line, parsing
+/// either source with [`env_parser`].
+///
+/// Unlike `Arg::env`, the variable is read through [`ProcessEnv`], so
+/// [`overrides`](super::overrides) apply and the read is recorded by
+/// [`record_access`](super::record_access). It becomes the argument's
+/// default, and `[env: KEY]` is added to the help text. The variable is read
+/// when the argument is created, not when the command line is parsed.
+pub fn env_arg<T: EnvValue>(arg: Arg, key: &'static str) -> Arg {
+    let help = match arg.get_help() {
+        Some(help) => format!("{} [env: {}]", help, key),
+        None => format!("[env: {}]", key),
+    };
+    let arg = arg.help(help).value_parser(env_parser::<T>());
+    match ProcessEnv.get(key) {
+        Some(value) => arg.default_value(value),
+        None => arg,
+    }
+}
+
+fn describe(error: EnvError) -> String {
+    match error {
+        EnvError::ParseError {
+            expected: Some(expected),
+            source: Some(source),
+            ..
+        } => format!("expected {} ({})", expected, source),
+        EnvError::ParseError {
+            expected: Some(expected),
+            ..
+        } => format!("expected {}", expected),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::overrides;
+    use crate::env::test::{ScopedEnv, without_vars};
+    use clap::Command;
+
+    fn command() -> Command {
+        Command::new("app")
+            .arg(env_arg::<Duration>(
+                Arg::new("timeout").long("timeout"),
+                "CLI_T_TIMEOUT",
+            ))
+            .arg(
+                env_arg::<Duration>(Arg::new("retries").long("retry-after"), "CLI_T_RETRIES")
+                    .value_delimiter(','),
+            )
+            .arg(env_arg::<bool>(
+                Arg::new("debug").long("debug"),
+                "CLI_T_DEBUG",
+            ))
+    }
+
+    #[test]
+    fn test_env_arg_prefers_command_line() {
+        let mut scope = ScopedEnv::new();
+        scope
+            .set("CLI_T_TIMEOUT", "5m")
+            .set("CLI_T_RETRIES", "1s, 5s,30s")
+            .set("CLI_T_DEBUG", "on");
+
+        let matches = command().get_matches_from(["app", "--timeout", "1h30m"]);
+        assert_eq!(
+            matches.get_one::<Duration>("timeout"),
+            Some(&Duration::from_secs(5400))
+        );
+        let retries: Vec<Duration> = matches
+            .get_many::<Duration>("retries")
+            .unwrap()
+            .copied()
+            .collect();
+        assert_eq!(retries, [1, 5, 30].map(Duration::from_secs).to_vec());
+        assert_eq!(matches.get_one::<bool>("debug"), Some(&true));
+    }
+
+    #[test]
+    fn test_env_arg_reads_overrides() {
+        let mut scope = ScopedEnv::new();
+        scope.set("CLI_T_OVERRIDDEN", "5m");
+        overrides().set("CLI_T_OVERRIDDEN", "10s");
+        let arg = env_arg::<Duration>(
+            Arg::new("timeout").long("timeout").help("Request timeout"),
+            "CLI_T_OVERRIDDEN",
+        );
+        overrides().restore("CLI_T_OVERRIDDEN");
+
+        assert_eq!(
+            arg.get_help().map(ToString::to_string).as_deref(),
+            Some("Request timeout [env: CLI_T_OVERRIDDEN]")
+        );
+        let matches = Command::new("app").arg(arg).get_matches_from(["app"]);
+        assert_eq!(
+            matches.get_one::<Duration>("timeout"),
+            Some(&Duration::from_secs(10))
+        );
+    }
+
+    #[test]
+    fn test_env_arg_reports_invalid_values() {
+        without_vars(&["CLI_T_TIMEOUT", "CLI_T_RETRIES", "CLI_T_DEBUG"], || {
+            let err = command()
+                .try_get_matches_from(["app", "--timeout", "soon"])
+                .unwrap_err();
+            assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+
+            let matches = command().get_matches_from(["app"]);
+            assert_eq!(matches.get_one::<Duration>("timeout"), None);
+        });
+        assert_eq!(
+            env_parser::<bool>()("maybe").unwrap_err(),
+            format!("expected bool (one of {})", BOOL_VALUES)
+        );
+        assert_eq!(
+            env_parser::<Duration>()("soon").unwrap_err(),
+            "expected duration (Invalid duration `soon`: expected a number before `soon`)"
+        );
+    }
+}
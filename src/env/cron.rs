@@ -0,0 +1,117 @@
+use crate::time;
+
+use super::env::{EnvError, get_required_from};
+use super::source::{EnvSource, ProcessEnv};
+
+const EXPECTED: &str = "cron expression";
+
+/// A parsed five-field cron schedule; see [`time::Cron`].
+pub type CronSchedule = time::Cron;
+
+/// Parse a cron expression, rejecting schedules that can never fire
+/// (e.g. `0 0 30 2 *`); see [`time::Cron::parse`].
+pub fn parse_cron(input: &str) -> Result<CronSchedule, EnvError> {
+    time::Cron::parse(input).map_err(|e| {
+        EnvError::parse_error("cron", input.trim())
+            .with_expected(EXPECTED)
+            .with_source(e.reason)
+    })
+}
+
+/// Read a required variable and parse it with [`parse_cron`].
+///
+/// Example:
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use common_utils_rs::env::{get_cron, test::with_vars};
+/// with_vars(&[("CLEANUP_SCHEDULE", "0 3 * * *")], || {
+///     let schedule = get_cron("CLEANUP_SCHEDULE").unwrap();
+///     let next = schedule.next_after(UNIX_EPOCH).unwrap();
+///     assert_eq!(next, UNIX_EPOCH + Duration::from_secs(3 * 3600));
+/// });
+/// ```
+pub fn get_cron(key: &str) -> Result<CronSchedule, EnvError> {
+    get_cron_from(&ProcessEnv, key)
+}
+
+pub(crate) fn get_cron_from(source: &impl EnvSource, key: &str) -> Result<CronSchedule, EnvError> {
+    let value = get_required_from(source, key)?;
+    parse_cron(&value).map_err(|e| match e {
+        EnvError::ParseError { source, .. } => EnvError::ParseError {
+            key: key.to_string(),
+            value,
+            expected: Some(EXPECTED.to_string()),
+            source,
+        },
+        other => other,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test::with_vars;
+    use crate::time::calendar::{days_from_civil, to_system_time};
+    use std::time::SystemTime;
+
+    /// A UTC date and time.
+    fn utc(year: i64, month: u32, day: u32, hour: i64, minute: i64) -> SystemTime {
+        let days = days_from_civil(year, month, day);
+        to_system_time(days * 86_400 + hour * 3600 + minute * 60, 0).unwrap()
+    }
+
+    #[test]
+    fn test_parse_cron_invalid() {
+        for input in [
+            "",
+            "* * * *",
+            "* * * * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * * 13 *",
+            "* * * * 8",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "* * * FOO *",
+            "@fortnightly",
+            "0 0 30 2 *",
+        ] {
+            assert!(
+                matches!(
+                    parse_cron(input),
+                    Err(EnvError::ParseError {
+                        source: Some(_),
+                        ..
+                    })
+                ),
+                "{:?} should not parse",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_get_cron() {
+        with_vars(
+            &[
+                ("CRON_CLEANUP", " 0 3 * * SUN "),
+                ("CRON_BAD", "0 25 * * *"),
+            ],
+            || {
+                let schedule = get_cron("CRON_CLEANUP").unwrap();
+                assert_eq!(schedule.to_string(), "0 3 * * SUN");
+                assert_eq!(
+                    schedule.next_after(utc(2024, 5, 1, 0, 0)),
+                    Some(utc(2024, 5, 5, 3, 0))
+                );
+                let err = get_cron("CRON_BAD").unwrap_err();
+                assert_eq!(
+                    err.to_string(),
+                    "Failed to parse environment variable `CRON_BAD` as cron expression: \
+                     0 25 * * * (hour `25` is not between 0 and 23)"
+                );
+            },
+        );
+    }
+}
@@ -0,0 +1,199 @@
+use std::time::SystemTime;
+
+use crate::time;
+use crate::time::calendar::to_system_time;
+
+use super::env::{EnvError, get_required_from};
+use super::source::{EnvSource, ProcessEnv};
+
+const EXPECTED: &str = "RFC 3339 timestamp";
+
+/// Parse an RFC 3339 timestamp such as `2024-05-01T12:30:00Z`, or a bare
+/// date as midnight UTC; see [`time::from_rfc3339`].
+pub fn parse_datetime(input: &str) -> Result<SystemTime, EnvError> {
+    time::from_rfc3339(input).map_err(|e| {
+        EnvError::parse_error("datetime", input.trim())
+            .with_expected(EXPECTED)
+            .with_source(e.reason)
+    })
+}
+
+/// Read a required variable and parse it with [`parse_datetime`].
+///
+/// Example:
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use common_utils_rs::env::{get_datetime, test::with_vars};
+/// with_vars(&[("MAINTENANCE_START", "2024-05-01T02:00:00+02:00")], || {
+///     let start = get_datetime("MAINTENANCE_START").unwrap();
+///     assert_eq!(start, UNIX_EPOCH + Duration::from_secs(1_714_521_600));
+/// });
+/// ```
+pub fn get_datetime(key: &str) -> Result<SystemTime, EnvError> {
+    get_datetime_from(&ProcessEnv, key)
+}
+
+/// Read a required variable holding whole seconds since the Unix epoch,
+/// e.g. `1714521600`. Negative values are before 1970.
+pub fn get_unix_timestamp(key: &str) -> Result<SystemTime, EnvError> {
+    get_unix_timestamp_from(&ProcessEnv, key)
+}
+
+/// Read a required variable as a `chrono` timestamp, keeping its offset
+/// (`chrono` feature).
+#[cfg(feature = "chrono")]
+pub fn get_chrono_datetime(key: &str) -> Result<chrono::DateTime<chrono::FixedOffset>, EnvError> {
+    get_chrono_datetime_from(&ProcessEnv, key)
+}
+
+pub(crate) fn get_datetime_from(
+    source: &impl EnvSource,
+    key: &str,
+) -> Result<SystemTime, EnvError> {
+    let value = get_required_from(source, key)?;
+    parse_datetime(&value).map_err(|e| match e {
+        EnvError::ParseError { source, .. } => EnvError::ParseError {
+            key: key.to_string(),
+            value,
+            expected: Some(EXPECTED.to_string()),
+            source,
+        },
+        other => other,
+    })
+}
+
+pub(crate) fn get_unix_timestamp_from(
+    source: &impl EnvSource,
+    key: &str,
+) -> Result<SystemTime, EnvError> {
+    let value = get_required_from(source, key)?;
+    let parse_error = || EnvError::parse_error(key, value.as_str()).with_expected("Unix timestamp");
+    let seconds: i64 = value
+        .trim()
+        .parse()
+        .map_err(|e| parse_error().with_source(e))?;
+    to_system_time(seconds, 0).ok_or_else(|| parse_error().with_source("timestamp is out of range"))
+}
+
+#[cfg(feature = "chrono")]
+pub(crate) fn get_chrono_datetime_from(
+    source: &impl EnvSource,
+    key: &str,
+) -> Result<chrono::DateTime<chrono::FixedOffset>, EnvError> {
+    let value = get_required_from(source, key)?;
+    chrono::DateTime::parse_from_rfc3339(value.trim()).map_err(|e| {
+        EnvError::parse_error(key, value.as_str())
+            .with_expected(EXPECTED)
+            .with_source(e)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test::with_vars;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn at(seconds: u64, nanos: u32) -> SystemTime {
+        UNIX_EPOCH + Duration::new(seconds, nanos)
+    }
+
+    #[test]
+    fn test_parse_datetime() {
+        assert_eq!(parse_datetime("1970-01-01T00:00:00Z").unwrap(), UNIX_EPOCH);
+        assert_eq!(
+            parse_datetime("2024-05-01T00:00:00Z").unwrap(),
+            at(1_714_521_600, 0)
+        );
+        assert_eq!(
+            parse_datetime(" 2024-05-01t02:00:00.25+02:00 ").unwrap(),
+            at(1_714_521_600, 250_000_000)
+        );
+        assert_eq!(
+            parse_datetime("2024-04-30 19:00:00.1234567891-05:00").unwrap(),
+            at(1_714_521_600, 123_456_789)
+        );
+        assert_eq!(parse_datetime("2024-05-01").unwrap(), at(1_714_521_600, 0));
+        assert_eq!(
+            parse_datetime("2024-02-29T00:00:00Z").unwrap(),
+            at(1_709_164_800, 0)
+        );
+        assert_eq!(
+            parse_datetime("1969-12-31T23:59:59Z").unwrap(),
+            UNIX_EPOCH - Duration::from_secs(1)
+        );
+    }
+
+    #[test]
+    fn test_parse_datetime_invalid() {
+        for input in [
+            "",
+            "2024-05-01T00:00:00",
+            "2024-13-01T00:00:00Z",
+            "2023-02-29T00:00:00Z",
+            "2024-05-01T24:00:00Z",
+            "2024-05-01T00:00Z",
+            "2024-05-01T00:00:00.Z",
+            "2024-05-01T00:00:00+0200",
+            "2024-05-01T00:00:00Z trailing",
+            "yesterday",
+        ] {
+            assert!(
+                matches!(
+                    parse_datetime(input),
+                    Err(EnvError::ParseError { ref expected, source: Some(_), .. })
+                        if expected.as_deref() == Some(EXPECTED)
+                ),
+                "{:?} should not parse",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_get_datetime_and_unix_timestamp() {
+        with_vars(
+            &[
+                ("DATETIME_EXPIRES", "2024-05-01T00:00:00Z"),
+                ("DATETIME_BAD", "2024-05-01T00:00:00"),
+                ("DATETIME_UNIX", " 1714521600 "),
+                ("DATETIME_UNIX_NEGATIVE", "-86400"),
+                ("DATETIME_UNIX_BAD", "1714521600.5"),
+            ],
+            || {
+                assert_eq!(
+                    get_datetime("DATETIME_EXPIRES").unwrap(),
+                    at(1_714_521_600, 0)
+                );
+                let err = get_datetime("DATETIME_BAD").unwrap_err();
+                assert_eq!(
+                    err.to_string(),
+                    "Failed to parse environment variable `DATETIME_BAD` as RFC 3339 timestamp: \
+                     2024-05-01T00:00:00 (missing `Z` or a UTC offset)"
+                );
+                assert_eq!(
+                    get_unix_timestamp("DATETIME_UNIX").unwrap(),
+                    at(1_714_521_600, 0)
+                );
+                assert_eq!(
+                    get_unix_timestamp("DATETIME_UNIX_NEGATIVE").unwrap(),
+                    UNIX_EPOCH - Duration::from_secs(86_400)
+                );
+                assert!(matches!(
+                    get_unix_timestamp("DATETIME_UNIX_BAD"),
+                    Err(EnvError::ParseError { .. })
+                ));
+            },
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_get_chrono_datetime() {
+        with_vars(&[("DATETIME_CHRONO", "2024-05-01T02:00:00+02:00")], || {
+            let value = get_chrono_datetime("DATETIME_CHRONO").unwrap();
+            assert_eq!(value.timestamp(), 1_714_521_600);
+            assert_eq!(value.offset().local_minus_utc(), 7200);
+        });
+    }
+}
@@ -0,0 +1,1025 @@
+//! Deserialize configuration structs from environment variables via serde.
+//!
+//! Each struct field maps to one variable. The variable name is the field
+//! name (after any `#[serde(rename)]`) converted with [`KeyCase`] and prefixed
+//! with [`FromEnvOptions::prefix`]. All missing and malformed variables are
+//! reported together in a single [`EnvError::Multiple`]. Errors about a
+//! struct as a whole, such as a failed `#[serde(try_from)]`, are reported as
+//! [`EnvError::Invalid`] against the struct's prefix.
+//!
+//! Example:
+//! ```no_run
+//! use common_utils_rs::env::{from_env_with, FromEnvOptions};
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Config {
+//!     host: String,
+//!     port: u16,
+//!     debug: bool,
+//! }
+//!
+//! // Reads APP_HOST, APP_PORT and APP_DEBUG.
+//! let config: Config = from_env_with(&FromEnvOptions::new().prefix("APP_")).unwrap();
+//! ```
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::de::value::StrDeserializer;
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, Visitor};
+use serde::forward_to_deserialize_any;
+
+use super::env::{EnvError, type_name};
+use super::nested::{EnvTree, NESTED_SEPARATOR, get_nested};
+use super::source::{EnvSource, ProcessEnv};
+
+/// How struct field names are converted into variable names.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KeyCase {
+    /// `db_url` -> `DB_URL`
+    #[default]
+    Upper,
+    /// `DbUrl` -> `dburl`
+    Lower,
+    /// Use the field name unchanged.
+    Preserve,
+}
+
+impl KeyCase {
+    fn apply(self, name: &str) -> String {
+        match self {
+            KeyCase::Upper => name.to_uppercase(),
+            KeyCase::Lower => name.to_lowercase(),
+            KeyCase::Preserve => name.to_string(),
+        }
+    }
+}
+
+/// Options for [`from_env_with`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FromEnvOptions {
+    prefix: String,
+    case: KeyCase,
+}
+
+impl FromEnvOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    pub fn case(mut self, case: KeyCase) -> Self {
+        self.case = case;
+        self
+    }
+
+    /// Variable name for a struct field.
+    pub fn key_for(&self, field: &str) -> String {
+        format!("{}{}", self.prefix, self.case.apply(field))
+    }
+}
+
+/// Deserialize `T` from the process environment using upper-cased field names.
+pub fn from_env<T>() -> Result<T, EnvError>
+where
+    T: DeserializeOwned,
+{
+    from_env_with(&FromEnvOptions::new())
+}
+
+/// Deserialize `T` from the process environment.
+pub fn from_env_with<T>(options: &FromEnvOptions) -> Result<T, EnvError>
+where
+    T: DeserializeOwned,
+{
+    from_source(&ProcessEnv, options)
+}
+
+/// Deserialize `T` from an in-memory map instead of the process environment.
+pub fn from_map<T>(map: &HashMap<String, String>, options: &FromEnvOptions) -> Result<T, EnvError>
+where
+    T: DeserializeOwned,
+{
+    from_source(map, options)
+}
+
+/// Deserialize `T` from any [`EnvSource`].
+pub fn from_source<T, S>(source: &S, options: &FromEnvOptions) -> Result<T, EnvError>
+where
+    T: DeserializeOwned,
+    S: EnvSource + ?Sized,
+{
+    from_lookup(options, |key| source.get(key))
+}
+
+/// Deserialize `T` from the `prefix__...` variables of the process
+/// environment, mapping nested structs to `PREFIX__SECTION__FIELD`.
+///
+/// Example:
+/// ```no_run
+/// use common_utils_rs::env::from_nested;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Database {
+///     host: String,
+///     port: u16,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Config {
+///     database: Database,
+/// }
+///
+/// // Reads APP__DATABASE__HOST and APP__DATABASE__PORT.
+/// let config: Config = from_nested("APP").unwrap();
+/// ```
+pub fn from_nested<T>(prefix: &str) -> Result<T, EnvError>
+where
+    T: DeserializeOwned,
+{
+    from_tree(&get_nested(prefix))
+}
+
+/// Deserialize `T` from an [`EnvTree`].
+///
+/// Unlike [`from_env_with`], deserialization stops at the first problem.
+/// Sequences may be written either as a comma-separated value or as
+/// numbered children (`APP__HOSTS__0`, `APP__HOSTS__1`).
+pub fn from_tree<T>(tree: &EnvTree) -> Result<T, EnvError>
+where
+    T: DeserializeOwned,
+{
+    T::deserialize(TreeDeserializer { tree }).map_err(|e| e.into_env_error(tree.key()))
+}
+
+fn from_lookup<T, F>(options: &FromEnvOptions, lookup: F) -> Result<T, EnvError>
+where
+    T: DeserializeOwned,
+    F: Fn(&str) -> Option<String>,
+{
+    // serde's derived visitors stop at the first bad field. To report every
+    // problem at once, each failing field is recorded and replaced with a
+    // placeholder value on the next attempt until deserialization succeeds.
+    let mut failed: Vec<(&'static str, EnvError)> = Vec::new();
+
+    loop {
+        let deserializer = StructDeserializer {
+            options,
+            lookup: &lookup,
+            failed: &failed,
+        };
+        let error = match T::deserialize(deserializer) {
+            Ok(value) if failed.is_empty() => return Ok(value),
+            Ok(_) => break,
+            Err(error) => error,
+        };
+
+        match error {
+            DeError::Field { field, error } if !failed.iter().any(|(f, _)| *f == field) => {
+                failed.push((field, error));
+            }
+            DeError::MissingField(field) if !failed.iter().any(|(f, _)| *f == field) => {
+                failed.push((field, EnvError::Missing(options.key_for(field))));
+            }
+            other => {
+                failed.push(("", other.into_env_error(&options.prefix)));
+                break;
+            }
+        }
+    }
+
+    EnvError::aggregate(failed.into_iter().map(|(_, e)| e).collect())?;
+    unreachable!("deserialization only stops early after recording an error")
+}
+
+#[derive(Debug)]
+enum DeError {
+    Field {
+        field: &'static str,
+        error: EnvError,
+    },
+    MissingField(&'static str),
+    Custom(String),
+}
+
+impl DeError {
+    /// Convert into an [`EnvError`], reporting errors that aren't tied to a
+    /// variable against `key`.
+    fn into_env_error(self, key: &str) -> EnvError {
+        match self {
+            DeError::Field { error, .. } => error,
+            DeError::MissingField(field) => EnvError::Missing(field.to_string()),
+            DeError::Custom(reason) => EnvError::Invalid {
+                key: key.to_string(),
+                reason,
+            },
+        }
+    }
+}
+
+impl fmt::Display for DeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeError::Field { error, .. } => write!(f, "{}", error),
+            DeError::MissingField(field) => write!(f, "missing field `{}`", field),
+            DeError::Custom(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for DeError {}
+
+impl de::Error for DeError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        DeError::Custom(msg.to_string())
+    }
+
+    fn missing_field(field: &'static str) -> Self {
+        DeError::MissingField(field)
+    }
+}
+
+struct StructDeserializer<'a, F> {
+    options: &'a FromEnvOptions,
+    lookup: &'a F,
+    failed: &'a [(&'static str, EnvError)],
+}
+
+impl<'de, F> de::Deserializer<'de> for StructDeserializer<'_, F>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, DeError> {
+        Err(DeError::Custom(
+            "only structs can be deserialized from the environment".to_string(),
+        ))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        visitor.visit_map(FieldAccess {
+            options: self.options,
+            lookup: self.lookup,
+            failed: self.failed,
+            fields: fields.iter(),
+            current: None,
+        })
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+enum FieldValue {
+    Present { key: String, value: String },
+    Placeholder,
+}
+
+struct FieldAccess<'a, F> {
+    options: &'a FromEnvOptions,
+    lookup: &'a F,
+    failed: &'a [(&'static str, EnvError)],
+    fields: std::slice::Iter<'static, &'static str>,
+    current: Option<(&'static str, FieldValue)>,
+}
+
+impl<'de, F> de::MapAccess<'de> for FieldAccess<'_, F>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    type Error = DeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, DeError> {
+        for &field in self.fields.by_ref() {
+            let value = if self.failed.iter().any(|(f, _)| *f == field) {
+                FieldValue::Placeholder
+            } else {
+                let key = self.options.key_for(field);
+                match (self.lookup)(&key) {
+                    Some(value) => FieldValue::Present { key, value },
+                    None => continue,
+                }
+            };
+            self.current = Some((field, value));
+            let name: StrDeserializer<'_, DeError> = field.into_deserializer();
+            return seed.deserialize(name).map(Some);
+        }
+        Ok(None)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, DeError> {
+        let (field, value) = self
+            .current
+            .take()
+            .ok_or_else(|| DeError::Custom("value requested before key".to_string()))?;
+
+        match value {
+            FieldValue::Present { key, value } => seed
+                .deserialize(ValueDeserializer { value: &value })
+                .map_err(|e| DeError::Field {
+                    field,
+                    error: EnvError::parse_error(key.as_str(), value.as_str()).with_source(e),
+                }),
+            FieldValue::Placeholder => seed.deserialize(Placeholder),
+        }
+    }
+}
+
+/// Deserializes a single variable value.
+struct ValueDeserializer<'a> {
+    value: &'a str,
+}
+
+impl ValueDeserializer<'_> {
+    fn parse<T: std::str::FromStr>(&self) -> Result<T, DeError> {
+        self.value
+            .trim()
+            .parse()
+            .map_err(|_| DeError::Custom(format!("invalid {} `{}`", type_name::<T>(), self.value)))
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+                visitor.$visit(self.parse()?)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer<'_> {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_str(self.value)
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        match self.value.trim().to_lowercase().as_str() {
+            "true" | "1" | "yes" | "on" => visitor.visit_bool(true),
+            "false" | "0" | "no" | "off" => visitor.visit_bool(false),
+            _ => Err(DeError::Custom(format!("invalid bool `{}`", self.value))),
+        }
+    }
+
+    deserialize_parsed! {
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        let items = self
+            .value
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|value| ValueDeserializer { value });
+        visitor.visit_seq(de::value::SeqDeserializer::new(items))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        let variant: StrDeserializer<'_, DeError> = self.value.trim().into_deserializer();
+        visitor.visit_enum(variant)
+    }
+
+    forward_to_deserialize_any! {
+        str string bytes byte_buf unit_struct tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, DeError> for ValueDeserializer<'_> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+/// Stands in for a field that already failed so the remaining fields can
+/// still be checked.
+struct Placeholder;
+
+impl<'de> de::Deserializer<'de> for Placeholder {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_bool(false)
+    }
+
+    fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_i64(0)
+    }
+
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_u64(0)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_f64(0.0)
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_char('\0')
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_str("")
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_none()
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_seq(de::value::SeqDeserializer::new(std::iter::empty::<
+            Placeholder,
+        >()))
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        let variant = variants.first().copied().unwrap_or_default();
+        let variant: StrDeserializer<'_, DeError> = variant.into_deserializer();
+        visitor.visit_enum(variant)
+    }
+
+    fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.deserialize_i64(visitor)
+    }
+
+    fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.deserialize_i64(visitor)
+    }
+
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.deserialize_i64(visitor)
+    }
+
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.deserialize_u64(visitor)
+    }
+
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.deserialize_u64(visitor)
+    }
+
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.deserialize_u64(visitor)
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.deserialize_f64(visitor)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.deserialize_str(visitor)
+    }
+
+    forward_to_deserialize_any! {
+        i128 u128 bytes byte_buf unit unit_struct tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, DeError> for Placeholder {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+/// Deserializes one node of an [`EnvTree`]; leaves go through
+/// [`ValueDeserializer`].
+#[derive(Clone, Copy)]
+struct TreeDeserializer<'a> {
+    tree: &'a EnvTree,
+}
+
+impl<'a> TreeDeserializer<'a> {
+    fn leaf(self) -> Result<&'a str, DeError> {
+        self.tree
+            .value()
+            .ok_or_else(|| self.error(EnvError::Missing(self.tree.key().to_string())))
+    }
+
+    fn error(self, error: EnvError) -> DeError {
+        DeError::Field { field: "", error }
+    }
+
+    fn parse_error(self, value: &str, source: DeError) -> DeError {
+        self.error(EnvError::parse_error(self.tree.key(), value).with_source(source))
+    }
+
+    fn children(self) -> impl Iterator<Item = (&'a str, TreeDeserializer<'a>)> {
+        self.tree
+            .children()
+            .map(|(name, tree)| (name, TreeDeserializer { tree }))
+    }
+}
+
+macro_rules! deserialize_leaf {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+                let value = self.leaf()?;
+                ValueDeserializer { value }
+                    .$method(visitor)
+                    .map_err(|e| self.parse_error(value, e))
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for TreeDeserializer<'_> {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        match self.tree.value() {
+            Some(value) if self.tree.children().next().is_none() => visitor.visit_str(value),
+            _ => self.deserialize_map(visitor),
+        }
+    }
+
+    deserialize_leaf! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_i128 deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_u128 deserialize_f32 deserialize_f64 deserialize_char deserialize_str
+        deserialize_string
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        if self.tree.is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        if let Some(value) = self.tree.value() {
+            return ValueDeserializer { value }
+                .deserialize_seq(visitor)
+                .map_err(|e| self.parse_error(value, e));
+        }
+
+        let mut items = Vec::new();
+        for (name, item) in self.children() {
+            let index = name.parse::<usize>().map_err(|_| {
+                self.error(EnvError::Invalid {
+                    key: item.tree.key().to_string(),
+                    reason: "expected a numeric index".to_string(),
+                })
+            })?;
+            items.push((index, item));
+        }
+        items.sort_by_key(|(index, _)| *index);
+        visitor.visit_seq(de::value::SeqDeserializer::new(
+            items.into_iter().map(|(_, item)| item),
+        ))
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_map(TreeMapAccess {
+            children: self.children(),
+            current: None,
+        })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        self.deserialize_map(visitor).map_err(|error| match error {
+            DeError::MissingField(field) => self.error(EnvError::Missing(format!(
+                "{}{}{}",
+                self.tree.key(),
+                NESTED_SEPARATOR,
+                field.to_uppercase()
+            ))),
+            DeError::Custom(reason) => self.error(EnvError::Invalid {
+                key: self.tree.key().to_string(),
+                reason,
+            }),
+            other => other,
+        })
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        let value = self.leaf()?;
+        ValueDeserializer { value }
+            .deserialize_enum(name, variants, visitor)
+            .map_err(|e| self.parse_error(value, e))
+    }
+
+    forward_to_deserialize_any! {
+        bytes byte_buf unit unit_struct tuple tuple_struct identifier ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, DeError> for TreeDeserializer<'_> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+/// The children of an [`EnvTree`] node as map entries. Errors a child's
+/// `Deserialize` impl raises itself, e.g. from `#[serde(try_from)]`, are
+/// reported against the child's key.
+struct TreeMapAccess<'a, I> {
+    children: I,
+    current: Option<TreeDeserializer<'a>>,
+}
+
+impl<'de, 'a, I> de::MapAccess<'de> for TreeMapAccess<'a, I>
+where
+    I: Iterator<Item = (&'a str, TreeDeserializer<'a>)>,
+{
+    type Error = DeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, DeError> {
+        let Some((name, child)) = self.children.next() else {
+            return Ok(None);
+        };
+        self.current = Some(child);
+        let name: StrDeserializer<'_, DeError> = name.into_deserializer();
+        seed.deserialize(name).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, DeError> {
+        let child = self
+            .current
+            .take()
+            .ok_or_else(|| DeError::Custom("value requested before key".to_string()))?;
+        seed.deserialize(child).map_err(|error| match error {
+            DeError::Custom(reason) => child.error(EnvError::Invalid {
+                key: child.tree.key().to_string(),
+                reason,
+            }),
+            other => other,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test::fixtures::map;
+    use crate::env::test::with_vars;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Config {
+        host: String,
+        port: u16,
+        debug: bool,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "lowercase")]
+    enum Mode {
+        Debug,
+        Release,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Extended {
+        mode: Mode,
+        tags: Vec<String>,
+        limit: Option<u32>,
+        #[serde(default)]
+        workers: usize,
+        #[serde(rename = "db_url")]
+        database: String,
+    }
+
+    #[test]
+    fn test_from_map_with_prefix() {
+        let vars = map(&[
+            ("APP_HOST", "localhost"),
+            ("APP_PORT", "8080"),
+            ("APP_DEBUG", "yes"),
+        ]);
+        let options = FromEnvOptions::new().prefix("APP_");
+        let config: Config = from_map(&vars, &options).unwrap();
+        assert_eq!(
+            config,
+            Config {
+                host: "localhost".to_string(),
+                port: 8080,
+                debug: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_from_source_layered() {
+        let source =
+            map(&[("PORT", "9000")]).or(map(&[("HOST", "base"), ("PORT", "1"), ("DEBUG", "off")]));
+        let config: Config = from_source(&source, &FromEnvOptions::new()).unwrap();
+        assert_eq!(config.host, "base");
+        assert_eq!(config.port, 9000);
+        assert!(!config.debug);
+    }
+
+    #[test]
+    fn test_from_map_case_mapping() {
+        let vars = map(&[("host", "h"), ("port", "1"), ("debug", "false")]);
+        let options = FromEnvOptions::new().case(KeyCase::Lower);
+        let config: Config = from_map(&vars, &options).unwrap();
+        assert_eq!(config.host, "h");
+        assert_eq!(
+            FromEnvOptions::new().prefix("X_").key_for("db_url"),
+            "X_DB_URL"
+        );
+    }
+
+    #[test]
+    fn test_from_map_enums_lists_options_and_defaults() {
+        let vars = map(&[
+            ("MODE", "release"),
+            ("TAGS", "a, b,c"),
+            ("DB_URL", "postgres://db"),
+        ]);
+        let config: Extended = from_map(&vars, &FromEnvOptions::new()).unwrap();
+        assert_eq!(config.mode, Mode::Release);
+        assert_eq!(config.tags, vec!["a", "b", "c"]);
+        assert_eq!(config.limit, None);
+        assert_eq!(config.workers, 0);
+        assert_eq!(config.database, "postgres://db");
+    }
+
+    #[test]
+    fn test_from_map_single_error() {
+        let vars = map(&[("HOST", "h"), ("PORT", "http"), ("DEBUG", "true")]);
+        let result: Result<Config, _> = from_map(&vars, &FromEnvOptions::new());
+        match result {
+            Err(EnvError::ParseError { key, value, .. }) => {
+                assert_eq!(key, "PORT");
+                assert_eq!(value, "http");
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_from_map_aggregates_errors() {
+        let vars = map(&[("APP_PORT", "99999"), ("APP_DEBUG", "maybe")]);
+        let options = FromEnvOptions::new().prefix("APP_");
+        let result: Result<Config, _> = from_map(&vars, &options);
+        let errors = match result {
+            Err(EnvError::Multiple(errors)) => errors,
+            other => panic!("unexpected result: {:?}", other),
+        };
+        assert_eq!(errors.len(), 3);
+        assert!(
+            errors
+                .iter()
+                .any(|e| matches!(e, EnvError::Missing(k) if k == "APP_HOST"))
+        );
+        assert!(
+            errors
+                .iter()
+                .any(|e| matches!(e, EnvError::ParseError { key, .. } if key == "APP_PORT"))
+        );
+        assert!(
+            errors
+                .iter()
+                .any(|e| matches!(e, EnvError::ParseError { key, .. } if key == "APP_DEBUG"))
+        );
+    }
+
+    #[test]
+    fn test_from_env_reads_process_environment() {
+        let vars = [
+            ("DE_TEST_HOST", "example.org"),
+            ("DE_TEST_PORT", "443"),
+            ("DE_TEST_DEBUG", "off"),
+        ];
+        with_vars(&vars, || {
+            let config: Config = from_env_with(&FromEnvOptions::new().prefix("DE_TEST_")).unwrap();
+            assert_eq!(config.port, 443);
+            assert!(!config.debug);
+        });
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Database {
+        host: String,
+        port: u16,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Nested {
+        name: String,
+        database: Database,
+        replicas: Vec<String>,
+        timeout: Option<u64>,
+    }
+
+    fn tree(pairs: &[(&str, &str)]) -> EnvTree {
+        EnvTree::from_vars("APP", pairs.iter().copied())
+    }
+
+    #[test]
+    fn test_from_tree_nested_structs() {
+        let tree = tree(&[
+            ("APP__NAME", "svc"),
+            ("APP__DATABASE__HOST", "db"),
+            ("APP__DATABASE__PORT", "5432"),
+            ("APP__REPLICAS__1", "b"),
+            ("APP__REPLICAS__0", "a"),
+        ]);
+        let config: Nested = from_tree(&tree).unwrap();
+        assert_eq!(
+            config,
+            Nested {
+                name: "svc".to_string(),
+                database: Database {
+                    host: "db".to_string(),
+                    port: 5432,
+                },
+                replicas: vec!["a".to_string(), "b".to_string()],
+                timeout: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_from_tree_reports_full_key() {
+        let base = [
+            ("APP__NAME", "svc"),
+            ("APP__REPLICAS", "a, b"),
+            ("APP__DATABASE__HOST", "db"),
+        ];
+        match from_tree::<Nested>(&tree(&base)) {
+            Err(EnvError::Missing(key)) => assert_eq!(key, "APP__DATABASE__PORT"),
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let mut bad = base.to_vec();
+        bad.push(("APP__DATABASE__PORT", "http"));
+        match from_tree::<Nested>(&tree(&bad)) {
+            Err(EnvError::ParseError { key, value, .. }) => {
+                assert_eq!(key, "APP__DATABASE__PORT");
+                assert_eq!(value, "http");
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct RawBounds {
+        low: u32,
+        high: u32,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(try_from = "RawBounds")]
+    struct Bounds {
+        #[allow(dead_code)]
+        low: u32,
+    }
+
+    impl TryFrom<RawBounds> for Bounds {
+        type Error = String;
+
+        fn try_from(raw: RawBounds) -> Result<Self, Self::Error> {
+            if raw.low > raw.high {
+                return Err("low must not exceed high".to_string());
+            }
+            Ok(Bounds { low: raw.low })
+        }
+    }
+
+    #[test]
+    fn test_struct_errors_name_the_prefix() {
+        let vars = map(&[("LIMITS_LOW", "5"), ("LIMITS_HIGH", "1")]);
+        let options = FromEnvOptions::new().prefix("LIMITS_");
+        match from_map::<Bounds>(&vars, &options) {
+            Err(EnvError::Invalid { key, reason }) => {
+                assert_eq!(key, "LIMITS_");
+                assert_eq!(reason, "low must not exceed high");
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        #[derive(Debug, Deserialize)]
+        struct Config {
+            #[allow(dead_code)]
+            limits: Bounds,
+        }
+        let tree = tree(&[("APP__LIMITS__LOW", "5"), ("APP__LIMITS__HIGH", "1")]);
+        match from_tree::<Config>(&tree) {
+            Err(EnvError::Invalid { key, reason }) => {
+                assert_eq!(key, "APP__LIMITS");
+                assert_eq!(reason, "low must not exceed high");
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_from_nested_reads_process_env() {
+        let vars = [
+            ("DE_NESTED__DATABASE__HOST", "h"),
+            ("DE_NESTED__DATABASE__PORT", "1"),
+        ];
+        with_vars(&vars, || {
+            #[derive(Deserialize)]
+            struct Config {
+                database: Database,
+            }
+            let config: Config = from_nested("DE_NESTED").unwrap();
+            assert_eq!(config.database.host, "h");
+        });
+    }
+}
@@ -0,0 +1,114 @@
+//! Support code for `#[derive(FromEnv)]`.
+//!
+//! Example:
+//! ```no_run
+//! use common_utils_rs::env::FromEnv;
+//!
+//! #[derive(FromEnv)]
+//! #[env(prefix = "APP_")]
+//! struct Config {
+//!     #[env(name = "DB_URL")]
+//!     database_url: String,
+//!     #[env(default = 8080)]
+//!     port: u16,
+//!     log_file: Option<String>,
+//! }
+//!
+//! let config = Config::from_env().unwrap();
+//! ```
+pub use common_utils_rs_derive::FromEnv;
+
+#[doc(hidden)]
+pub mod __private {
+    use std::str::FromStr;
+
+    use crate::env::env::{BoxError, parse_value};
+    use crate::env::{EnvError, EnvSource, ProcessEnv};
+
+    /// Read and parse one field, falling back to `default` when unset.
+    /// Returns `Ok(None)` when the variable is unset and there is no default.
+    pub fn read_field<T>(key: &str, default: Option<&str>) -> Result<Option<T>, EnvError>
+    where
+        T: FromStr,
+        T::Err: Into<BoxError>,
+    {
+        let value = match (ProcessEnv.get_os(key), default) {
+            (Some(value), _) => value
+                .into_string()
+                .map_err(|_| EnvError::NotUnicode(key.to_string()))?,
+            (None, Some(default)) => default.to_string(),
+            (None, None) => return Ok(None),
+        };
+        parse_value(key, value).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::EnvError;
+    use crate::env::test::ScopedEnv;
+
+    #[derive(Debug, FromEnv)]
+    #[env(prefix = "DERIVE_A_")]
+    struct Config {
+        host: String,
+        #[env(default = 8080)]
+        port: u16,
+        #[env(name = "DB")]
+        database_url: String,
+        workers: Option<usize>,
+        #[env(default = "info")]
+        log_level: String,
+    }
+
+    #[derive(Debug, FromEnv)]
+    #[allow(dead_code)]
+    struct Required {
+        #[env(name = "DERIVE_B_TOKEN", required)]
+        token: Option<String>,
+        #[env(name = "DERIVE_B_RETRIES")]
+        retries: u8,
+    }
+
+    #[test]
+    fn test_derive_reads_fields_and_defaults() {
+        let mut scope = ScopedEnv::new();
+        scope
+            .set("DERIVE_A_HOST", "localhost")
+            .set("DERIVE_A_DB", "postgres://db")
+            .remove("DERIVE_A_PORT")
+            .remove("DERIVE_A_WORKERS")
+            .remove("DERIVE_A_LOG_LEVEL");
+        let config = Config::from_env().unwrap();
+        assert_eq!(config.host, "localhost");
+        assert_eq!(config.port, 8080);
+        assert_eq!(config.database_url, "postgres://db");
+        assert_eq!(config.workers, None);
+        assert_eq!(config.log_level, "info");
+
+        scope
+            .set("DERIVE_A_PORT", "9000")
+            .set("DERIVE_A_WORKERS", "4");
+        let config = Config::from_env().unwrap();
+        assert_eq!(config.port, 9000);
+        assert_eq!(config.workers, Some(4));
+    }
+
+    #[test]
+    fn test_derive_aggregates_errors() {
+        let mut scope = ScopedEnv::new();
+        scope
+            .remove("DERIVE_B_TOKEN")
+            .set("DERIVE_B_RETRIES", "many");
+        let errors = match Required::from_env() {
+            Err(EnvError::Multiple(errors)) => errors,
+            other => panic!("unexpected result: {:?}", other),
+        };
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(&errors[0], EnvError::Missing(k) if k == "DERIVE_B_TOKEN"));
+        assert!(
+            matches!(&errors[1], EnvError::ParseError { key, .. } if key == "DERIVE_B_RETRIES")
+        );
+    }
+}
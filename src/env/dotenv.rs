@@ -0,0 +1,399 @@
+//! Loader for `.env` files.
+//!
+//! Supports `#` comments (whole-line or after a value), blank lines, an
+//! optional `export` keyword, and single- or double-quoted values, which may
+//! contain escapes (double quotes only) and span several lines.
+//!
+//! Example:
+//! ```no_run
+//! use common_utils_rs::env::dotenv;
+//! dotenv::load().expect("failed to load .env");
+//! ```
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::env::EnvError;
+use super::overlay;
+
+/// Options for loading a dotenv file.
+///
+/// By default the file is `.env` in the current directory and variables that
+/// are already set in the process environment, or in
+/// [`overrides`](super::overrides), are left untouched.
+#[derive(Debug, Clone)]
+pub struct Dotenv {
+    path: PathBuf,
+    override_existing: bool,
+}
+
+impl Default for Dotenv {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from(".env"),
+            override_existing: false,
+        }
+    }
+}
+
+impl Dotenv {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn path(mut self, path: impl AsRef<Path>) -> Self {
+        self.path = path.as_ref().to_path_buf();
+        self
+    }
+
+    /// Replace variables that are already set in the process environment.
+    pub fn override_existing(mut self, yes: bool) -> Self {
+        self.override_existing = yes;
+        self
+    }
+
+    /// Parse the file into an in-memory map without touching the environment.
+    /// When a key appears more than once, the last value wins.
+    pub fn read(&self) -> Result<HashMap<String, String>, EnvError> {
+        Ok(parse(&read_file(&self.path)?)?.into_iter().collect())
+    }
+
+    /// Parse the file and set its variables in the process environment.
+    /// As with [`read`](Self::read), the last value of a repeated key wins.
+    pub fn load(&self) -> Result<(), EnvError> {
+        for (key, value) in self.read()? {
+            if !self.override_existing && is_set(&key) {
+                continue;
+            }
+            // SAFETY: mutating the environment is only sound while no other
+            // thread reads it; dotenv files are expected to be loaded at startup.
+            unsafe {
+                env::set_var(&key, &value);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether `key` is set as the getters see it: overrides first, then the
+/// process environment. Unlike a getter, the check isn't recorded by
+/// [`record_access`](super::record_access).
+fn is_set(key: &str) -> bool {
+    match overlay::lookup(key) {
+        Some(value) => value.is_some(),
+        None => env::var_os(key).is_some(),
+    }
+}
+
+/// Load `.env` from the current directory without overriding existing variables.
+pub fn load() -> Result<(), EnvError> {
+    Dotenv::new().load()
+}
+
+/// Load the given file without overriding existing variables.
+pub fn load_from(path: impl AsRef<Path>) -> Result<(), EnvError> {
+    Dotenv::new().path(path).load()
+}
+
+/// Read the given file into a map without touching the process environment.
+pub fn read_from(path: impl AsRef<Path>) -> Result<HashMap<String, String>, EnvError> {
+    Dotenv::new().path(path).read()
+}
+
+/// Parse dotenv content into key/value pairs, preserving file order.
+///
+/// Quoted values may span several lines. Errors name the line they occur
+/// on, or for an unterminated quote the line where the quote opened.
+pub fn parse(content: &str) -> Result<Vec<(String, String)>, EnvError> {
+    let mut entries = Vec::new();
+    let mut lines = content
+        .lines()
+        .enumerate()
+        .map(|(index, raw)| (index + 1, raw));
+
+    while let Some((line_no, raw)) = lines.next() {
+        // Keep trailing whitespace, which may open a multiline quoted value.
+        let line = raw.trim_start();
+        if line.trim_end().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let line = line
+            .strip_prefix("export")
+            .filter(|rest| rest.starts_with(char::is_whitespace))
+            .map(str::trim_start)
+            .unwrap_or(line);
+
+        let (key, value) = line.split_once('=').ok_or_else(|| EnvError::Dotenv {
+            line: line_no,
+            message: "expected `KEY=VALUE`".to_string(),
+        })?;
+
+        let key = key.trim();
+        if key.is_empty()
+            || !key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+        {
+            return Err(EnvError::Dotenv {
+                line: line_no,
+                message: format!("invalid key `{}`", key),
+            });
+        }
+
+        let value = parse_value(value, line_no, &mut lines)?;
+        entries.push((key.to_string(), value));
+    }
+
+    Ok(entries)
+}
+
+/// Parse the value after `=`, reading further lines from `lines` while a
+/// quote is open.
+///
+/// Single-quoted values are literal. Double-quoted values understand `\n`,
+/// `\r`, `\t` and `\` before any other character (so `\"` and `\\`), and
+/// a `\` at the end of a line joins it to the next. Unquoted values end at
+/// the first `#` that follows whitespace.
+fn parse_value<'a>(
+    raw: &str,
+    line: usize,
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+) -> Result<String, EnvError> {
+    let quote = match raw.trim_start().chars().next() {
+        Some(quote @ ('\'' | '"')) => quote,
+        _ => {
+            let comment = raw
+                .char_indices()
+                .find(|&(pos, c)| c == '#' && raw[..pos].ends_with(char::is_whitespace));
+            let value = comment.map_or(raw, |(pos, _)| &raw[..pos]);
+            return Ok(value.trim().to_string());
+        }
+    };
+
+    let mut value = String::new();
+    let mut text = &raw.trim_start()[1..];
+    let mut current = line;
+    loop {
+        let mut chars = text.char_indices();
+        let mut continued = false;
+        while let Some((pos, c)) = chars.next() {
+            match c {
+                c if c == quote => {
+                    let rest = text[pos + 1..].trim();
+                    if !rest.is_empty() && !rest.starts_with('#') {
+                        return Err(EnvError::Dotenv {
+                            line: current,
+                            message: format!("unexpected `{}` after closing quote", rest),
+                        });
+                    }
+                    return Ok(value);
+                }
+                '\\' if quote == '"' => match chars.next() {
+                    Some((_, 'n')) => value.push('\n'),
+                    Some((_, 'r')) => value.push('\r'),
+                    Some((_, 't')) => value.push('\t'),
+                    Some((_, other)) => value.push(other),
+                    None => continued = true,
+                },
+                _ => value.push(c),
+            }
+        }
+        let Some((next_no, next)) = lines.next() else {
+            return Err(EnvError::Dotenv {
+                line,
+                message: "unterminated quote".to_string(),
+            });
+        };
+        if !continued {
+            value.push('\n');
+        }
+        text = next;
+        current = next_no;
+    }
+}
+
+/// Format `value` so that [`parse`] reads it back unchanged, quoting it only
+/// when needed.
+pub(crate) fn quote_value(value: &str) -> String {
+    let plain = value.trim() == value
+        && !value.contains(['\n', '\r', '\t'])
+        && !value.contains(" #")
+        && !value.starts_with(['\'', '"']);
+    if plain {
+        return value.to_string();
+    }
+    let mut quoted = String::from('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn read_file(path: &Path) -> Result<String, EnvError> {
+    fs::read_to_string(path).map_err(|source| EnvError::Io {
+        path: path.display().to_string(),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::overrides;
+    use crate::env::test::fixtures::temp_file;
+    use crate::env::test::{ScopedEnv, without_vars};
+
+    #[test]
+    fn test_parse_basic_comments_and_export() {
+        let content = "# comment\n\nHOST=localhost\nexport PORT = 8080\nNAME=app # trailing\n\
+                       export\tTABBED=1\nexport=plain\n";
+        let entries = parse(content).unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                ("HOST".to_string(), "localhost".to_string()),
+                ("PORT".to_string(), "8080".to_string()),
+                ("NAME".to_string(), "app".to_string()),
+                ("TABBED".to_string(), "1".to_string()),
+                ("export".to_string(), "plain".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_quoted_values() {
+        let content = "A='single # not comment'\nB=\"line\\nbreak \\\"quoted\\\"\"\nC=\"\"\n";
+        let map: HashMap<_, _> = parse(content).unwrap().into_iter().collect();
+        assert_eq!(map["A"], "single # not comment");
+        assert_eq!(map["B"], "line\nbreak \"quoted\"");
+        assert_eq!(map["C"], "");
+    }
+
+    #[test]
+    fn test_parse_multiline_and_escapes() {
+        let content = "CERT=\"-----BEGIN-----\nabc  \n-----END-----\"\n\
+                       RAW='a\\nb\n  c' # literal\n\
+                       JOINED=\"one \\\ntwo\"\n\
+                       DOLLAR=\"\\$HOME \\\\ \\q\"\n\
+                       COLOR=#fff\n\
+                       EMPTY= # nothing\n\
+                       AFTER=1\n";
+        let map: HashMap<_, _> = parse(content).unwrap().into_iter().collect();
+        assert_eq!(map["CERT"], "-----BEGIN-----\nabc  \n-----END-----");
+        assert_eq!(map["RAW"], "a\\nb\n  c");
+        assert_eq!(map["JOINED"], "one two");
+        assert_eq!(map["DOLLAR"], "$HOME \\ q");
+        assert_eq!(map["COLOR"], "#fff");
+        assert_eq!(map["EMPTY"], "");
+        assert_eq!(map["AFTER"], "1");
+    }
+
+    #[test]
+    fn test_parse_errors_report_line() {
+        let result = parse("OK=1\nNOT VALID\n");
+        assert!(matches!(result, Err(EnvError::Dotenv { line: 2, .. })));
+        let result = parse("A=\"open\n");
+        assert!(matches!(result, Err(EnvError::Dotenv { line: 1, .. })));
+
+        let content = "A=1\nB='one\ntwo'\nC=\"never\nclosed\nD=4\n";
+        let err = parse(content).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid dotenv syntax at line 4: unterminated quote"
+        );
+        let result = parse("A=1\nB=\"multi\nline\" trailing\n");
+        assert!(matches!(
+            result,
+            Err(EnvError::Dotenv { line: 3, message }) if message == "unexpected `trailing` after closing quote"
+        ));
+    }
+
+    #[test]
+    fn test_read_from_does_not_touch_env() {
+        let path = temp_file("read.env", "DOTENV_READ_ONLY=1\n");
+        without_vars(&["DOTENV_READ_ONLY"], || {
+            let map = read_from(&path).unwrap();
+            assert_eq!(map.get("DOTENV_READ_ONLY").map(String::as_str), Some("1"));
+            assert!(env::var("DOTENV_READ_ONLY").is_err());
+        });
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_load_respects_override_option() {
+        let path = temp_file("load.env", "DOTENV_NEW=new\nDOTENV_EXISTING=from-file\n");
+        let mut scope = ScopedEnv::new();
+        scope
+            .set("DOTENV_EXISTING", "original")
+            .remove("DOTENV_NEW");
+
+        load_from(&path).unwrap();
+        assert_eq!(env::var("DOTENV_NEW").unwrap(), "new");
+        assert_eq!(env::var("DOTENV_EXISTING").unwrap(), "original");
+
+        Dotenv::new()
+            .path(&path)
+            .override_existing(true)
+            .load()
+            .unwrap();
+        assert_eq!(env::var("DOTENV_EXISTING").unwrap(), "from-file");
+
+        drop(scope);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_duplicate_keys_last_wins() {
+        let path = temp_file("duplicates.env", "DOTENV_DUP=first\nDOTENV_DUP=second\n");
+        assert_eq!(
+            read_from(&path)
+                .unwrap()
+                .get("DOTENV_DUP")
+                .map(String::as_str),
+            Some("second")
+        );
+
+        let mut scope = ScopedEnv::new();
+        scope.remove("DOTENV_DUP");
+        load_from(&path).unwrap();
+        assert_eq!(env::var("DOTENV_DUP").unwrap(), "second");
+
+        drop(scope);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_load_respects_overrides() {
+        let path = temp_file(
+            "overlaid.env",
+            "DOTENV_OVERLAID=from-file
+",
+        );
+        let mut scope = ScopedEnv::new();
+        scope.remove("DOTENV_OVERLAID");
+        overrides().set("DOTENV_OVERLAID", "override");
+
+        load_from(&path).unwrap();
+        overrides().restore("DOTENV_OVERLAID");
+        assert!(env::var("DOTENV_OVERLAID").is_err());
+
+        drop(scope);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        let result = load_from("/definitely/not/here/.env");
+        assert!(matches!(result, Err(EnvError::Io { .. })));
+    }
+}
@@ -0,0 +1,157 @@
+use std::time::Duration;
+
+use crate::time;
+
+use super::access;
+use super::env::{EnvError, get_required_from};
+use super::source::{EnvSource, ProcessEnv};
+
+/// Parse a human-readable duration (e.g. 30s, 5m, 1h30m, 1.5h, 250ms, or
+/// 90 for seconds), as [`time::parse_duration`] does.
+pub fn parse_duration(input: &str) -> Result<Duration, EnvError> {
+    time::parse_duration(input).map_err(|e| {
+        EnvError::parse_error("duration", input.trim())
+            .with_expected("duration")
+            .with_source(e)
+    })
+}
+
+/// Read a required variable and parse it as a human-readable duration.
+pub fn get_duration(key: &str) -> Result<Duration, EnvError> {
+    get_duration_from(&ProcessEnv, key)
+}
+
+/// Like [`get_duration`], returning `default` when the variable is unset or
+/// cannot be parsed.
+///
+/// Example:
+/// ```
+/// use std::time::Duration;
+/// use common_utils_rs::env::{get_duration_or_default, test::without_vars};
+/// without_vars(&["REQUEST_TIMEOUT"], || {
+///     let timeout = get_duration_or_default("REQUEST_TIMEOUT", Duration::from_secs(30));
+///     assert_eq!(timeout, Duration::from_secs(30));
+/// });
+/// ```
+pub fn get_duration_or_default(key: &str, default: Duration) -> Duration {
+    let value = get_duration(key).unwrap_or(default);
+    access::record_default(key, || format!("{:?}", default));
+    value
+}
+
+pub(crate) fn get_duration_from(source: &impl EnvSource, key: &str) -> Result<Duration, EnvError> {
+    parse_duration_value(key, get_required_from(source, key)?)
+}
+
+/// Parse `value` as [`parse_duration`] does, reporting errors against `key`.
+pub(crate) fn parse_duration_value(key: &str, value: String) -> Result<Duration, EnvError> {
+    parse_duration(&value).map_err(|e| match e {
+        EnvError::ParseError { source, .. } => EnvError::ParseError {
+            key: key.to_string(),
+            value,
+            expected: Some("duration".to_string()),
+            source,
+        },
+        other => other,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test::{with_vars, without_vars};
+
+    #[test]
+    fn test_parse_duration_single_units() {
+        assert_eq!(parse_duration("250ms").unwrap(), Duration::from_millis(250));
+        assert_eq!(parse_duration("30s").unwrap(), Duration::from_secs(30));
+        assert_eq!(parse_duration("5m").unwrap(), Duration::from_secs(300));
+        assert_eq!(parse_duration("2h").unwrap(), Duration::from_secs(7200));
+        assert_eq!(parse_duration("1d").unwrap(), Duration::from_secs(86400));
+        assert_eq!(parse_duration("15us").unwrap(), Duration::from_micros(15));
+        assert_eq!(parse_duration("7ns").unwrap(), Duration::from_nanos(7));
+    }
+
+    #[test]
+    fn test_parse_duration_combined() {
+        assert_eq!(parse_duration("1h30m").unwrap(), Duration::from_secs(5400));
+        assert_eq!(
+            parse_duration("1m 30s 500ms").unwrap(),
+            Duration::from_millis(90_500)
+        );
+    }
+
+    #[test]
+    fn test_parse_duration_fractions_and_seconds() {
+        assert_eq!(parse_duration("1.5h").unwrap(), Duration::from_secs(5400));
+        assert_eq!(parse_duration("30").unwrap(), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_parse_duration_invalid() {
+        for input in ["", "abc", "5x", "m5", "1.5.h", "-5s"] {
+            assert!(
+                matches!(parse_duration(input), Err(EnvError::ParseError { .. })),
+                "expected `{}` to be rejected",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_get_duration() {
+        with_vars(&[("DURATION_KEY", "1h30m")], || {
+            assert_eq!(
+                get_duration("DURATION_KEY").unwrap(),
+                Duration::from_secs(5400)
+            );
+        });
+        with_vars(&[("DURATION_KEY", "soon")], || {
+            let err = get_duration("DURATION_KEY").unwrap_err();
+            let reason = std::error::Error::source(&err)
+                .and_then(|source| source.downcast_ref::<time::ParseDurationError>())
+                .map(|source| source.reason.clone());
+            assert!(reason.is_some(), "missing source: {:?}", err);
+            match err {
+                EnvError::ParseError {
+                    key,
+                    value,
+                    expected,
+                    ..
+                } => {
+                    assert_eq!(key, "DURATION_KEY");
+                    assert_eq!(value, "soon");
+                    assert_eq!(expected.as_deref(), Some("duration"));
+                }
+                other => panic!("unexpected error: {:?}", other),
+            }
+        });
+        without_vars(&["DURATION_KEY"], || {
+            assert!(matches!(
+                get_duration("DURATION_KEY"),
+                Err(EnvError::Missing(_))
+            ));
+        });
+    }
+
+    #[test]
+    fn test_get_duration_or_default() {
+        let default = Duration::from_secs(30);
+        with_vars(
+            &[("DURATION_OR_SET", "5s"), ("DURATION_OR_BAD", "soon")],
+            || {
+                assert_eq!(
+                    get_duration_or_default("DURATION_OR_SET", default),
+                    Duration::from_secs(5)
+                );
+                assert_eq!(get_duration_or_default("DURATION_OR_BAD", default), default);
+            },
+        );
+        without_vars(&["DURATION_OR_UNSET"], || {
+            assert_eq!(
+                get_duration_or_default("DURATION_OR_UNSET", default),
+                default
+            );
+        });
+    }
+}
@@ -0,0 +1,202 @@
+//! Binary values supplied as text.
+//!
+//! Errors describe what is wrong with the encoding without echoing the
+//! value, since these variables usually hold keys and tokens.
+use super::env::{EnvError, get_required_from};
+use super::source::{EnvSource, ProcessEnv};
+
+/// Read a base64-encoded variable.
+///
+/// Both the standard (`+/`) and URL-safe (`-_`) alphabets are accepted, with
+/// or without `=` padding. Whitespace is ignored, so wrapped PEM-style bodies
+/// work as-is.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::env::{get_base64, test::with_vars};
+/// with_vars(&[("SIGNING_KEY", "aGVsbG8=")], || {
+///     assert_eq!(get_base64("SIGNING_KEY").unwrap(), b"hello");
+/// });
+/// ```
+pub fn get_base64(key: &str) -> Result<Vec<u8>, EnvError> {
+    get_base64_from(&ProcessEnv, key)
+}
+
+pub(crate) fn get_base64_from(source: &impl EnvSource, key: &str) -> Result<Vec<u8>, EnvError> {
+    let value = get_required_from(source, key)?;
+    decode_base64(&value).map_err(|reason| EnvError::Invalid {
+        key: key.to_string(),
+        reason: format!("invalid base64: {}", reason),
+    })
+}
+
+fn decode_base64(input: &str) -> Result<Vec<u8>, String> {
+    let chars: Vec<u8> = input.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+    let data = chars
+        .strip_suffix(b"==")
+        .or_else(|| chars.strip_suffix(b"="))
+        .unwrap_or(&chars);
+    if data.len() != chars.len() && !chars.len().is_multiple_of(4) {
+        return Err("incorrect padding".to_string());
+    }
+    if data.len() % 4 == 1 {
+        return Err("truncated input".to_string());
+    }
+
+    let has_standard = data.iter().any(|b| matches!(b, b'+' | b'/'));
+    let has_url_safe = data.iter().any(|b| matches!(b, b'-' | b'_'));
+    if has_standard && has_url_safe {
+        return Err("mixes the standard and URL-safe alphabets".to_string());
+    }
+
+    let mut out = Vec::with_capacity(data.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for (position, &byte) in data.iter().enumerate() {
+        let sextet = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            _ => return Err(format!("unexpected character at position {}", position)),
+        };
+        buffer = (buffer << 6) | u32::from(sextet);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Ok(out)
+}
+
+/// Read a hex-encoded variable (e.g. `deadbeef`), case-insensitive.
+///
+/// An optional `0x` prefix and whitespace are ignored.
+pub fn get_hex(key: &str) -> Result<Vec<u8>, EnvError> {
+    get_hex_from(&ProcessEnv, key)
+}
+
+/// Like [`get_hex`], but the decoded value must be exactly `len` bytes.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::env::{get_hex_exact, test::with_vars};
+/// with_vars(&[("TRACE_ID", "4bf92f3577b34da6a3ce929d0e0e4736")], || {
+///     assert_eq!(get_hex_exact("TRACE_ID", 16).unwrap().len(), 16);
+///     assert!(get_hex_exact("TRACE_ID", 32).is_err());
+/// });
+/// ```
+pub fn get_hex_exact(key: &str, len: usize) -> Result<Vec<u8>, EnvError> {
+    get_hex_exact_from(&ProcessEnv, key, len)
+}
+
+pub(crate) fn get_hex_from(source: &impl EnvSource, key: &str) -> Result<Vec<u8>, EnvError> {
+    let value = get_required_from(source, key)?;
+    decode_hex(&value).map_err(|reason| EnvError::Invalid {
+        key: key.to_string(),
+        reason: format!("invalid hex: {}", reason),
+    })
+}
+
+pub(crate) fn get_hex_exact_from(
+    source: &impl EnvSource,
+    key: &str,
+    len: usize,
+) -> Result<Vec<u8>, EnvError> {
+    let bytes = get_hex_from(source, key)?;
+    if bytes.len() != len {
+        return Err(EnvError::Invalid {
+            key: key.to_string(),
+            reason: format!("expected {} bytes, got {}", len, bytes.len()),
+        });
+    }
+    Ok(bytes)
+}
+
+fn decode_hex(input: &str) -> Result<Vec<u8>, String> {
+    let digits: Vec<u8> = input.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+    let digits = digits
+        .strip_prefix(b"0x")
+        .or_else(|| digits.strip_prefix(b"0X"))
+        .unwrap_or(&digits);
+    if !digits.len().is_multiple_of(2) {
+        return Err("odd number of digits".to_string());
+    }
+
+    let nibble = |position: usize| {
+        (digits[position] as char)
+            .to_digit(16)
+            .map(|d| d as u8)
+            .ok_or_else(|| format!("unexpected character at position {}", position))
+    };
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| Ok((nibble(i)? << 4) | nibble(i + 1)?))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test::with_vars;
+
+    #[test]
+    fn test_decode_base64_alphabets_and_padding() {
+        assert_eq!(decode_base64("").unwrap(), b"");
+        assert_eq!(decode_base64("Zg==").unwrap(), b"f");
+        assert_eq!(decode_base64("Zg").unwrap(), b"f");
+        assert_eq!(decode_base64("Zm8=").unwrap(), b"fo");
+        assert_eq!(decode_base64("Zm9v").unwrap(), b"foo");
+        assert_eq!(decode_base64("+/+/").unwrap(), [0xfb, 0xff, 0xbf]);
+        assert_eq!(decode_base64("-_-_").unwrap(), [0xfb, 0xff, 0xbf]);
+        assert_eq!(decode_base64("Zm9v\n YmFy").unwrap(), b"foobar");
+    }
+
+    #[test]
+    fn test_decode_base64_rejects_bad_input() {
+        assert!(decode_base64("Zm9v!").is_err());
+        assert!(decode_base64("Z").is_err());
+        assert!(decode_base64("Zg=").is_err());
+        assert!(decode_base64("+-==").is_err());
+    }
+
+    #[test]
+    fn test_get_base64_error_hides_value() {
+        with_vars(&[("BASE64_BAD", "s3cr3t!!")], || {
+            match get_base64("BASE64_BAD") {
+                Err(EnvError::Invalid { key, reason }) => {
+                    assert_eq!(key, "BASE64_BAD");
+                    assert!(!reason.contains("s3cr3t"));
+                }
+                other => panic!("unexpected result: {:?}", other),
+            }
+        });
+    }
+
+    #[test]
+    fn test_decode_hex() {
+        assert_eq!(decode_hex("").unwrap(), b"");
+        assert_eq!(decode_hex("DeadBeef").unwrap(), [0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(decode_hex("0x00ff").unwrap(), [0x00, 0xff]);
+        assert_eq!(decode_hex("00 ff\n10").unwrap(), [0x00, 0xff, 0x10]);
+        assert!(decode_hex("abc").is_err());
+        assert!(decode_hex("zz").is_err());
+    }
+
+    #[test]
+    fn test_get_hex_exact() {
+        with_vars(&[("HEX_KEY", "00112233")], || {
+            assert_eq!(get_hex("HEX_KEY").unwrap(), [0x00, 0x11, 0x22, 0x33]);
+            assert_eq!(get_hex_exact("HEX_KEY", 4).unwrap().len(), 4);
+            match get_hex_exact("HEX_KEY", 32) {
+                Err(EnvError::Invalid { reason, .. }) => {
+                    assert_eq!(reason, "expected 32 bytes, got 4")
+                }
+                other => panic!("unexpected result: {:?}", other),
+            }
+        });
+    }
+}
@@ -1,204 +1,1350 @@
-use std::env;
+use std::collections::HashMap;
+use std::ffi::OsString;
 use std::str::FromStr;
 
 use thiserror::Error;
 
+use super::access;
+use super::bytesize::ByteSize;
+use super::source::{EnvSource, ProcessEnv};
+
+/// A boxed error, as carried by [`EnvError::ParseError`].
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
 #[derive(Debug, Error)]
 pub enum EnvError {
     #[error("Environment variable `{0}` is not set")]
     Missing(String),
 
-    #[error("Failed to parse environment variable `{key}`: {value}")]
-    ParseError { key: String, value: String },
+    #[error("Environment variable `{0}` is not valid unicode")]
+    NotUnicode(String),
+
+    #[error(
+        "Failed to parse environment variable `{key}`{}: {value}{}",
+        describe_expected(.expected),
+        describe_source(.source)
+    )]
+    ParseError {
+        key: String,
+        value: String,
+        /// The type the value should have parsed as, e.g. `u16`.
+        expected: Option<String>,
+        /// Why parsing failed, usually the `FromStr` error.
+        #[source]
+        source: Option<BoxError>,
+    },
+
+    #[error(
+        "Failed to parse element {index} of environment variable `{key}`: {value}{}",
+        describe_source(.source)
+    )]
+    ListItem {
+        key: String,
+        index: usize,
+        value: String,
+        /// Why the element failed to parse, usually the `FromStr` error.
+        #[source]
+        source: Option<BoxError>,
+    },
+
+    #[error("Invalid value for environment variable `{key}`: {reason}")]
+    Invalid { key: String, reason: String },
+
+    #[error("Environment variable `{key}` is too large: {value}")]
+    Overflow { key: String, value: String },
+
+    #[error("Environment variable `{key}` points to `{}`, which {reason}", path.display())]
+    InvalidPath {
+        key: String,
+        path: std::path::PathBuf,
+        reason: String,
+    },
+
+    #[error("Failed to read `{path}`: {source}")]
+    Io {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Invalid dotenv syntax at line {line}: {message}")]
+    Dotenv { line: usize, message: String },
+
+    #[error("{} environment variable errors:\n{}", .0.len(), format_errors(.0))]
+    Multiple(Vec<EnvError>),
+}
+
+impl EnvError {
+    /// Fold several errors into one: `Ok(())` when there are none, the error
+    /// itself when there is exactly one, and `Multiple` otherwise.
+    pub fn aggregate(mut errors: Vec<EnvError>) -> Result<(), EnvError> {
+        match errors.len() {
+            0 => Ok(()),
+            1 => Err(errors.remove(0)),
+            _ => Err(EnvError::Multiple(errors)),
+        }
+    }
+
+    /// A [`EnvError::ParseError`] for `value` of `key`, without an expected
+    /// type or source.
+    pub fn parse_error(key: impl Into<String>, value: impl Into<String>) -> Self {
+        EnvError::ParseError {
+            key: key.into(),
+            value: value.into(),
+            expected: None,
+            source: None,
+        }
+    }
+
+    /// Record the type a [`EnvError::ParseError`] expected. Other errors are
+    /// returned unchanged.
+    pub fn with_expected(mut self, type_name: impl Into<String>) -> Self {
+        if let EnvError::ParseError { expected, .. } = &mut self {
+            *expected = Some(type_name.into());
+        }
+        self
+    }
+
+    /// Record why a [`EnvError::ParseError`] failed. Other errors are returned
+    /// unchanged.
+    pub fn with_source(mut self, error: impl Into<BoxError>) -> Self {
+        if let EnvError::ParseError { source, .. } = &mut self {
+            *source = Some(error.into());
+        }
+        self
+    }
+}
+
+fn describe_expected(expected: &Option<String>) -> String {
+    match expected {
+        Some(expected) => format!(" as {}", expected),
+        None => String::new(),
+    }
+}
+
+fn describe_source(source: &Option<BoxError>) -> String {
+    match source {
+        Some(source) => format!(" ({})", source),
+        None => String::new(),
+    }
+}
+
+/// The name of `T` without module paths, e.g. `Vec<SocketAddr>`.
+pub(crate) fn type_name<T: ?Sized>() -> String {
+    let full = std::any::type_name::<T>();
+    let mut name = String::with_capacity(full.len());
+    let mut segment = String::new();
+    for c in full.chars() {
+        if c.is_alphanumeric() || c == '_' || c == ':' {
+            segment.push(c);
+        } else {
+            name.push_str(segment.rsplit("::").next().unwrap_or_default());
+            segment.clear();
+            name.push(c);
+        }
+    }
+    name.push_str(segment.rsplit("::").next().unwrap_or_default());
+    name
+}
+
+/// Parse `value` of `key` as `T`, keeping the `FromStr` error as the source.
+pub(crate) fn parse_value<T>(key: &str, value: String) -> Result<T, EnvError>
+where
+    T: FromStr,
+    T::Err: Into<BoxError>,
+{
+    value.parse::<T>().map_err(|e| {
+        EnvError::parse_error(key, value)
+            .with_expected(type_name::<T>())
+            .with_source(e)
+    })
+}
+
+fn format_errors(errors: &[EnvError]) -> String {
+    errors
+        .iter()
+        .map(|e| format!("  - {}", e))
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 pub fn get_required(key: &str) -> Result<String, EnvError> {
-    env::var(key).map_err(|_| EnvError::Missing(key.to_string()))
+    get_required_from(&ProcessEnv, key)
+}
+
+pub(crate) fn get_required_from(source: &impl EnvSource, key: &str) -> Result<String, EnvError> {
+    source
+        .get(key)
+        .ok_or_else(|| missing_or_not_unicode(source, key))
+}
+
+/// Error for a variable that [`EnvSource::get`] didn't return.
+fn missing_or_not_unicode(source: &impl EnvSource, key: &str) -> EnvError {
+    match source.get_os(key) {
+        Some(_) => EnvError::NotUnicode(key.to_string()),
+        None => EnvError::Missing(key.to_string()),
+    }
+}
+
+/// Read a required variable without requiring it to be valid unicode.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::env::{get_required_os, test::with_vars};
+/// with_vars(&[("DATA_DIR", "/var/lib/app")], || {
+///     assert_eq!(get_required_os("DATA_DIR").unwrap(), "/var/lib/app");
+/// });
+/// ```
+pub fn get_required_os(key: &str) -> Result<OsString, EnvError> {
+    get_required_os_from(&ProcessEnv, key)
+}
+
+pub(crate) fn get_required_os_from(
+    source: &impl EnvSource,
+    key: &str,
+) -> Result<OsString, EnvError> {
+    source
+        .get_os(key)
+        .ok_or_else(|| EnvError::Missing(key.to_string()))
+}
+
+/// Like [`get_required`], but a variable set to `""` counts as missing.
+pub fn get_nonempty(key: &str) -> Result<String, EnvError> {
+    get_nonempty_from(&ProcessEnv, key)
+}
+
+pub(crate) fn get_nonempty_from(source: &impl EnvSource, key: &str) -> Result<String, EnvError> {
+    let value = get_required_from(source, key)?;
+    if value.is_empty() {
+        return Err(EnvError::Missing(key.to_string()));
+    }
+    Ok(value)
 }
 
 pub fn get_or_default(key: &str, default: &str) -> String {
-    env::var(key).unwrap_or_else(|_| default.to_string())
+    let value = get_or_default_from(&ProcessEnv, key, default);
+    access::record_default(key, || default.to_string());
+    value
 }
 
-pub fn get_parsed<T>(key: &str) -> Result<T, EnvError> where T: FromStr, {
-    let value = get_required(key)?;
-    value.parse::<T>().map_err(|_| EnvError::ParseError {
-        key: key.to_string(),
-        value,
-    })
+pub(crate) fn get_or_default_from(source: &impl EnvSource, key: &str, default: &str) -> String {
+    source.get(key).unwrap_or_else(|| default.to_string())
+}
+
+pub fn get_parsed<T>(key: &str) -> Result<T, EnvError> where T: FromStr, T::Err: Into<BoxError>, {
+    get_parsed_from(&ProcessEnv, key)
+}
+
+pub(crate) fn get_parsed_from<T>(source: &impl EnvSource, key: &str) -> Result<T, EnvError>
+where
+    T: FromStr,
+    T::Err: Into<BoxError>,
+{
+    parse_value(key, get_required_from(source, key)?)
 }
 
 pub fn get_parsed_or_default<T>(key: &str, default: T) -> T where T: FromStr, {
-    env::var(key)
-        .ok()
+    get_parsed_or_default_from(&ProcessEnv, key, default)
+}
+
+pub(crate) fn get_parsed_or_default_from<T>(source: &impl EnvSource, key: &str, default: T) -> T
+where
+    T: FromStr,
+{
+    source
+        .get(key)
         .and_then(|v| v.parse::<T>().ok())
         .unwrap_or(default)
 }
 
+/// Parse a variable into an enum-like type, keeping the `FromStr` error
+/// message in the returned error
+pub fn get_enum<T>(key: &str) -> Result<T, EnvError>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    get_enum_from(&ProcessEnv, key)
+}
+
+pub(crate) fn get_enum_from<T>(source: &impl EnvSource, key: &str) -> Result<T, EnvError>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    let value = get_required_from(source, key)?;
+    value.trim().parse::<T>().map_err(|e| EnvError::Invalid {
+        key: key.to_string(),
+        reason: e.to_string(),
+    })
+}
+
+/// Read a variable that must match one of `allowed` (case-insensitive),
+/// returning the matching entry from `allowed`
+pub fn get_one_of<'a>(key: &str, allowed: &[&'a str]) -> Result<&'a str, EnvError> {
+    get_one_of_from(&ProcessEnv, key, allowed)
+}
+
+pub(crate) fn get_one_of_from<'a>(
+    source: &impl EnvSource,
+    key: &str,
+    allowed: &[&'a str],
+) -> Result<&'a str, EnvError> {
+    let value = get_required_from(source, key)?;
+    let trimmed = value.trim();
+    allowed
+        .iter()
+        .copied()
+        .find(|candidate| candidate.eq_ignore_ascii_case(trimmed))
+        .ok_or_else(|| EnvError::Invalid {
+            key: key.to_string(),
+            reason: format!("`{}` is not one of: {}", value, allowed.join(", ")),
+        })
+}
+
 pub fn get_bool(key: &str, default: bool) -> bool {
-    match env::var(key) {
-        Ok(val) => matches!(val.to_lowercase().as_str(), "true" | "1" | "yes" | "on"),
-        Err(_) => default,
+    let value = get_bool_from(&ProcessEnv, key, default);
+    access::record_default(key, || default.to_string());
+    value
+}
+
+pub(crate) fn get_bool_from(source: &impl EnvSource, key: &str, default: bool) -> bool {
+    match source.get(key) {
+        Some(val) => matches!(val.to_lowercase().as_str(), "true" | "1" | "yes" | "on"),
+        None => default,
+    }
+}
+
+pub(crate) const BOOL_VALUES: &str = "true/false, 1/0, yes/no, on/off, enabled/disabled";
+
+pub(crate) fn parse_bool_strict(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" | "enabled" => Some(true),
+        "false" | "0" | "no" | "off" | "disabled" => Some(false),
+        _ => None,
+    }
+}
+
+/// Read a required boolean, rejecting anything outside the recognized values
+/// (true/false, 1/0, yes/no, on/off, enabled/disabled; case-insensitive)
+pub fn get_bool_strict(key: &str) -> Result<bool, EnvError> {
+    get_bool_strict_from(&ProcessEnv, key)
+}
+
+pub(crate) fn get_bool_strict_from(source: &impl EnvSource, key: &str) -> Result<bool, EnvError> {
+    let value = get_required_from(source, key)?;
+    parse_bool_value(key, &value)
+}
+
+pub(crate) fn parse_bool_value(key: &str, value: &str) -> Result<bool, EnvError> {
+    parse_bool_strict(value).ok_or_else(|| EnvError::Invalid {
+        key: key.to_string(),
+        reason: format!("`{}` is not a boolean (expected {})", value, BOOL_VALUES),
+    })
+}
+
+/// Read an optional boolean: `Ok(None)` when the variable is unset or empty,
+/// otherwise the same values as [`get_bool_strict`], erroring on anything else
+///
+/// Example:
+/// ```
+/// use common_utils_rs::env::{get_bool_opt, test::with_vars};
+/// with_vars(&[("NEW_CHECKOUT", "off")], || {
+///     match get_bool_opt("NEW_CHECKOUT").unwrap() {
+///         Some(enabled) => assert!(!enabled), // operator decided
+///         None => unreachable!("rollout default applies"),
+///     }
+/// });
+/// ```
+pub fn get_bool_opt(key: &str) -> Result<Option<bool>, EnvError> {
+    get_bool_opt_from(&ProcessEnv, key)
+}
+
+pub(crate) fn get_bool_opt_from(
+    source: &impl EnvSource,
+    key: &str,
+) -> Result<Option<bool>, EnvError> {
+    match get_nonempty_from(source, key) {
+        Ok(value) => parse_bool_value(key, &value).map(Some),
+        Err(EnvError::Missing(_)) => Ok(None),
+        Err(e) => Err(e),
     }
 }
 
 pub fn get_list(key: &str) -> Result<Vec<String>, EnvError> {
-    let value = get_required(key)?;
-    Ok(value.split(',').map(|s| s.trim().to_string()).collect())
-}
-
-/// Parse memory size (e.g. 10MB, 512KB, 1GB)
-pub fn parse_memory_size(input: &str) -> Result<usize, EnvError> {
-    let input = input.trim().to_uppercase();
-
-    let (num_part, multiplier) = if input.ends_with("KB") {
-        (&input[..input.len() - 2], 1024)
-    } else if input.ends_with("MB") {
-        (&input[..input.len() - 2], 1024 * 1024)
-    } else if input.ends_with("GB") {
-        (&input[..input.len() - 2], 1024 * 1024 * 1024)
-    } else {
-        (input.as_str(), 1)
-    };
+    get_list_from(&ProcessEnv, key)
+}
+
+pub(crate) fn get_list_from(source: &impl EnvSource, key: &str) -> Result<Vec<String>, EnvError> {
+    get_list_with_from(source, key, &ListOptions::default())
+}
+
+/// How [`get_list_with`] splits a value.
+///
+/// The default matches [`get_list`]: split on `,` and trim each item.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::env::{get_list_with, test::with_vars, ListOptions};
+/// with_vars(&[("SEARCH_PATH", "/usr/bin::/bin:/usr/bin")], || {
+///     let options = ListOptions { delimiter: ":", skip_empty: true, dedup: true, ..Default::default() };
+///     assert_eq!(get_list_with("SEARCH_PATH", &options).unwrap(), vec!["/usr/bin", "/bin"]);
+/// });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ListOptions<'a> {
+    /// Separator between items.
+    pub delimiter: &'a str,
+    /// Trim whitespace around each item.
+    pub trim: bool,
+    /// Drop items that are empty (after trimming, if enabled).
+    pub skip_empty: bool,
+    /// Drop repeated items, keeping the first occurrence.
+    pub dedup: bool,
+}
+
+impl Default for ListOptions<'_> {
+    fn default() -> Self {
+        Self {
+            delimiter: ",",
+            trim: true,
+            skip_empty: false,
+            dedup: false,
+        }
+    }
+}
+
+/// Split a variable according to `options`
+pub fn get_list_with(key: &str, options: &ListOptions) -> Result<Vec<String>, EnvError> {
+    get_list_with_from(&ProcessEnv, key, options)
+}
+
+pub(crate) fn get_list_with_from(
+    source: &impl EnvSource,
+    key: &str,
+    options: &ListOptions,
+) -> Result<Vec<String>, EnvError> {
+    let value = get_required_from(source, key)?;
+    let mut items: Vec<String> = Vec::new();
+    for item in value.split(options.delimiter) {
+        let item = if options.trim { item.trim() } else { item };
+        if options.skip_empty && item.is_empty() {
+            continue;
+        }
+        if options.dedup && items.iter().any(|seen| seen == item) {
+            continue;
+        }
+        items.push(item.to_string());
+    }
+    Ok(items)
+}
+
+/// Parse each comma-separated element with `FromStr` (e.g. `PORTS=80,443`)
+pub fn get_parsed_list<T>(key: &str) -> Result<Vec<T>, EnvError> where T: FromStr, T::Err: Into<BoxError>, {
+    get_parsed_list_from(&ProcessEnv, key)
+}
+
+/// Like [`get_parsed_list`], splitting according to `options`
+pub fn get_parsed_list_with<T>(key: &str, options: &ListOptions) -> Result<Vec<T>, EnvError> where T: FromStr, T::Err: Into<BoxError>, {
+    get_parsed_list_with_from(&ProcessEnv, key, options)
+}
+
+pub(crate) fn get_parsed_list_from<T>(
+    source: &impl EnvSource,
+    key: &str,
+) -> Result<Vec<T>, EnvError>
+where
+    T: FromStr,
+    T::Err: Into<BoxError>,
+{
+    get_parsed_list_with_from(source, key, &ListOptions::default())
+}
+
+pub(crate) fn get_parsed_list_with_from<T>(
+    source: &impl EnvSource,
+    key: &str,
+    options: &ListOptions,
+) -> Result<Vec<T>, EnvError>
+where
+    T: FromStr,
+    T::Err: Into<BoxError>,
+{
+    get_list_with_from(source, key, options)?
+        .into_iter()
+        .enumerate()
+        .map(|(index, item)| {
+            item.parse::<T>().map_err(|e| EnvError::ListItem {
+                key: key.to_string(),
+                index,
+                value: item,
+                source: Some(e.into()),
+            })
+        })
+        .collect()
+}
+
+/// Parse `key=value` pairs separated by commas (e.g. `a=1,b=true,c=hello`)
+pub fn get_map(key: &str) -> Result<HashMap<String, String>, EnvError> {
+    get_map_with(key, ",", "=")
+}
+
+/// Parse pairs using custom item and key/value separators
+/// (e.g. `get_map_with("LABELS", ";", ":")` for `a:1;b:2`)
+pub fn get_map_with(
+    key: &str,
+    item_separator: &str,
+    pair_separator: &str,
+) -> Result<HashMap<String, String>, EnvError> {
+    get_map_with_from(&ProcessEnv, key, item_separator, pair_separator)
+}
+
+pub(crate) fn get_map_with_from(
+    source: &impl EnvSource,
+    key: &str,
+    item_separator: &str,
+    pair_separator: &str,
+) -> Result<HashMap<String, String>, EnvError> {
+    let value = get_required_from(source, key)?;
+    value
+        .split(item_separator)
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| match item.split_once(pair_separator) {
+            Some((k, v)) if !k.trim().is_empty() => {
+                Ok((k.trim().to_string(), v.trim().to_string()))
+            }
+            _ => Err(EnvError::parse_error(key, item).with_expected("map entry")),
+        })
+        .collect()
+}
+
+/// Parse weighted entries such as `backend-a:3,backend-b:1`, e.g. for load
+/// balancing or canary traffic splits.
+///
+/// A missing weight defaults to 1 and the weight follows the last `:`, so
+/// names may contain colons (`host:8080:3`). Names must be unique and at least
+/// one weight must be non-zero.
+pub fn get_weighted_list(key: &str) -> Result<Vec<(String, u32)>, EnvError> {
+    get_weighted_list_from(&ProcessEnv, key)
+}
+
+pub(crate) fn get_weighted_list_from(
+    source: &impl EnvSource,
+    key: &str,
+) -> Result<Vec<(String, u32)>, EnvError> {
+    let mut entries: Vec<(String, u32)> = Vec::new();
+    for (index, item) in get_list_from(source, key)?.into_iter().enumerate() {
+        if item.is_empty() {
+            continue;
+        }
+        let item_error = || EnvError::ListItem {
+            key: key.to_string(),
+            index,
+            value: item.clone(),
+            source: None,
+        };
+        let (name, weight) = match item.rsplit_once(':') {
+            Some((name, weight)) => (
+                name.trim(),
+                weight.trim().parse().map_err(|_| item_error())?,
+            ),
+            None => (item.as_str(), 1),
+        };
+        if name.is_empty() {
+            return Err(item_error());
+        }
+        if entries.iter().any(|(existing, _)| existing == name) {
+            return Err(EnvError::Invalid {
+                key: key.to_string(),
+                reason: format!("`{}` is listed more than once", name),
+            });
+        }
+        entries.push((name.to_string(), weight));
+    }
+    if entries.iter().all(|(_, weight)| *weight == 0) {
+        return Err(EnvError::Invalid {
+            key: key.to_string(),
+            reason: "at least one weight must be greater than zero".to_string(),
+        });
+    }
+    Ok(entries)
+}
+
+/// Every variable whose name starts with `prefix`, keyed by the rest of the
+/// name (e.g. `FEATURE_X_ENABLED` becomes `X_ENABLED` for `FEATURE_`).
+///
+/// Variables named exactly `prefix` are skipped, as are values that aren't
+/// valid unicode.
+pub fn get_all_with_prefix(prefix: &str) -> HashMap<String, String> {
+    get_all_with_prefix_from(&ProcessEnv, prefix)
+}
+
+pub(crate) fn get_all_with_prefix_from(
+    source: &impl EnvSource,
+    prefix: &str,
+) -> HashMap<String, String> {
+    source
+        .keys()
+        .into_iter()
+        .filter_map(|key| {
+            let name = key.strip_prefix(prefix).filter(|name| !name.is_empty())?;
+            Some((name.to_string(), source.get(&key)?))
+        })
+        .collect()
+}
+
+/// How [`parse_memory_size_with`] interprets SI-style units such as `KB`.
+///
+/// IEC units (`KiB`, `MiB`, ... and the Kubernetes-style `Ki`, `Mi`, ...) are
+/// always powers of 1024.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MemoryUnits {
+    /// `KB`, `MB`, `GB` and `TB` are powers of 1024, as in most configs.
+    #[default]
+    Binary,
+    /// `KB`, `MB`, `GB` and `TB` are powers of 1000, as in Kubernetes and SI.
+    Decimal,
+}
+
+/// Parse memory size (e.g. 10MB, 512KB, 1.5GB, `512 MB`, 256Mi)
+///
+/// Accepts `B`, `K`/`KB`, `M`/`MB`, `G`/`GB`, `T`/`TB` (powers of 1024, see
+/// [`MemoryUnits`]) and `Ki`/`KiB` through `Ti`/`TiB`. Units are
+/// case-insensitive and may be separated from the number by whitespace;
+/// without a unit the number is in bytes. Fractional sizes are rounded down
+/// to a whole byte
+pub fn parse_memory_size(input: &str) -> Result<ByteSize, EnvError> {
+    parse_memory_size_with(input, MemoryUnits::Binary)
+}
+
+/// Like [`parse_memory_size`], choosing what SI-style units mean
+pub fn parse_memory_size_with(input: &str, units: MemoryUnits) -> Result<ByteSize, EnvError> {
+    let bytes = parse_memory_bytes(input, units)?;
+    u64::try_from(bytes)
+        .map(ByteSize)
+        .map_err(|_| memory_overflow(input))
+}
+
+/// Like [`parse_memory_size`], returning a byte count that can exceed
+/// `u64::MAX` (16 EiB), e.g. for totals across a fleet
+pub fn parse_memory_size_u128(input: &str) -> Result<u128, EnvError> {
+    parse_memory_bytes(input, MemoryUnits::Binary)
+}
 
-    let number: usize = num_part.parse().map_err(|_| EnvError::ParseError {
+fn memory_overflow(input: &str) -> EnvError {
+    EnvError::Overflow {
         key: "memory_size".to_string(),
-        value: input.clone(),
-    })?;
+        value: input.trim().to_string(),
+    }
+}
+
+fn parse_memory_bytes(input: &str, units: MemoryUnits) -> Result<u128, EnvError> {
+    let trimmed = input.trim();
+    let invalid = |reason: String| EnvError::Invalid {
+        key: "memory_size".to_string(),
+        reason,
+    };
+
+    let split = trimmed
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(trimmed.len());
+    let (number, unit) = trimmed.split_at(split);
+    let si_base: u64 = match units {
+        MemoryUnits::Binary => 1024,
+        MemoryUnits::Decimal => 1000,
+    };
+    let multiplier = match unit.trim_start().to_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" => si_base,
+        "M" | "MB" => si_base.pow(2),
+        "G" | "GB" => si_base.pow(3),
+        "T" | "TB" => si_base.pow(4),
+        "KI" | "KIB" => 1 << 10,
+        "MI" | "MIB" => 1 << 20,
+        "GI" | "GIB" => 1 << 30,
+        "TI" | "TIB" => 1 << 40,
+        _ => {
+            return Err(invalid(format!(
+                "unknown unit in `{}` (expected B, KB, MB, GB, TB, KiB, MiB, GiB or TiB)",
+                trimmed
+            )));
+        }
+    };
+
+    let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+    if (whole.is_empty() && fraction.is_empty()) || fraction.contains('.') {
+        return Err(invalid(format!(
+            "`{}` is not a memory size (expected a number such as 512MB or 1.5GB)",
+            trimmed
+        )));
+    }
+
+    let fraction = fraction.trim_end_matches('0');
+    let too_large = || memory_overflow(trimmed);
+    let scale = 10u128
+        .checked_pow(fraction.len() as u32)
+        .ok_or_else(too_large)?;
+    let digits: u128 = format!("{}{}", whole, fraction)
+        .parse()
+        .map_err(|_| too_large())?;
+    Ok(digits
+        .checked_mul(u128::from(multiplier))
+        .ok_or_else(too_large)?
+        / scale)
+}
+
+/// Read a required variable and parse it as a memory size (e.g. 512MB)
+pub fn get_memory_size(key: &str) -> Result<ByteSize, EnvError> {
+    get_memory_size_from(&ProcessEnv, key)
+}
+
+/// Like [`get_memory_size`], choosing what SI-style units mean
+///
+/// Example:
+/// ```
+/// use common_utils_rs::env::{get_memory_size_with, test::with_vars, ByteSize, MemoryUnits};
+/// with_vars(&[("POD_MEMORY", "1G"), ("POD_CACHE", "512Mi")], || {
+///     let memory = get_memory_size_with("POD_MEMORY", MemoryUnits::Decimal).unwrap();
+///     assert_eq!(memory, ByteSize(1_000_000_000));
+///     let cache = get_memory_size_with("POD_CACHE", MemoryUnits::Decimal).unwrap();
+///     assert_eq!(cache, ByteSize::mib(512));
+/// });
+/// ```
+pub fn get_memory_size_with(key: &str, units: MemoryUnits) -> Result<ByteSize, EnvError> {
+    get_memory_size_with_from(&ProcessEnv, key, units)
+}
 
-    Ok(number * multiplier)
+/// Like [`get_memory_size`], returning `default` when the variable is unset or
+/// cannot be parsed
+pub fn get_memory_size_or_default(key: &str, default: ByteSize) -> ByteSize {
+    let value = get_memory_size(key).unwrap_or(default);
+    access::record_default(key, || default.to_string());
+    value
+}
+
+pub(crate) fn get_memory_size_from(
+    source: &impl EnvSource,
+    key: &str,
+) -> Result<ByteSize, EnvError> {
+    get_memory_size_with_from(source, key, MemoryUnits::Binary)
+}
+
+pub(crate) fn get_memory_size_with_from(
+    source: &impl EnvSource,
+    key: &str,
+    units: MemoryUnits,
+) -> Result<ByteSize, EnvError> {
+    let value = get_required_from(source, key)?;
+    parse_memory_size_value(key, &value, units)
+}
+
+/// Parse `value` as [`parse_memory_size_with`] does, reporting errors
+/// against `key`.
+pub(crate) fn parse_memory_size_value(
+    key: &str,
+    value: &str,
+    units: MemoryUnits,
+) -> Result<ByteSize, EnvError> {
+    parse_memory_size_with(value, units).map_err(|e| match e {
+        EnvError::Invalid { reason, .. } => EnvError::Invalid {
+            key: key.to_string(),
+            reason,
+        },
+        EnvError::Overflow { value, .. } => EnvError::Overflow {
+            key: key.to_string(),
+            value,
+        },
+        other => other,
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::env;
-
-    fn reset_env(key: &str) {
-        unsafe {
-            env::remove_var(key);
-        }
-    }
+    use crate::env::test::{with_vars, without_vars};
 
     #[test]
     fn test_get_required_success() {
-        unsafe {
-            env::set_var("REQUIRED_KEY", "value");
+        with_vars(&[("REQUIRED_KEY", "value")], || {
             assert_eq!(get_required("REQUIRED_KEY").unwrap(), "value");
-            reset_env("REQUIRED_KEY");
-        }
+        });
     }
 
     #[test]
     fn test_get_required_missing() {
-        reset_env("MISSING_KEY");
-        let result = get_required("MISSING_KEY");
-        assert!(matches!(result, Err(EnvError::Missing(_))));
+        without_vars(&["MISSING_KEY"], || {
+            let result = get_required("MISSING_KEY");
+            assert!(matches!(result, Err(EnvError::Missing(_))));
+        });
+    }
+
+    #[test]
+    fn test_get_nonempty() {
+        with_vars(&[("NONEMPTY_SET", "v"), ("NONEMPTY_EMPTY", "")], || {
+            assert_eq!(get_nonempty("NONEMPTY_SET").unwrap(), "v");
+            assert!(matches!(
+                get_nonempty("NONEMPTY_EMPTY"),
+                Err(EnvError::Missing(key)) if key == "NONEMPTY_EMPTY"
+            ));
+            assert_eq!(get_required("NONEMPTY_EMPTY").unwrap(), "");
+        });
     }
 
     #[test]
     fn test_get_or_default() {
-        reset_env("DEFAULT_KEY");
-        assert_eq!(get_or_default("DEFAULT_KEY", "default"), "default");
-        unsafe {
-            env::set_var("DEFAULT_KEY", "value");
+        without_vars(&["DEFAULT_KEY"], || {
+            assert_eq!(get_or_default("DEFAULT_KEY", "default"), "default");
+        });
+        with_vars(&[("DEFAULT_KEY", "value")], || {
             assert_eq!(get_or_default("DEFAULT_KEY", "default"), "value");
-            reset_env("DEFAULT_KEY");
-        }
+        });
     }
 
     #[test]
     fn test_get_parsed_success() {
-        unsafe {
-            env::set_var("PARSE_KEY", "42");
+        with_vars(&[("PARSE_KEY", "42")], || {
             let result: i32 = get_parsed("PARSE_KEY").unwrap();
             assert_eq!(result, 42);
-            reset_env("PARSE_KEY");
-        }
+        });
     }
 
     #[test]
     fn test_get_parsed_failure() {
-        unsafe {
-            env::set_var("PARSE_BAD", "abc");
+        with_vars(&[("PARSE_BAD", "abc")], || {
             let result: Result<i32, _> = get_parsed("PARSE_BAD");
             assert!(matches!(result, Err(EnvError::ParseError { .. })));
-            reset_env("PARSE_BAD");
-        }
+        });
+    }
+
+    #[test]
+    fn test_get_parsed_failure_keeps_expected_type_and_source() {
+        use std::error::Error;
+
+        with_vars(&[("PARSE_BAD_PORT", "http")], || {
+            let err = get_parsed::<u16>("PARSE_BAD_PORT").unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "Failed to parse environment variable `PARSE_BAD_PORT` as u16: http (invalid digit found in string)"
+            );
+            assert!(
+                err.source()
+                    .is_some_and(|source| source.is::<std::num::ParseIntError>())
+            );
+        });
+        let err = EnvError::parse_error("KEY", "value");
+        assert_eq!(
+            err.to_string(),
+            "Failed to parse environment variable `KEY`: value"
+        );
+        assert!(err.source().is_none());
+    }
+
+    #[test]
+    fn test_type_name_strips_paths() {
+        assert_eq!(type_name::<u16>(), "u16");
+        assert_eq!(type_name::<String>(), "String");
+        assert_eq!(type_name::<Vec<std::net::SocketAddr>>(), "Vec<SocketAddr>");
     }
 
     #[test]
     fn test_get_parsed_or_default() {
-        reset_env("PARSE_DEFAULT");
-        let value: u64 = get_parsed_or_default("PARSE_DEFAULT", 100);
-        assert_eq!(value, 100);
-        unsafe {
-            env::set_var("PARSE_DEFAULT", "200");
+        without_vars(&["PARSE_DEFAULT"], || {
+            let value: u64 = get_parsed_or_default("PARSE_DEFAULT", 100);
+            assert_eq!(value, 100);
+        });
+        with_vars(&[("PARSE_DEFAULT", "200")], || {
             let value: u64 = get_parsed_or_default("PARSE_DEFAULT", 100);
             assert_eq!(value, 200);
-            reset_env("PARSE_DEFAULT");
+        });
+
+        // The error is discarded, so `FromStr` types with `type Err = ()` work too.
+        #[derive(Debug, PartialEq)]
+        struct Even(u64);
+        impl FromStr for Even {
+            type Err = ();
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s.parse() {
+                    Ok(n) if n % 2 == 0 => Ok(Even(n)),
+                    _ => Err(()),
+                }
+            }
+        }
+        with_vars(&[("PARSE_DEFAULT", "3")], || {
+            assert_eq!(get_parsed_or_default("PARSE_DEFAULT", Even(0)), Even(0));
+        });
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Mode {
+        Debug,
+        Release,
+    }
+
+    impl FromStr for Mode {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            match s {
+                "debug" => Ok(Mode::Debug),
+                "release" => Ok(Mode::Release),
+                other => Err(format!(
+                    "unknown mode `{}`, expected debug or release",
+                    other
+                )),
+            }
         }
     }
 
+    #[test]
+    fn test_get_enum() {
+        with_vars(&[("ENUM_KEY", " release ")], || {
+            assert_eq!(get_enum::<Mode>("ENUM_KEY").unwrap(), Mode::Release);
+        });
+        with_vars(&[("ENUM_KEY", "fast")], || {
+            match get_enum::<Mode>("ENUM_KEY") {
+                Err(EnvError::Invalid { key, reason }) => {
+                    assert_eq!(key, "ENUM_KEY");
+                    assert!(reason.contains("expected debug or release"));
+                }
+                other => panic!("unexpected result: {:?}", other),
+            }
+        });
+    }
+
+    #[test]
+    fn test_get_one_of() {
+        let allowed = ["json", "text", "pretty"];
+        with_vars(&[("ONE_OF_KEY", "JSON")], || {
+            assert_eq!(get_one_of("ONE_OF_KEY", &allowed).unwrap(), "json");
+        });
+        with_vars(&[("ONE_OF_KEY", "xml")], || {
+            match get_one_of("ONE_OF_KEY", &allowed) {
+                Err(err @ EnvError::Invalid { .. }) => {
+                    let message = err.to_string();
+                    assert!(message.contains("ONE_OF_KEY"));
+                    assert!(message.contains("`xml` is not one of: json, text, pretty"));
+                }
+                other => panic!("unexpected result: {:?}", other),
+            }
+        });
+    }
+
     #[test]
     fn test_get_bool_true_values() {
-        unsafe {
-            let trues = ["true", "1", "yes", "on"];
-            for &v in &trues {
-                env::set_var("BOOL_KEY", v);
+        let trues = ["true", "1", "yes", "on"];
+        for &v in &trues {
+            with_vars(&[("BOOL_KEY", v)], || {
                 assert!(get_bool("BOOL_KEY", false));
-            }
-            reset_env("BOOL_KEY");
+            });
         }
     }
 
     #[test]
     fn test_get_bool_false_values() {
-        unsafe {
-            let falses = ["false", "0", "no", "off", "random"];
-            for &v in &falses {
-                env::set_var("BOOL_KEY", v);
+        let falses = ["false", "0", "no", "off", "random"];
+        for &v in &falses {
+            with_vars(&[("BOOL_KEY", v)], || {
                 assert!(!get_bool("BOOL_KEY", false));
-            }
-            reset_env("BOOL_KEY");
+            });
         }
     }
 
     #[test]
     fn test_get_bool_default() {
-        reset_env("BOOL_DEFAULT");
-        assert_eq!(get_bool("BOOL_DEFAULT", true), true);
-        assert_eq!(get_bool("BOOL_DEFAULT", false), false);
+        without_vars(&["BOOL_DEFAULT"], || {
+            assert!(get_bool("BOOL_DEFAULT", true));
+            assert!(!get_bool("BOOL_DEFAULT", false));
+        });
+    }
+
+    #[test]
+    fn test_get_bool_strict_recognized_values() {
+        let values = [
+            ("true", true),
+            ("YES", true),
+            ("On", true),
+            ("1", true),
+            ("enabled", true),
+            ("false", false),
+            ("no", false),
+            ("OFF", false),
+            ("0", false),
+            ("Disabled", false),
+        ];
+        for (value, expected) in values {
+            with_vars(&[("BOOL_STRICT", value)], || {
+                assert_eq!(get_bool_strict("BOOL_STRICT").unwrap(), expected);
+            });
+        }
+    }
+
+    #[test]
+    fn test_get_bool_strict_rejects_garbage() {
+        with_vars(&[("BOOL_STRICT_BAD", "maybe")], || {
+            match get_bool_strict("BOOL_STRICT_BAD") {
+                Err(EnvError::Invalid { key, reason }) => {
+                    assert_eq!(key, "BOOL_STRICT_BAD");
+                    assert!(reason.contains("maybe"));
+                    assert!(reason.contains("enabled/disabled"));
+                }
+                other => panic!("unexpected result: {:?}", other),
+            }
+        });
+        without_vars(&["BOOL_STRICT_BAD"], || {
+            assert!(matches!(
+                get_bool_strict("BOOL_STRICT_BAD"),
+                Err(EnvError::Missing(_))
+            ));
+        });
+    }
+
+    #[test]
+    fn test_get_bool_opt() {
+        let vars = [
+            ("BOOL_OPT_ON", "Enabled"),
+            ("BOOL_OPT_OFF", "0"),
+            ("BOOL_OPT_EMPTY", ""),
+            ("BOOL_OPT_BAD", "sometimes"),
+        ];
+        with_vars(&vars, || {
+            assert_eq!(get_bool_opt("BOOL_OPT_ON").unwrap(), Some(true));
+            assert_eq!(get_bool_opt("BOOL_OPT_OFF").unwrap(), Some(false));
+            assert_eq!(get_bool_opt("BOOL_OPT_EMPTY").unwrap(), None);
+            assert!(matches!(
+                get_bool_opt("BOOL_OPT_BAD"),
+                Err(EnvError::Invalid { .. })
+            ));
+        });
+        without_vars(&["BOOL_OPT_UNSET"], || {
+            assert_eq!(get_bool_opt("BOOL_OPT_UNSET").unwrap(), None);
+        });
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_unicode_values() {
+        use crate::env::test::ScopedEnv;
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let raw = OsStr::from_bytes(b"/srv/caf\xe9");
+        let mut scope = ScopedEnv::new();
+        scope
+            .set("NOT_UNICODE_KEY", raw)
+            .remove("NOT_UNICODE_UNSET");
+
+        assert!(matches!(
+            get_required("NOT_UNICODE_KEY"),
+            Err(EnvError::NotUnicode(key)) if key == "NOT_UNICODE_KEY"
+        ));
+        assert_eq!(get_required_os("NOT_UNICODE_KEY").unwrap(), raw);
+        assert_eq!(
+            crate::env::get_path("NOT_UNICODE_KEY").unwrap().as_os_str(),
+            raw
+        );
+        assert!(matches!(
+            get_required_os("NOT_UNICODE_UNSET"),
+            Err(EnvError::Missing(_))
+        ));
     }
 
     #[test]
     fn test_get_list_success() {
-        unsafe {
-            env::set_var("LIST_KEY", "a, b ,c");
+        with_vars(&[("LIST_KEY", "a, b ,c")], || {
             let list = get_list("LIST_KEY").unwrap();
             assert_eq!(list, vec!["a", "b", "c"]);
-            reset_env("LIST_KEY");
-        }
+        });
     }
 
     #[test]
     fn test_get_list_missing() {
-        reset_env("LIST_MISSING");
-        let list = get_list("LIST_MISSING");
-        assert!(matches!(list, Err(EnvError::Missing(_))));
+        without_vars(&["LIST_MISSING"], || {
+            let list = get_list("LIST_MISSING");
+            assert!(matches!(list, Err(EnvError::Missing(_))));
+        });
+    }
+
+    #[test]
+    fn test_get_list_with_options() {
+        let vars = [
+            ("LIST_PATH", "/usr/bin::/bin:/usr/bin:"),
+            ("LIST_SEMI", " a ; b;;c "),
+        ];
+        with_vars(&vars, || {
+            let path = ListOptions {
+                delimiter: ":",
+                skip_empty: true,
+                dedup: true,
+                ..Default::default()
+            };
+            assert_eq!(
+                get_list_with("LIST_PATH", &path).unwrap(),
+                vec!["/usr/bin", "/bin"]
+            );
+
+            let raw = ListOptions {
+                delimiter: ";",
+                trim: false,
+                ..Default::default()
+            };
+            assert_eq!(
+                get_list_with("LIST_SEMI", &raw).unwrap(),
+                vec![" a ", " b", "", "c "]
+            );
+        });
+        with_vars(&[("LIST_PORTS_SEMI", "80; ;443")], || {
+            let options = ListOptions {
+                delimiter: ";",
+                skip_empty: true,
+                ..Default::default()
+            };
+            let ports: Vec<u16> = get_parsed_list_with("LIST_PORTS_SEMI", &options).unwrap();
+            assert_eq!(ports, vec![80, 443]);
+        });
+    }
+
+    #[test]
+    fn test_get_parsed_list_success() {
+        with_vars(&[("PORTS_KEY", "80, 443,8080")], || {
+            let ports: Vec<u16> = get_parsed_list("PORTS_KEY").unwrap();
+            assert_eq!(ports, vec![80, 443, 8080]);
+        });
+    }
+
+    #[test]
+    fn test_get_parsed_list_reports_index() {
+        with_vars(&[("PORTS_BAD", "80,http,443")], || {
+            match get_parsed_list::<u16>("PORTS_BAD") {
+                Err(EnvError::ListItem {
+                    key,
+                    index,
+                    value,
+                    source,
+                }) => {
+                    assert_eq!(key, "PORTS_BAD");
+                    assert_eq!(index, 1);
+                    assert_eq!(value, "http");
+                    assert!(source.is_some());
+                }
+                other => panic!("unexpected result: {:?}", other),
+            }
+        });
+    }
+
+    #[test]
+    fn test_get_map_success() {
+        with_vars(&[("MAP_KEY", "a=1, b = true,c=hello=world,")], || {
+            let map = get_map("MAP_KEY").unwrap();
+            assert_eq!(map.len(), 3);
+            assert_eq!(map["a"], "1");
+            assert_eq!(map["b"], "true");
+            assert_eq!(map["c"], "hello=world");
+        });
+    }
+
+    #[test]
+    fn test_get_map_with_custom_separators() {
+        with_vars(&[("MAP_CUSTOM", "tier:gold; region:eu")], || {
+            let map = get_map_with("MAP_CUSTOM", ";", ":").unwrap();
+            assert_eq!(map["tier"], "gold");
+            assert_eq!(map["region"], "eu");
+        });
+    }
+
+    #[test]
+    fn test_get_map_invalid_pair() {
+        with_vars(&[("MAP_BAD", "a=1,oops")], || match get_map("MAP_BAD") {
+            Err(EnvError::ParseError {
+                key,
+                value,
+                expected,
+                ..
+            }) => {
+                assert_eq!(key, "MAP_BAD");
+                assert_eq!(value, "oops");
+                assert_eq!(expected.as_deref(), Some("map entry"));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        });
+    }
+
+    #[test]
+    fn test_get_weighted_list() {
+        with_vars(
+            &[
+                (
+                    "WEIGHTED_OK",
+                    "backend-a:3, backend-b:1,canary, host:8080:0",
+                ),
+                ("WEIGHTED_BAD_WEIGHT", "a:3,b:lots"),
+                ("WEIGHTED_NO_NAME", "a:1,:2"),
+                ("WEIGHTED_DUPLICATE", "a:1,a:2"),
+                ("WEIGHTED_ZERO", "a:0,b:0"),
+            ],
+            || {
+                assert_eq!(
+                    get_weighted_list("WEIGHTED_OK").unwrap(),
+                    vec![
+                        ("backend-a".to_string(), 3),
+                        ("backend-b".to_string(), 1),
+                        ("canary".to_string(), 1),
+                        ("host:8080".to_string(), 0),
+                    ]
+                );
+                assert!(matches!(
+                    get_weighted_list("WEIGHTED_BAD_WEIGHT"),
+                    Err(EnvError::ListItem { index: 1, .. })
+                ));
+                assert!(matches!(
+                    get_weighted_list("WEIGHTED_NO_NAME"),
+                    Err(EnvError::ListItem { index: 1, .. })
+                ));
+                assert!(matches!(
+                    get_weighted_list("WEIGHTED_DUPLICATE"),
+                    Err(EnvError::Invalid { .. })
+                ));
+                assert!(matches!(
+                    get_weighted_list("WEIGHTED_ZERO"),
+                    Err(EnvError::Invalid { .. })
+                ));
+            },
+        );
+    }
+
+    #[test]
+    fn test_get_all_with_prefix() {
+        with_vars(
+            &[
+                ("ALLPREFIX_X_ENABLED", "true"),
+                ("ALLPREFIX_Y_LIMIT", "5"),
+                ("ALLPREFIX_", "skipped"),
+                ("OTHER_ALLPREFIX_Z", "skipped"),
+            ],
+            || {
+                let all = get_all_with_prefix("ALLPREFIX_");
+                assert_eq!(all.len(), 2);
+                assert_eq!(all["X_ENABLED"], "true");
+                assert_eq!(all["Y_LIMIT"], "5");
+                assert!(get_all_with_prefix("NO_SUCH_ALLPREFIX_").is_empty());
+            },
+        );
     }
 
     #[test]
     fn test_parse_memory_size_kb_mb_gb_and_plain() {
-        assert_eq!(parse_memory_size("1KB").unwrap(), 1024);
-        assert_eq!(parse_memory_size("1MB").unwrap(), 1024 * 1024);
-        assert_eq!(parse_memory_size("1GB").unwrap(), 1024 * 1024 * 1024);
-        assert_eq!(parse_memory_size("123").unwrap(), 123);
+        assert_eq!(parse_memory_size("1KB").unwrap(), ByteSize(1024));
+        assert_eq!(parse_memory_size("1MB").unwrap(), ByteSize(1024 * 1024));
+        assert_eq!(
+            parse_memory_size("1GB").unwrap(),
+            ByteSize(1024 * 1024 * 1024)
+        );
+        assert_eq!(parse_memory_size("123").unwrap(), ByteSize(123));
+    }
+
+    #[test]
+    fn test_parse_memory_size_fractions_and_spacing() {
+        assert_eq!(parse_memory_size("1.5GB").unwrap(), ByteSize::mib(1536));
+        assert_eq!(parse_memory_size("0.5MB").unwrap(), ByteSize::kib(512));
+        assert_eq!(parse_memory_size(".5 kb").unwrap(), ByteSize(512));
+        assert_eq!(parse_memory_size(" 512 MB ").unwrap(), ByteSize::mib(512));
+        assert_eq!(parse_memory_size("0.1KB").unwrap(), ByteSize(102));
+        assert_eq!(parse_memory_size("2.").unwrap(), ByteSize(2));
+    }
+
+    #[test]
+    fn test_parse_memory_size_units() {
+        assert_eq!(parse_memory_size("42B").unwrap(), ByteSize(42));
+        assert_eq!(parse_memory_size("256Mi").unwrap(), ByteSize::mib(256));
+        assert_eq!(parse_memory_size("2 GiB").unwrap(), ByteSize::gib(2));
+        assert_eq!(parse_memory_size("1TB").unwrap(), ByteSize::tib(1));
+        assert_eq!(parse_memory_size("1tib").unwrap(), ByteSize::tib(1));
+        assert_eq!(parse_memory_size("4k").unwrap(), ByteSize::kib(4));
+
+        let decimal = |input| parse_memory_size_with(input, MemoryUnits::Decimal).unwrap();
+        assert_eq!(decimal("1KB"), ByteSize(1000));
+        assert_eq!(decimal("1.5M"), ByteSize(1_500_000));
+        assert_eq!(decimal("2TB"), ByteSize(2_000_000_000_000));
+        assert_eq!(decimal("1KiB"), ByteSize(1024));
     }
 
     #[test]
     fn test_parse_memory_size_invalid() {
         let result = parse_memory_size("abcMB");
-        assert!(matches!(result, Err(EnvError::ParseError { .. })));
+        assert!(matches!(result, Err(EnvError::Invalid { .. })));
+        match parse_memory_size("12XB") {
+            Err(EnvError::Invalid { reason, .. }) => assert!(reason.contains("KB, MB, GB, TB")),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(parse_memory_size("1.2.3MB").is_err());
+        assert!(parse_memory_size(".").is_err());
+        assert!(parse_memory_size("").is_err());
+    }
+
+    #[test]
+    fn test_parse_memory_size_overflow() {
+        assert_eq!(
+            parse_memory_size("16777215TiB").unwrap(),
+            ByteSize(16_777_215 << 40)
+        );
+        match parse_memory_size(" 99999999999GB ") {
+            Err(EnvError::Overflow { key, value }) => {
+                assert_eq!(key, "memory_size");
+                assert_eq!(value, "99999999999GB");
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert_eq!(
+            parse_memory_size_u128("99999999999GB").unwrap(),
+            99_999_999_999u128 << 30
+        );
+        assert!(matches!(
+            parse_memory_size_u128(&"9".repeat(60)),
+            Err(EnvError::Overflow { .. })
+        ));
+        assert_eq!(
+            parse_memory_size(&format!("1.{}KB", "0".repeat(60))).unwrap(),
+            ByteSize::kib(1)
+        );
+        with_vars(&[("MEMORY_HUGE", "20000000TB")], || {
+            assert!(matches!(
+                get_memory_size("MEMORY_HUGE"),
+                Err(EnvError::Overflow { key, .. }) if key == "MEMORY_HUGE"
+            ));
+        });
+    }
+
+    #[test]
+    fn test_get_memory_size() {
+        with_vars(&[("MEMORY_KEY", "512MB")], || {
+            assert_eq!(get_memory_size("MEMORY_KEY").unwrap(), ByteSize::mib(512));
+        });
+        with_vars(&[("MEMORY_KEY", "big")], || {
+            assert!(matches!(
+                get_memory_size("MEMORY_KEY"),
+                Err(EnvError::Invalid { key, .. }) if key == "MEMORY_KEY"
+            ));
+        });
+    }
+
+    #[test]
+    fn test_get_memory_size_or_default() {
+        with_vars(
+            &[("MEMORY_OR_SET", "1GB"), ("MEMORY_OR_BAD", "big")],
+            || {
+                let default = ByteSize::mib(64);
+                assert_eq!(
+                    get_memory_size_or_default("MEMORY_OR_SET", default),
+                    ByteSize::gib(1)
+                );
+                assert_eq!(
+                    get_memory_size_or_default("MEMORY_OR_BAD", default),
+                    default
+                );
+                assert_eq!(
+                    get_memory_size_or_default("MEMORY_OR_UNSET", default),
+                    default
+                );
+            },
+        );
     }
 }
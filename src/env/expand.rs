@@ -0,0 +1,250 @@
+use super::env::{EnvError, get_required_from};
+use super::source::{EnvSource, ProcessEnv};
+
+/// What to do when a value references a variable that is not set.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UndefinedPolicy {
+    /// Fail with [`EnvError::Invalid`].
+    #[default]
+    Error,
+    /// Substitute an empty string.
+    Empty,
+    /// Leave the reference untouched, e.g. `${NAME}`.
+    Keep,
+}
+
+/// Read a variable and expand `${OTHER}` and `$OTHER` references in it.
+///
+/// References are expanded recursively. `${NAME:-fallback}` supplies an
+/// inline default, which may itself contain references such as
+/// `${PRIMARY:-${SECONDARY}}`, and `$$` produces a literal `$`. Undefined
+/// references are an error; see [`get_expanded_with`] to change that.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::env::{get_expanded, test::with_vars};
+/// let vars = [("DB_HOST", "db"), ("DATABASE_URL", "postgres://${DB_HOST}:${DB_PORT:-5432}/app")];
+/// with_vars(&vars, || {
+///     assert_eq!(get_expanded("DATABASE_URL").unwrap(), "postgres://db:5432/app");
+/// });
+/// ```
+pub fn get_expanded(key: &str) -> Result<String, EnvError> {
+    get_expanded_with(key, UndefinedPolicy::Error)
+}
+
+/// Like [`get_expanded`] with a custom policy for undefined references.
+pub fn get_expanded_with(key: &str, undefined: UndefinedPolicy) -> Result<String, EnvError> {
+    get_expanded_with_from(&ProcessEnv, key, undefined)
+}
+
+/// References are resolved through `source` as well, without any prefix.
+pub(crate) fn get_expanded_with_from(
+    source: &impl EnvSource,
+    key: &str,
+    undefined: UndefinedPolicy,
+) -> Result<String, EnvError> {
+    let value = get_required_from(source, key)?;
+    let mut stack = vec![key.to_string()];
+    expand(source, &value, &mut stack, undefined)
+}
+
+fn expand(
+    source: &impl EnvSource,
+    value: &str,
+    stack: &mut Vec<String>,
+    undefined: UndefinedPolicy,
+) -> Result<String, EnvError> {
+    let invalid = |stack: &[String], reason: String| EnvError::Invalid {
+        key: stack[0].clone(),
+        reason,
+    };
+
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(pos) = rest.find('$') {
+        out.push_str(&rest[..pos]);
+        rest = &rest[pos + 1..];
+
+        let (name, fallback, raw_len) = if let Some(inner) = rest.strip_prefix('{') {
+            let end = closing_brace(inner)
+                .ok_or_else(|| invalid(stack, "unterminated `${` reference".to_string()))?;
+            let body = &inner[..end];
+            let (name, fallback) = match body.split_once(":-") {
+                Some((name, fallback)) => (name, Some(fallback)),
+                None => (body, None),
+            };
+            (name, fallback, end + 2)
+        } else if rest.starts_with('$') {
+            out.push('$');
+            rest = &rest[1..];
+            continue;
+        } else {
+            let len = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            if len == 0 {
+                out.push('$');
+                continue;
+            }
+            (&rest[..len], None, len)
+        };
+
+        if name.is_empty() {
+            return Err(invalid(stack, "empty variable reference".to_string()));
+        }
+
+        if stack.iter().any(|k| k == name) {
+            let mut chain = stack.clone();
+            chain.push(name.to_string());
+            return Err(invalid(
+                stack,
+                format!("cyclic reference: {}", chain.join(" -> ")),
+            ));
+        }
+
+        match source.get(name) {
+            Some(nested) => {
+                stack.push(name.to_string());
+                out.push_str(&expand(source, &nested, stack, undefined)?);
+                stack.pop();
+            }
+            None => match (fallback, undefined) {
+                (Some(fallback), _) => out.push_str(&expand(source, fallback, stack, undefined)?),
+                (None, UndefinedPolicy::Error) => {
+                    return Err(invalid(
+                        stack,
+                        format!("references undefined variable `{}`", name),
+                    ));
+                }
+                (None, UndefinedPolicy::Empty) => {}
+                (None, UndefinedPolicy::Keep) => {
+                    out.push('$');
+                    out.push_str(&rest[..raw_len]);
+                }
+            },
+        }
+
+        rest = &rest[raw_len..];
+    }
+
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// The offset of the `}` closing a `${` whose body starts `inner`, skipping
+/// over nested `${...}` references and `$$` escapes.
+fn closing_brace(inner: &str) -> Option<usize> {
+    let bytes = inner.as_bytes();
+    let mut depth = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match (bytes[i], bytes.get(i + 1)) {
+            (b'$', Some(b'{')) => {
+                depth += 1;
+                i += 1;
+            }
+            (b'$', Some(b'$')) => i += 1,
+            (b'}', _) if depth == 0 => return Some(i),
+            (b'}', _) => depth -= 1,
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test::{ScopedEnv, with_vars};
+
+    #[test]
+    fn test_get_expanded_braced_and_bare() {
+        let vars = [
+            ("EXPAND_HOST", "db.local"),
+            ("EXPAND_PORT", "5432"),
+            ("EXPAND_URL", "postgres://${EXPAND_HOST}:$EXPAND_PORT/app"),
+        ];
+        with_vars(&vars, || {
+            assert_eq!(
+                get_expanded("EXPAND_URL").unwrap(),
+                "postgres://db.local:5432/app"
+            );
+        });
+    }
+
+    #[test]
+    fn test_get_expanded_recursive_defaults_and_escapes() {
+        let vars = [
+            ("EXPAND_BASE", "/srv"),
+            ("EXPAND_DATA", "${EXPAND_BASE}/data"),
+            (
+                "EXPAND_PATH",
+                "${EXPAND_DATA}/${EXPAND_UNSET:-cache} costs $$5 $",
+            ),
+        ];
+        with_vars(&vars, || {
+            assert_eq!(
+                get_expanded("EXPAND_PATH").unwrap(),
+                "/srv/data/cache costs $5 $"
+            );
+        });
+    }
+
+    #[test]
+    fn test_get_expanded_nested_defaults() {
+        let mut scope = ScopedEnv::new();
+        scope
+            .set("EXPAND_NESTED", "${EXPAND_FIRST:-${EXPAND_SECOND:-none}}/x")
+            .set("EXPAND_OPEN", "${EXPAND_FIRST:-${EXPAND_SECOND}")
+            .remove("EXPAND_FIRST")
+            .remove("EXPAND_SECOND");
+
+        assert_eq!(get_expanded("EXPAND_NESTED").unwrap(), "none/x");
+        scope.set("EXPAND_SECOND", "second");
+        assert_eq!(get_expanded("EXPAND_NESTED").unwrap(), "second/x");
+        scope.set("EXPAND_FIRST", "first");
+        assert_eq!(get_expanded("EXPAND_NESTED").unwrap(), "first/x");
+
+        match get_expanded("EXPAND_OPEN") {
+            Err(EnvError::Invalid { reason, .. }) => {
+                assert_eq!(reason, "unterminated `${` reference")
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_get_expanded_detects_cycles() {
+        let vars = [("EXPAND_A", "x${EXPAND_B}"), ("EXPAND_B", "$EXPAND_A")];
+        with_vars(&vars, || match get_expanded("EXPAND_A") {
+            Err(EnvError::Invalid { key, reason }) => {
+                assert_eq!(key, "EXPAND_A");
+                assert!(reason.contains("EXPAND_A -> EXPAND_B -> EXPAND_A"));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        });
+    }
+
+    #[test]
+    fn test_get_expanded_undefined_policies() {
+        let mut scope = ScopedEnv::new();
+        scope
+            .set("EXPAND_REF", "a-${EXPAND_MISSING}-$EXPAND_MISSING")
+            .remove("EXPAND_MISSING");
+
+        assert!(matches!(
+            get_expanded("EXPAND_REF"),
+            Err(EnvError::Invalid { .. })
+        ));
+        assert_eq!(
+            get_expanded_with("EXPAND_REF", UndefinedPolicy::Empty).unwrap(),
+            "a--"
+        );
+        assert_eq!(
+            get_expanded_with("EXPAND_REF", UndefinedPolicy::Keep).unwrap(),
+            "a-${EXPAND_MISSING}-$EXPAND_MISSING"
+        );
+    }
+}
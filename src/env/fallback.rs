@@ -0,0 +1,265 @@
+use std::str::FromStr;
+
+use super::env::{BoxError, EnvError, parse_value, type_name};
+use super::source::{EnvSource, ProcessEnv};
+
+/// A value together with the variable it was read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Found<T> {
+    pub key: String,
+    pub value: T,
+}
+
+/// Return the first variable in `keys` that is set.
+///
+/// Example:
+/// ```no_run
+/// use common_utils_rs::env::get_first_of;
+/// let found = get_first_of(&["DATABASE_URL", "DB_URL"]).unwrap();
+/// if found.key != "DATABASE_URL" {
+///     eprintln!("{} is deprecated, use DATABASE_URL", found.key);
+/// }
+/// ```
+pub fn get_first_of(keys: &[&str]) -> Result<Found<String>, EnvError> {
+    get_first_of_from(&ProcessEnv, keys)
+}
+
+pub(crate) fn get_first_of_from(
+    source: &impl EnvSource,
+    keys: &[&str],
+) -> Result<Found<String>, EnvError> {
+    keys.iter()
+        .find_map(|key| {
+            source.get(key).map(|value| Found {
+                key: key.to_string(),
+                value,
+            })
+        })
+        .ok_or_else(|| EnvError::Missing(keys.join(" or ")))
+}
+
+/// Like [`get_first_of`], parsing the value with `FromStr`.
+///
+/// Only the first set variable is parsed; a malformed value does not fall
+/// through to later keys.
+pub fn get_first_of_parsed<T>(keys: &[&str]) -> Result<Found<T>, EnvError>
+where
+    T: FromStr,
+    T::Err: Into<BoxError>,
+{
+    parse_found(get_first_of(keys)?)
+}
+
+fn parse_found<T>(found: Found<String>) -> Result<Found<T>, EnvError>
+where
+    T: FromStr,
+    T::Err: Into<BoxError>,
+{
+    match found.value.parse::<T>() {
+        Ok(value) => Ok(Found {
+            key: found.key,
+            value,
+        }),
+        Err(e) => Err(EnvError::parse_error(found.key, found.value)
+            .with_expected(type_name::<T>())
+            .with_source(e)),
+    }
+}
+
+/// Read `key`, defaulting to the value of `fallback_key` when it is unset.
+///
+/// The returned [`Found::key`] records which of the two supplied the value,
+/// and a missing-variable error names both.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::env::{get_or_from, test::with_vars};
+/// with_vars(&[("HOST", "10.0.0.5")], || {
+///     let found = get_or_from("METRICS_HOST", "HOST").unwrap();
+///     assert_eq!((found.key.as_str(), found.value.as_str()), ("HOST", "10.0.0.5"));
+/// });
+/// ```
+pub fn get_or_from(key: &str, fallback_key: &str) -> Result<Found<String>, EnvError> {
+    get_first_of_from(&ProcessEnv, &[key, fallback_key])
+}
+
+/// Like [`get_or_from`], parsing the value with `FromStr`.
+///
+/// A malformed `key` is an error rather than a reason to use `fallback_key`.
+pub fn get_parsed_or_from<T>(key: &str, fallback_key: &str) -> Result<Found<T>, EnvError>
+where
+    T: FromStr,
+    T::Err: Into<BoxError>,
+{
+    parse_found(get_or_from(key, fallback_key)?)
+}
+
+pub(crate) fn get_parsed_or_from_from<T>(
+    source: &impl EnvSource,
+    key: &str,
+    fallback_key: &str,
+) -> Result<Found<T>, EnvError>
+where
+    T: FromStr,
+    T::Err: Into<BoxError>,
+{
+    parse_found(get_first_of_from(source, &[key, fallback_key])?)
+}
+
+/// Read `key`, falling back to deprecated `aliases` in order.
+///
+/// When an alias is used, a warning naming the canonical key is emitted
+/// through `log` and/or `tracing` if those features are enabled.
+///
+/// Example:
+/// ```no_run
+/// use common_utils_rs::env::get_with_aliases;
+/// let url = get_with_aliases("DATABASE_URL", &["DB_URL", "PG_URL"]).unwrap();
+/// ```
+pub fn get_with_aliases(key: &str, aliases: &[&str]) -> Result<String, EnvError> {
+    get_with_aliases_from(&ProcessEnv, key, aliases)
+}
+
+pub(crate) fn get_with_aliases_from(
+    source: &impl EnvSource,
+    key: &str,
+    aliases: &[&str],
+) -> Result<String, EnvError> {
+    if let Some(value) = source.get(key) {
+        return Ok(value);
+    }
+
+    match get_first_of_from(source, aliases) {
+        Ok(found) => {
+            warn_deprecated(&found.key, key);
+            Ok(found.value)
+        }
+        Err(_) => Err(EnvError::Missing(key.to_string())),
+    }
+}
+
+/// Like [`get_with_aliases`], parsing the value with `FromStr`.
+pub fn get_parsed_with_aliases<T>(key: &str, aliases: &[&str]) -> Result<T, EnvError>
+where
+    T: FromStr,
+    T::Err: Into<BoxError>,
+{
+    parse_value(key, get_with_aliases(key, aliases)?)
+}
+
+#[cfg_attr(
+    not(any(feature = "log", feature = "tracing")),
+    allow(unused_variables)
+)]
+fn warn_deprecated(alias: &str, key: &str) {
+    #[cfg(feature = "log")]
+    log::warn!(
+        "environment variable `{}` is deprecated, use `{}` instead",
+        alias,
+        key
+    );
+    #[cfg(feature = "tracing")]
+    tracing::warn!(alias, key, "deprecated environment variable used");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test::{ScopedEnv, without_vars};
+
+    #[test]
+    fn test_get_first_of_prefers_earlier_keys() {
+        let mut scope = ScopedEnv::new();
+        scope
+            .remove("FIRST_NEW")
+            .set("FIRST_LEGACY", "legacy")
+            .set("FIRST_OLDEST", "oldest");
+        let found = get_first_of(&["FIRST_NEW", "FIRST_LEGACY", "FIRST_OLDEST"]).unwrap();
+        assert_eq!(found.key, "FIRST_LEGACY");
+        assert_eq!(found.value, "legacy");
+
+        scope.set("FIRST_NEW", "new");
+        let found = get_first_of(&["FIRST_NEW", "FIRST_LEGACY"]).unwrap();
+        assert_eq!(found.key, "FIRST_NEW");
+    }
+
+    #[test]
+    fn test_get_first_of_missing() {
+        without_vars(&["FIRST_X", "FIRST_Y"], || {
+            match get_first_of(&["FIRST_X", "FIRST_Y"]) {
+                Err(EnvError::Missing(keys)) => assert_eq!(keys, "FIRST_X or FIRST_Y"),
+                other => panic!("unexpected result: {:?}", other),
+            }
+        });
+    }
+
+    #[test]
+    fn test_get_first_of_parsed() {
+        let mut scope = ScopedEnv::new();
+        scope.remove("FIRST_PORT").set("FIRST_LEGACY_PORT", "8080");
+        let found: Found<u16> = get_first_of_parsed(&["FIRST_PORT", "FIRST_LEGACY_PORT"]).unwrap();
+        assert_eq!(found.value, 8080);
+        assert_eq!(found.key, "FIRST_LEGACY_PORT");
+
+        scope.set("FIRST_PORT", "http");
+        let result = get_first_of_parsed::<u16>(&["FIRST_PORT", "FIRST_LEGACY_PORT"]);
+        assert!(matches!(result, Err(EnvError::ParseError { key, .. }) if key == "FIRST_PORT"));
+    }
+
+    #[test]
+    fn test_get_with_aliases() {
+        let mut scope = ScopedEnv::new();
+        scope
+            .remove("ALIAS_URL")
+            .remove("ALIAS_OLD_URL")
+            .set("ALIAS_OLDER_URL", "older");
+        let aliases = ["ALIAS_OLD_URL", "ALIAS_OLDER_URL"];
+        assert_eq!(get_with_aliases("ALIAS_URL", &aliases).unwrap(), "older");
+
+        scope.set("ALIAS_URL", "canonical");
+        assert_eq!(
+            get_with_aliases("ALIAS_URL", &aliases).unwrap(),
+            "canonical"
+        );
+
+        scope.remove("ALIAS_URL").remove("ALIAS_OLDER_URL");
+        assert!(matches!(
+            get_with_aliases("ALIAS_URL", &aliases),
+            Err(EnvError::Missing(k)) if k == "ALIAS_URL"
+        ));
+    }
+
+    #[test]
+    fn test_get_parsed_with_aliases() {
+        let mut scope = ScopedEnv::new();
+        scope.remove("ALIAS_PORT").set("ALIAS_OLD_PORT", "8080");
+        let port: u16 = get_parsed_with_aliases("ALIAS_PORT", &["ALIAS_OLD_PORT"]).unwrap();
+        assert_eq!(port, 8080);
+    }
+
+    #[test]
+    fn test_get_or_from() {
+        let mut scope = ScopedEnv::new();
+        scope
+            .set("OR_FROM_HOST", "10.0.0.5")
+            .remove("OR_FROM_METRICS_HOST")
+            .set("OR_FROM_PORT", "9000")
+            .set("OR_FROM_METRICS_PORT", "9100");
+
+        let host = get_or_from("OR_FROM_METRICS_HOST", "OR_FROM_HOST").unwrap();
+        assert_eq!(host.key, "OR_FROM_HOST");
+        assert_eq!(host.value, "10.0.0.5");
+
+        let port: Found<u16> = get_parsed_or_from("OR_FROM_METRICS_PORT", "OR_FROM_PORT").unwrap();
+        assert_eq!(port.key, "OR_FROM_METRICS_PORT");
+        assert_eq!(port.value, 9100);
+
+        scope.remove("OR_FROM_HOST");
+        match get_or_from("OR_FROM_METRICS_HOST", "OR_FROM_HOST") {
+            Err(EnvError::Missing(keys)) => {
+                assert_eq!(keys, "OR_FROM_METRICS_HOST or OR_FROM_HOST")
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! HTTP header lists such as `X-Api-Key: abc; X-Tenant: foo`.
+use super::env::{EnvError, get_required_from};
+use super::source::{EnvSource, ProcessEnv};
+
+/// Parse `Name: value` headers separated by `;` or newlines, e.g. extra
+/// headers for a proxy or SDK client.
+///
+/// Headers keep their order, and a name may repeat. Names must be HTTP
+/// tokens (letters, digits and ``!#$%&'*+-.^_`|~``) and values may not
+/// contain control characters other than tab. Surrounding whitespace and
+/// empty entries are ignored.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::env::{get_headers, test::with_vars};
+/// with_vars(&[("EXTRA_HEADERS", "X-Api-Key: abc; X-Tenant: foo")], || {
+///     let headers = get_headers("EXTRA_HEADERS").unwrap();
+///     assert_eq!(headers[0], ("X-Api-Key".to_string(), "abc".to_string()));
+///     assert_eq!(headers[1], ("X-Tenant".to_string(), "foo".to_string()));
+/// });
+/// ```
+pub fn get_headers(key: &str) -> Result<Vec<(String, String)>, EnvError> {
+    get_headers_from(&ProcessEnv, key)
+}
+
+pub(crate) fn get_headers_from(
+    source: &impl EnvSource,
+    key: &str,
+) -> Result<Vec<(String, String)>, EnvError> {
+    let value = get_required_from(source, key)?;
+    let mut headers = Vec::new();
+    for (index, item) in value.split([';', '\n']).map(str::trim).enumerate() {
+        if item.is_empty() {
+            continue;
+        }
+        let Some((name, header_value)) = item.split_once(':') else {
+            return Err(EnvError::ListItem {
+                key: key.to_string(),
+                index,
+                value: item.to_string(),
+                source: None,
+            });
+        };
+        let (name, header_value) = (name.trim(), header_value.trim());
+        if name.is_empty() || !name.bytes().all(is_token_byte) {
+            return Err(EnvError::Invalid {
+                key: key.to_string(),
+                reason: format!("`{}` is not a valid header name", name),
+            });
+        }
+        if header_value.chars().any(|c| c.is_control() && c != '\t') {
+            return Err(EnvError::Invalid {
+                key: key.to_string(),
+                reason: format!(
+                    "the value of header `{}` contains a control character",
+                    name
+                ),
+            });
+        }
+        headers.push((name.to_string(), header_value.to_string()));
+    }
+    Ok(headers)
+}
+
+/// Whether `byte` may appear in an HTTP token (RFC 9110, section 5.6.2).
+fn is_token_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&byte)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test::with_vars;
+
+    fn pairs(items: &[(&str, &str)]) -> Vec<(String, String)> {
+        items
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_get_headers() {
+        with_vars(
+            &[
+                ("HEADERS_SIMPLE", " X-Api-Key: abc;X-Tenant:foo ; ;X-Empty:"),
+                (
+                    "HEADERS_LINES",
+                    "Authorization: Bearer t0k:en\nX-Tag: a\nX-Tag: b\n",
+                ),
+            ],
+            || {
+                assert_eq!(
+                    get_headers("HEADERS_SIMPLE").unwrap(),
+                    pairs(&[("X-Api-Key", "abc"), ("X-Tenant", "foo"), ("X-Empty", "")])
+                );
+                assert_eq!(
+                    get_headers("HEADERS_LINES").unwrap(),
+                    pairs(&[
+                        ("Authorization", "Bearer t0k:en"),
+                        ("X-Tag", "a"),
+                        ("X-Tag", "b")
+                    ])
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn test_get_headers_invalid() {
+        with_vars(
+            &[
+                ("HEADERS_NO_COLON", "X-Api-Key: abc; X-Tenant"),
+                ("HEADERS_BAD_NAME", "X Api Key: abc"),
+                ("HEADERS_NO_NAME", ": abc"),
+                ("HEADERS_BAD_VALUE", "X-Api-Key: a\u{7f}b"),
+            ],
+            || {
+                match get_headers("HEADERS_NO_COLON") {
+                    Err(EnvError::ListItem { index, value, .. }) => {
+                        assert_eq!(index, 1);
+                        assert_eq!(value, "X-Tenant");
+                    }
+                    other => panic!("unexpected result: {:?}", other),
+                }
+                match get_headers("HEADERS_BAD_NAME") {
+                    Err(EnvError::Invalid { reason, .. }) => {
+                        assert_eq!(reason, "`X Api Key` is not a valid header name");
+                    }
+                    other => panic!("unexpected result: {:?}", other),
+                }
+                assert!(matches!(
+                    get_headers("HEADERS_NO_NAME"),
+                    Err(EnvError::Invalid { .. })
+                ));
+                assert!(matches!(
+                    get_headers("HEADERS_BAD_VALUE"),
+                    Err(EnvError::Invalid { .. })
+                ));
+            },
+        );
+    }
+}
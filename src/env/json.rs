@@ -0,0 +1,104 @@
+//! JSON documents stored in a single variable (`json` feature).
+use serde::de::DeserializeOwned;
+
+use super::env::{EnvError, get_required_from};
+use super::source::{EnvSource, ProcessEnv};
+
+/// Read a variable and deserialize it as JSON.
+///
+/// Errors name the JSON path of the failure, e.g. `limits.burst`.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::env::{get_json, test::with_vars};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Limits {
+///     rate: u32,
+///     burst: u32,
+/// }
+///
+/// with_vars(&[("LIMITS", r#"{"rate": 10, "burst": 50}"#)], || {
+///     let limits: Limits = get_json("LIMITS").unwrap();
+///     assert_eq!(limits.burst, 50);
+/// });
+/// ```
+pub fn get_json<T>(key: &str) -> Result<T, EnvError>
+where
+    T: DeserializeOwned,
+{
+    get_json_from(&ProcessEnv, key)
+}
+
+pub(crate) fn get_json_from<T>(source: &impl EnvSource, key: &str) -> Result<T, EnvError>
+where
+    T: DeserializeOwned,
+{
+    let value = get_required_from(source, key)?;
+    let deserializer = &mut serde_json::Deserializer::from_str(&value);
+    let parsed: T =
+        serde_path_to_error::deserialize(deserializer).map_err(|error| EnvError::Invalid {
+            key: key.to_string(),
+            reason: format!("invalid JSON at `{}`: {}", error.path(), error.inner()),
+        })?;
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test::with_vars;
+    use serde::Deserialize;
+    use std::collections::HashMap;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Limits {
+        rate: u32,
+        burst: u32,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Config {
+        name: String,
+        limits: Limits,
+    }
+
+    #[test]
+    fn test_get_json_typed() {
+        let json = r#"{"name": "api", "limits": {"rate": 10, "burst": 50}}"#;
+        with_vars(
+            &[("JSON_CONFIG", json), ("JSON_MAP", r#"{"a": 1}"#)],
+            || {
+                let config: Config = get_json("JSON_CONFIG").unwrap();
+                assert_eq!(
+                    config.limits,
+                    Limits {
+                        rate: 10,
+                        burst: 50
+                    }
+                );
+                let map: HashMap<String, i32> = get_json("JSON_MAP").unwrap();
+                assert_eq!(map["a"], 1);
+            },
+        );
+    }
+
+    #[test]
+    fn test_get_json_error_includes_path() {
+        let json = r#"{"name": "api", "limits": {"rate": 10, "burst": "lots"}}"#;
+        with_vars(&[("JSON_BAD", json), ("JSON_SYNTAX", "{oops")], || {
+            match get_json::<Config>("JSON_BAD") {
+                Err(EnvError::Invalid { key, reason }) => {
+                    assert_eq!(key, "JSON_BAD");
+                    assert!(reason.contains("`limits.burst`"), "{}", reason);
+                }
+                other => panic!("unexpected result: {:?}", other),
+            }
+            assert!(matches!(
+                get_json::<Config>("JSON_SYNTAX"),
+                Err(EnvError::Invalid { .. })
+            ));
+        });
+    }
+}
@@ -0,0 +1,133 @@
+use std::str::FromStr;
+use std::sync::{Arc, PoisonError, RwLock};
+
+use super::env::{BoxError, EnvError, get_parsed};
+
+/// A variable that is read and parsed once, then served from memory.
+///
+/// Only successful reads are cached, so a missing or malformed variable is
+/// retried (and reported again) on the next call. The cache can be dropped
+/// with [`invalidate`](Self::invalidate), even on a `static`.
+///
+/// Example:
+/// ```no_run
+/// use common_utils_rs::env::LazyEnv;
+/// static MAX_CONNECTIONS: LazyEnv<usize> = LazyEnv::new("MAX_CONNECTIONS");
+///
+/// fn handle_request() {
+///     let limit: usize = *MAX_CONNECTIONS.get().unwrap();
+///     # let _ = limit;
+/// }
+/// ```
+#[derive(Debug)]
+pub struct LazyEnv<T> {
+    key: &'static str,
+    value: RwLock<Option<Arc<T>>>,
+}
+
+/// Shorthand for [`LazyEnv::new`].
+pub const fn lazy<T>(key: &'static str) -> LazyEnv<T> {
+    LazyEnv::new(key)
+}
+
+impl<T> LazyEnv<T> {
+    pub const fn new(key: &'static str) -> Self {
+        Self {
+            key,
+            value: RwLock::new(None),
+        }
+    }
+
+    pub fn key(&self) -> &'static str {
+        self.key
+    }
+
+    /// Whether a value has been cached.
+    pub fn is_cached(&self) -> bool {
+        self.cached().is_some()
+    }
+
+    /// Forget the cached value so the next [`get`](Self::get) reads the
+    /// environment again. Values already handed out are unaffected.
+    pub fn invalidate(&self) {
+        self.value
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+    }
+
+    fn cached(&self) -> Option<Arc<T>> {
+        self.value
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+impl<T> LazyEnv<T>
+where
+    T: FromStr,
+    T::Err: Into<BoxError>,
+{
+    /// Return the cached value, reading and parsing the variable on first use.
+    pub fn get(&self) -> Result<Arc<T>, EnvError> {
+        if let Some(value) = self.cached() {
+            return Ok(value);
+        }
+        let parsed = get_parsed::<T>(self.key)?;
+        let mut slot = self.value.write().unwrap_or_else(PoisonError::into_inner);
+        Ok(slot.get_or_insert_with(|| Arc::new(parsed)).clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test::ScopedEnv;
+
+    #[test]
+    fn test_lazy_env_caches_value() {
+        let mut scope = ScopedEnv::new();
+        scope.set("LAZY_PORT", "8080");
+
+        let port: LazyEnv<u16> = lazy("LAZY_PORT");
+        assert_eq!(port.key(), "LAZY_PORT");
+        assert!(!port.is_cached());
+        assert_eq!(*port.get().unwrap(), 8080);
+        assert!(port.is_cached());
+
+        scope.set("LAZY_PORT", "9090");
+        assert_eq!(*port.get().unwrap(), 8080);
+
+        port.invalidate();
+        assert_eq!(*port.get().unwrap(), 9090);
+    }
+
+    #[test]
+    fn test_lazy_env_does_not_cache_errors() {
+        let mut scope = ScopedEnv::new();
+        scope.remove("LAZY_RETRY");
+        let value = LazyEnv::<u32>::new("LAZY_RETRY");
+        assert!(matches!(value.get(), Err(EnvError::Missing(_))));
+
+        scope.set("LAZY_RETRY", "nope");
+        assert!(matches!(value.get(), Err(EnvError::ParseError { .. })));
+
+        scope.set("LAZY_RETRY", "3");
+        assert_eq!(*value.get().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_lazy_env_in_static() {
+        static WORKERS: LazyEnv<usize> = LazyEnv::new("LAZY_STATIC_WORKERS");
+        let mut scope = ScopedEnv::new();
+        scope.set("LAZY_STATIC_WORKERS", "4");
+        assert_eq!(*WORKERS.get().unwrap(), 4);
+
+        scope.set("LAZY_STATIC_WORKERS", "8");
+        assert_eq!(*WORKERS.get().unwrap(), 4);
+        WORKERS.invalidate();
+        assert!(!WORKERS.is_cached());
+        assert_eq!(*WORKERS.get().unwrap(), 8);
+    }
+}
@@ -0,0 +1,166 @@
+//! Log levels from `LOG_LEVEL` / `RUST_LOG`-style variables (`log` and
+//! `env-filter` features).
+#[cfg(feature = "log")]
+use std::str::FromStr;
+
+#[cfg(feature = "log")]
+pub use log::LevelFilter;
+#[cfg(feature = "env-filter")]
+pub use tracing_subscriber::EnvFilter;
+
+#[cfg(feature = "log")]
+use super::access;
+use super::env::{EnvError, get_required_from};
+use super::source::{EnvSource, ProcessEnv};
+
+#[cfg(feature = "log")]
+const LEVELS: &str = "off, error, warn, info, debug, trace";
+
+/// Read a log level such as `info` or a `RUST_LOG`-style directive list such
+/// as `warn,hyper=debug`.
+///
+/// For directive lists the most verbose level mentioned is returned, which is
+/// what `log::set_max_level` needs for every directive to take effect.
+/// Every directive must name a level; level names are case-insensitive and
+/// `warning` is accepted for `warn`.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::env::{get_log_level, test::with_vars, LevelFilter};
+/// with_vars(&[("LOG_LEVEL", "warn,my_app::db=debug")], || {
+///     assert_eq!(get_log_level("LOG_LEVEL").unwrap(), LevelFilter::Debug);
+/// });
+/// ```
+#[cfg(feature = "log")]
+pub fn get_log_level(key: &str) -> Result<LevelFilter, EnvError> {
+    get_log_level_from(&ProcessEnv, key)
+}
+
+/// Like [`get_log_level`], returning `default` when the variable is unset.
+/// Malformed values are still an error.
+#[cfg(feature = "log")]
+pub fn get_log_level_or(key: &str, default: LevelFilter) -> Result<LevelFilter, EnvError> {
+    let value = get_log_level(key);
+    access::record_default(key, || default.to_string());
+    match value {
+        Err(EnvError::Missing(_)) => Ok(default),
+        other => other,
+    }
+}
+
+#[cfg(feature = "log")]
+pub(crate) fn get_log_level_from(
+    source: &impl EnvSource,
+    key: &str,
+) -> Result<LevelFilter, EnvError> {
+    let value = get_required_from(source, key)?;
+    let invalid = |level: &str| EnvError::Invalid {
+        key: key.to_string(),
+        reason: format!("unknown log level `{}`, expected one of: {}", level, LEVELS),
+    };
+
+    let mut max = None;
+    for directive in value.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+        let level = match directive.rsplit_once('=') {
+            Some((_, level)) => level.trim(),
+            None => directive,
+        };
+        let level = match level.to_lowercase().as_str() {
+            "warning" => LevelFilter::Warn,
+            other => LevelFilter::from_str(other).map_err(|_| invalid(level))?,
+        };
+        max = max.max(Some(level));
+    }
+    max.ok_or_else(|| invalid(value.trim()))
+}
+
+/// Read a `RUST_LOG`-style variable into a `tracing_subscriber` filter.
+///
+/// Example:
+/// ```no_run
+/// use common_utils_rs::env::get_env_filter;
+/// let filter = get_env_filter("RUST_LOG").unwrap_or_else(|_| "info".into());
+/// ```
+#[cfg(feature = "env-filter")]
+pub fn get_env_filter(key: &str) -> Result<EnvFilter, EnvError> {
+    get_env_filter_from(&ProcessEnv, key)
+}
+
+#[cfg(feature = "env-filter")]
+pub(crate) fn get_env_filter_from(
+    source: &impl EnvSource,
+    key: &str,
+) -> Result<EnvFilter, EnvError> {
+    let value = get_required_from(source, key)?;
+    EnvFilter::try_new(&value).map_err(|e| EnvError::Invalid {
+        key: key.to_string(),
+        reason: e.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test::{with_vars, without_vars};
+
+    #[cfg(feature = "log")]
+    #[test]
+    fn test_get_log_level_simple_and_directives() {
+        let vars = [
+            ("LEVEL_SIMPLE", "INFO"),
+            ("LEVEL_WARNING", "warning"),
+            ("LEVEL_DIRECTIVES", "error, hyper=trace ,my_app=info"),
+        ];
+        with_vars(&vars, || {
+            assert_eq!(get_log_level("LEVEL_SIMPLE").unwrap(), LevelFilter::Info);
+            assert_eq!(get_log_level("LEVEL_WARNING").unwrap(), LevelFilter::Warn);
+            assert_eq!(
+                get_log_level("LEVEL_DIRECTIVES").unwrap(),
+                LevelFilter::Trace
+            );
+        });
+    }
+
+    #[cfg(feature = "log")]
+    #[test]
+    fn test_get_log_level_errors_and_default() {
+        with_vars(&[("LEVEL_BAD", "loud"), ("LEVEL_EMPTY", " ")], || {
+            match get_log_level("LEVEL_BAD") {
+                Err(EnvError::Invalid { reason, .. }) => {
+                    assert!(reason.contains("`loud`"));
+                    assert!(reason.contains("off, error, warn, info, debug, trace"));
+                }
+                other => panic!("unexpected result: {:?}", other),
+            }
+            assert!(get_log_level("LEVEL_EMPTY").is_err());
+            assert!(get_log_level_or("LEVEL_BAD", LevelFilter::Info).is_err());
+        });
+        without_vars(&["LEVEL_UNSET"], || {
+            assert_eq!(
+                get_log_level_or("LEVEL_UNSET", LevelFilter::Warn).unwrap(),
+                LevelFilter::Warn
+            );
+        });
+    }
+
+    #[cfg(feature = "env-filter")]
+    #[test]
+    fn test_get_env_filter() {
+        with_vars(
+            &[("FILTER_OK", "info,hyper=warn"), ("FILTER_BAD", "x=[")],
+            || {
+                assert!(get_env_filter("FILTER_OK").is_ok());
+                assert!(matches!(
+                    get_env_filter("FILTER_BAD"),
+                    Err(EnvError::Invalid { .. })
+                ));
+            },
+        );
+        without_vars(&["FILTER_UNSET"], || {
+            assert!(matches!(
+                get_env_filter("FILTER_UNSET"),
+                Err(EnvError::Missing(_))
+            ));
+        });
+    }
+}
@@ -2,12 +2,67 @@
 //! 
 //! Features:
 //! - `get_required`
+//! - `get_nonempty` (empty strings count as unset)
+//! - `get_required_os` (values that aren't valid unicode)
 //! - `get_or_default`
-//! - `get_parsed`
+//! - `get_parsed` (`ParseError` names the expected type and keeps the `FromStr` error as `source()`)
 //! - `get_parsed_or_default`
+//! - `get_enum` / `get_one_of`
 //! - `get_bool`
+//! - `get_bool_strict`
+//! - `get_bool_opt` (unset vs. explicitly true/false)
 //! - `get_list`
-//! - `parse_memory_size`
+//! - `get_list_with` (`ListOptions`: delimiter, trimming, empty items, dedup)
+//! - `get_parsed_list`
+//! - `get_map` / `get_map_with`
+//! - `get_weighted_list` (`backend-a:3,backend-b:1`)
+//! - `get_headers` (`X-Api-Key: abc; X-Tenant: foo` as ordered, validated pairs)
+//! - `get_all_with_prefix` (`FEATURE_X_ENABLED` as `X_ENABLED`, for dynamically named settings)
+//! - `get_range` (`8000-8100` as `RangeInclusive`)
+//! - `get_percent` / `get_percent_with` (`80%`, `0.8` or `80` as a fraction)
+//! - `parse_memory_size` / `get_memory_size` / `get_memory_size_or_default` / `ByteSize`
+//! - `get_memory_size_with` / `MemoryUnits` (1000-based `KB`/`MB`; `KiB`/`Mi` are always 1024-based)
+//! - `parse_memory_size_u128` (sizes beyond `u64`; overflow is `EnvError::Overflow`)
+//! - `get_duration` / `get_duration_or_default` / `parse_duration`
+//! - `get_datetime` / `parse_datetime` / `get_unix_timestamp` (RFC 3339 as `SystemTime`; `get_chrono_datetime` with the `chrono` feature)
+//! - `get_cron` / `parse_cron` / `CronSchedule` (five-field cron expressions with `next_after`; see `time::Cron`)
+//! - `get_port` / `get_port_with` / `get_port_or_random`
+//! - `get_socket_addr` / `get_socket_addr_or_default` / `get_socket_addrs` / `resolve_socket_addrs`
+//! - `get_ip` / `get_ipv4` / `get_ipv6` / `get_cidr` / `get_cidrs` / `IpNet`
+//! - `get_semver` / `get_semver_matching` / `Version` / `VersionReq`
+//! - `get_path` / `get_existing_file` / `get_existing_dir` / `get_writable_dir`
+//! - `get_first_of` / `get_first_of_parsed` (fallback chains)
+//! - `get_or_from` / `get_parsed_or_from` (default one variable to another)
+//! - `get_with_aliases` (deprecated names, warns via the `log`/`tracing` features)
+//! - `get_expanded` (`${VAR}` / `$VAR` interpolation)
+//! - `get_nested` / `EnvTree` (`APP__SECTION__KEY` hierarchies)
+//! - `provider` / `EnvProvider` (nested variables as a `figment::Provider`, `figment` feature)
+//! - `get_secret` / `Secret<T>` (redacted in `Debug` and `Display`)
+//! - `get_base64` / `get_hex` / `get_hex_exact` (binary values)
+//! - `get_required_or_file` / `get_secret_or_file` (Docker-style `*_FILE` secrets)
+//! - `get_url` / `get_url_or_default` / `get_url_with_schemes` (`url` feature)
+//! - `get_regex` (compiled rewrite rules and filters, `regex` feature)
+//! - `get_uuid` (tenant, instance and correlation IDs, `uuid` feature)
+//! - `get_json` (typed JSON values, `json` feature)
+//! - `get_log_level` (`log` feature) / `get_env_filter` (`env-filter` feature)
+//! - `LazyEnv` / `lazy` (parse once, serve from memory)
+//! - `snapshot_with_prefix` / `Snapshot` (JSON/TOML dumps with redaction, `capture` / `diff`)
+//! - `log_config` / `RedactionRules` (startup config logging with `*_KEY`, `*_TOKEN`, ... masked)
+//! - `EnvReader` (prefix-scoped lookups, optional case-insensitive keys via `KeyMatch`)
+//! - `overrides` (process-wide in-memory variables, consulted before the real environment without `set_var`)
+//! - `record_access` / `access_log` (which variables were read, whether they were set and their defaults)
+//! - `EnvSource` / `ProcessEnv` / `FileSource` (pluggable variable sources)
+//! - `Validator` (report every missing or malformed variable at once)
+//! - `Schema` / `VarSpec` (declare type, default, description and required flag; `load`, `help` and `render_example`; `strict` rejects undeclared `APP_*` typos)
+//! - `require_all` / `assert_required!` (fail fast on missing variables)
+//! - `get_parsed_validated` with `in_range` / `non_empty` / `min_len` / `one_of` / `matches_regex` / `try_matches_regex` (`regex` feature)
+//! - `env_arg` / `env_parser` / `EnvValue` (clap arguments with env fallback and matching parsing, `clap` feature)
+//! - `test::ScopedEnv` / `test::with_vars` (environment overrides for tests)
+//! - `dotenv` (`.env` file loading; quotes, escapes, multiline values, errors by line)
+//! - `watch` (`.env` reload notifications, `watch` feature)
+//! - `Profiled` / `load_profile` (`APP_ENV=staging` prefers `STAGING_*` and `.env.staging`)
+//! - `from_env` / `from_nested` (serde deserialization, `serde` feature)
+//! - `#[derive(FromEnv)]` (`derive` feature)
 //!
 //! Example:
 //! ```
@@ -15,7 +70,148 @@
 //! let val = get_or_default("HOST", "127.0.0.1");
 //! ```
 #[cfg(feature = "env")]
+#[allow(clippy::module_inception)]
 pub mod env;
+#[cfg(feature = "env")]
+pub mod access;
+#[cfg(feature = "env")]
+pub mod bytesize;
+#[cfg(feature = "clap")]
+pub mod cli;
+#[cfg(feature = "env")]
+pub mod cron;
+#[cfg(feature = "env")]
+pub mod datetime;
+#[cfg(feature = "serde")]
+pub mod de;
+#[cfg(feature = "derive")]
+pub mod derive;
+#[cfg(feature = "env")]
+pub mod dotenv;
+#[cfg(feature = "env")]
+pub mod duration;
+#[cfg(feature = "env")]
+pub mod encoding;
+#[cfg(feature = "env")]
+pub mod expand;
+#[cfg(feature = "env")]
+pub mod fallback;
+#[cfg(feature = "env")]
+pub mod headers;
+#[cfg(feature = "json")]
+pub mod json;
+#[cfg(feature = "env")]
+pub mod lazy;
+#[cfg(any(feature = "log", feature = "env-filter"))]
+pub mod level;
+#[cfg(feature = "env")]
+pub mod nested;
+#[cfg(feature = "env")]
+pub mod net;
+#[cfg(feature = "env")]
+pub mod overlay;
+#[cfg(feature = "env")]
+pub mod paths;
+#[cfg(feature = "regex")]
+pub mod patterns;
+#[cfg(feature = "env")]
+pub mod percent;
+#[cfg(feature = "env")]
+pub mod profile;
+#[cfg(feature = "figment")]
+pub mod provider;
+#[cfg(feature = "env")]
+pub mod range;
+#[cfg(feature = "env")]
+pub mod reader;
+#[cfg(feature = "env")]
+pub mod schema;
+#[cfg(feature = "env")]
+pub mod secret;
+#[cfg(feature = "env")]
+pub mod snapshot;
+#[cfg(feature = "env")]
+pub mod source;
+#[cfg(feature = "env")]
+pub mod test;
+#[cfg(feature = "url")]
+pub mod urls;
+#[cfg(feature = "uuid")]
+pub mod uuids;
+#[cfg(feature = "env")]
+pub mod validator;
+#[cfg(feature = "env")]
+pub mod version;
+#[cfg(feature = "watch")]
+pub mod watch;
 
 #[cfg(feature = "env")]
 pub use env::*;
+#[cfg(feature = "env")]
+pub use access::*;
+#[cfg(feature = "env")]
+pub use bytesize::*;
+#[cfg(feature = "clap")]
+pub use cli::*;
+#[cfg(feature = "env")]
+pub use cron::*;
+#[cfg(feature = "env")]
+pub use datetime::*;
+#[cfg(feature = "serde")]
+pub use de::*;
+#[cfg(feature = "derive")]
+pub use derive::*;
+#[cfg(feature = "env")]
+pub use duration::*;
+#[cfg(feature = "env")]
+pub use encoding::*;
+#[cfg(feature = "env")]
+pub use expand::*;
+#[cfg(feature = "env")]
+pub use fallback::*;
+#[cfg(feature = "env")]
+pub use headers::*;
+#[cfg(feature = "json")]
+pub use json::*;
+#[cfg(feature = "env")]
+pub use lazy::*;
+#[cfg(any(feature = "log", feature = "env-filter"))]
+pub use level::*;
+#[cfg(feature = "env")]
+pub use nested::*;
+#[cfg(feature = "env")]
+pub use net::*;
+#[cfg(feature = "env")]
+pub use overlay::*;
+#[cfg(feature = "env")]
+pub use paths::*;
+#[cfg(feature = "regex")]
+pub use patterns::*;
+#[cfg(feature = "env")]
+pub use percent::*;
+#[cfg(feature = "env")]
+pub use profile::*;
+#[cfg(feature = "figment")]
+pub use provider::*;
+#[cfg(feature = "env")]
+pub use range::*;
+#[cfg(feature = "env")]
+pub use reader::*;
+#[cfg(feature = "env")]
+pub use schema::*;
+#[cfg(feature = "env")]
+pub use secret::*;
+#[cfg(feature = "env")]
+pub use snapshot::*;
+#[cfg(feature = "env")]
+pub use source::*;
+#[cfg(feature = "url")]
+pub use urls::*;
+#[cfg(feature = "uuid")]
+pub use uuids::*;
+#[cfg(feature = "env")]
+pub use validator::*;
+#[cfg(feature = "env")]
+pub use version::*;
+#[cfg(feature = "watch")]
+pub use watch::*;
@@ -0,0 +1,181 @@
+//! Hierarchical configuration from `PREFIX__SECTION__KEY` variables.
+//!
+//! Double underscores separate levels, so `APP__DATABASE__HOST` becomes the
+//! `host` entry of the `database` table under `APP`. Segment names are
+//! lower-cased, matching how config-rs maps environment variables. With the
+//! `serde` feature the tree can be deserialized directly via `from_nested`.
+//!
+//! Example:
+//! ```
+//! use common_utils_rs::env::{get_nested, test::with_vars};
+//! let vars = [("APP__DATABASE__HOST", "db"), ("APP__DATABASE__PORT", "5432")];
+//! with_vars(&vars, || {
+//!     let tree = get_nested("APP");
+//!     assert_eq!(tree.get("database.host"), Some("db"));
+//!     assert_eq!(tree.child("database").unwrap().len(), 2);
+//! });
+//! ```
+use std::collections::BTreeMap;
+
+use super::source::ProcessEnv;
+
+/// Separator between levels of a nested key.
+pub const NESTED_SEPARATOR: &str = "__";
+
+/// One level of a nested configuration tree.
+///
+/// A node may carry both a value and children, e.g. when `APP__DB` and
+/// `APP__DB__HOST` are both set.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnvTree {
+    key: String,
+    value: Option<String>,
+    children: BTreeMap<String, EnvTree>,
+}
+
+impl EnvTree {
+    /// Build a tree from `vars`, keeping those named `prefix` or starting
+    /// with `prefix__`.
+    pub fn from_vars<I, K, V>(prefix: &str, vars: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: Into<String>,
+    {
+        let mut root = EnvTree {
+            key: prefix.to_string(),
+            ..Self::default()
+        };
+        let head = format!("{}{}", prefix, NESTED_SEPARATOR);
+
+        for (name, value) in vars {
+            let name = name.as_ref();
+            if name == prefix {
+                root.value = Some(value.into());
+                continue;
+            }
+            let Some(rest) = name.strip_prefix(&head) else {
+                continue;
+            };
+            if rest.split(NESTED_SEPARATOR).any(str::is_empty) {
+                continue;
+            }
+
+            let mut node = &mut root;
+            for segment in rest.split(NESTED_SEPARATOR) {
+                let key = format!("{}{}{}", node.key, NESTED_SEPARATOR, segment);
+                node = node
+                    .children
+                    .entry(segment.to_lowercase())
+                    .or_insert_with(|| EnvTree {
+                        key,
+                        ..Self::default()
+                    });
+            }
+            node.value = Some(value.into());
+        }
+
+        root
+    }
+
+    /// Variable name this node corresponds to, e.g. `APP__DATABASE`.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn value(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
+
+    /// Direct child by segment name (case-insensitive).
+    pub fn child(&self, name: &str) -> Option<&EnvTree> {
+        self.children.get(&name.to_lowercase())
+    }
+
+    /// Direct children in name order.
+    pub fn children(&self) -> impl Iterator<Item = (&str, &EnvTree)> {
+        self.children
+            .iter()
+            .map(|(name, node)| (name.as_str(), node))
+    }
+
+    /// Value at a dotted path such as `"database.host"`.
+    pub fn get(&self, path: &str) -> Option<&str> {
+        path.split('.')
+            .try_fold(self, |node, segment| node.child(segment))?
+            .value()
+    }
+
+    /// Number of direct children.
+    pub fn len(&self) -> usize {
+        self.children.len()
+    }
+
+    /// `true` when the node has neither a value nor children.
+    pub fn is_empty(&self) -> bool {
+        self.value.is_none() && self.children.is_empty()
+    }
+}
+
+/// Collect every `prefix__...` variable of the process environment into a tree.
+///
+/// Variables whose names are not valid Unicode are skipped.
+pub fn get_nested(prefix: &str) -> EnvTree {
+    EnvTree::from_vars(prefix, ProcessEnv.vars())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test::with_vars;
+
+    #[test]
+    fn test_from_vars_builds_levels() {
+        let tree = EnvTree::from_vars(
+            "APP",
+            [
+                ("APP__DATABASE__HOST", "db"),
+                ("APP__DATABASE__PORT", "5432"),
+                ("APP__NAME", "svc"),
+                ("APPLE__X", "ignored"),
+                ("OTHER__NAME", "ignored"),
+            ],
+        );
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree.get("name"), Some("svc"));
+        assert_eq!(tree.get("database.port"), Some("5432"));
+        assert_eq!(tree.get("database.user"), None);
+
+        let database = tree.child("DATABASE").unwrap();
+        assert_eq!(database.key(), "APP__DATABASE");
+        assert_eq!(database.value(), None);
+        assert_eq!(database.child("host").unwrap().key(), "APP__DATABASE__HOST");
+        let names: Vec<_> = database.children().map(|(name, _)| name).collect();
+        assert_eq!(names, vec!["host", "port"]);
+    }
+
+    #[test]
+    fn test_from_vars_value_and_children() {
+        let tree = EnvTree::from_vars(
+            "APP",
+            [("APP", "root"), ("APP__DB", "url"), ("APP__DB__POOL", "4")],
+        );
+        assert_eq!(tree.value(), Some("root"));
+        assert_eq!(tree.get("db"), Some("url"));
+        assert_eq!(tree.get("db.pool"), Some("4"));
+    }
+
+    #[test]
+    fn test_from_vars_skips_empty_segments() {
+        let tree = EnvTree::from_vars("APP", [("APP____X", "1"), ("APP__A__", "2")]);
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    fn test_get_nested_reads_process_env() {
+        with_vars(&[("NESTED_T__CACHE__TTL", "60")], || {
+            let tree = get_nested("NESTED_T");
+            assert_eq!(tree.get("cache.ttl"), Some("60"));
+        });
+    }
+}
@@ -0,0 +1,537 @@
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener, ToSocketAddrs};
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use super::access;
+use super::env::{BoxError, EnvError, get_list_from, get_required_from, type_name};
+use super::source::{EnvSource, ProcessEnv};
+
+fn parse_trimmed<T>(key: &str, value: &str) -> Result<T, EnvError>
+where
+    T: FromStr,
+    T::Err: Into<BoxError>,
+{
+    value.trim().parse().map_err(|e| {
+        EnvError::parse_error(key, value)
+            .with_expected(type_name::<T>())
+            .with_source(e)
+    })
+}
+
+fn get_trimmed_from<T>(source: &impl EnvSource, key: &str) -> Result<T, EnvError>
+where
+    T: FromStr,
+    T::Err: Into<BoxError>,
+{
+    let value = get_required_from(source, key)?;
+    parse_trimmed(key, &value)
+}
+
+fn get_trimmed_list_from<T>(source: &impl EnvSource, key: &str) -> Result<Vec<T>, EnvError>
+where
+    T: FromStr,
+    T::Err: Into<BoxError>,
+{
+    get_list_from(source, key)?
+        .iter()
+        .filter(|item| !item.is_empty())
+        .map(|item| parse_trimmed(key, item))
+        .collect()
+}
+
+/// Which ports [`get_port_with`] accepts. Port 0 is always rejected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PortRange {
+    /// Any port from 1 to 65535.
+    #[default]
+    Any,
+    /// Ports from 1024 up, which don't need elevated privileges to bind.
+    Unprivileged,
+}
+
+/// Read a TCP/UDP port number, rejecting `0` and values above 65535.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::env::{get_port, test::with_vars};
+/// with_vars(&[("HTTP_PORT", "8080"), ("BAD_PORT", "0")], || {
+///     assert_eq!(get_port("HTTP_PORT").unwrap(), 8080);
+///     assert!(get_port("BAD_PORT").is_err());
+/// });
+/// ```
+pub fn get_port(key: &str) -> Result<u16, EnvError> {
+    get_port_with(key, PortRange::Any)
+}
+
+/// Like [`get_port`], restricted to `range`.
+pub fn get_port_with(key: &str, range: PortRange) -> Result<u16, EnvError> {
+    get_port_with_from(&ProcessEnv, key, range)
+}
+
+pub(crate) fn get_port_with_from(
+    source: &impl EnvSource,
+    key: &str,
+    range: PortRange,
+) -> Result<u16, EnvError> {
+    let number: u64 = get_trimmed_from(source, key)?;
+    let invalid = |reason: String| EnvError::Invalid {
+        key: key.to_string(),
+        reason,
+    };
+    match (u16::try_from(number), range) {
+        (Err(_), _) | (Ok(0), _) => Err(invalid(format!(
+            "{} is not a valid port (expected 1-65535)",
+            number
+        ))),
+        (Ok(port), PortRange::Unprivileged) if port < 1024 => Err(invalid(format!(
+            "{} is a privileged port (expected 1024-65535)",
+            port
+        ))),
+        (Ok(port), _) => Ok(port),
+    }
+}
+
+/// Like [`get_port`], but asks the OS for a free port when the variable is
+/// unset. Handy for tests and sidecars that report their port elsewhere.
+///
+/// The port is free when this returns, but nothing reserves it, so another
+/// process may take it before it is bound.
+pub fn get_port_or_random(key: &str) -> Result<u16, EnvError> {
+    match get_port(key) {
+        Err(EnvError::Missing(_)) => TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .and_then(|listener| listener.local_addr())
+            .map(|addr| addr.port())
+            .map_err(|e| EnvError::Invalid {
+                key: key.to_string(),
+                reason: format!("not set and no free port is available: {}", e),
+            }),
+        other => other,
+    }
+}
+
+/// Read a `host:port` socket address such as `0.0.0.0:8080` or `[::1]:8080`.
+///
+/// The host must be an IP literal; use [`resolve_socket_addrs`] for hostnames.
+pub fn get_socket_addr(key: &str) -> Result<SocketAddr, EnvError> {
+    get_socket_addr_from(&ProcessEnv, key)
+}
+
+/// Like [`get_socket_addr`], returning `default` when the variable is unset
+/// or cannot be parsed.
+pub fn get_socket_addr_or_default(key: &str, default: SocketAddr) -> SocketAddr {
+    let value = get_socket_addr(key).unwrap_or(default);
+    access::record_default(key, || default.to_string());
+    value
+}
+
+pub(crate) fn get_socket_addr_from(
+    source: &impl EnvSource,
+    key: &str,
+) -> Result<SocketAddr, EnvError> {
+    get_trimmed_from(source, key)
+}
+
+/// Read a comma-separated list of socket addresses.
+pub fn get_socket_addrs(key: &str) -> Result<Vec<SocketAddr>, EnvError> {
+    get_socket_addrs_from(&ProcessEnv, key)
+}
+
+pub(crate) fn get_socket_addrs_from(
+    source: &impl EnvSource,
+    key: &str,
+) -> Result<Vec<SocketAddr>, EnvError> {
+    get_trimmed_list_from(source, key)
+}
+
+/// Read a comma-separated list of `host:port` entries, resolving hostnames
+/// through DNS. Each entry may expand to several addresses.
+pub fn resolve_socket_addrs(key: &str) -> Result<Vec<SocketAddr>, EnvError> {
+    resolve_socket_addrs_from(&ProcessEnv, key)
+}
+
+pub(crate) fn resolve_socket_addrs_from(
+    source: &impl EnvSource,
+    key: &str,
+) -> Result<Vec<SocketAddr>, EnvError> {
+    let mut addrs = Vec::new();
+    for item in get_list_from(source, key)?
+        .iter()
+        .filter(|item| !item.is_empty())
+    {
+        let resolved = item.to_socket_addrs().map_err(|e| EnvError::Invalid {
+            key: key.to_string(),
+            reason: format!("cannot resolve `{}`: {}", item, e),
+        })?;
+        addrs.extend(resolved);
+    }
+    Ok(addrs)
+}
+
+/// Read an IPv4 or IPv6 address.
+pub fn get_ip(key: &str) -> Result<IpAddr, EnvError> {
+    get_ip_from(&ProcessEnv, key)
+}
+
+/// Read an IPv4 address; IPv6 values are rejected.
+pub fn get_ipv4(key: &str) -> Result<Ipv4Addr, EnvError> {
+    get_trimmed_from(&ProcessEnv, key)
+}
+
+/// Read an IPv6 address; IPv4 values are rejected.
+pub fn get_ipv6(key: &str) -> Result<Ipv6Addr, EnvError> {
+    get_trimmed_from(&ProcessEnv, key)
+}
+
+/// Read a network in CIDR notation such as `10.0.0.0/8`.
+pub fn get_cidr(key: &str) -> Result<IpNet, EnvError> {
+    get_cidr_from(&ProcessEnv, key)
+}
+
+/// Read a comma-separated list of networks.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::env::{get_cidrs, test::with_vars};
+/// with_vars(&[("TRUSTED_NETWORKS", "10.0.0.0/8,192.168.0.0/16")], || {
+///     let trusted = get_cidrs("TRUSTED_NETWORKS").unwrap();
+///     let client = "192.168.4.20".parse().unwrap();
+///     assert!(trusted.iter().any(|net| net.contains(client)));
+/// });
+/// ```
+pub fn get_cidrs(key: &str) -> Result<Vec<IpNet>, EnvError> {
+    get_cidrs_from(&ProcessEnv, key)
+}
+
+pub(crate) fn get_ip_from(source: &impl EnvSource, key: &str) -> Result<IpAddr, EnvError> {
+    get_trimmed_from(source, key)
+}
+
+pub(crate) fn get_cidr_from(source: &impl EnvSource, key: &str) -> Result<IpNet, EnvError> {
+    get_trimmed_from(source, key)
+}
+
+pub(crate) fn get_cidrs_from(source: &impl EnvSource, key: &str) -> Result<Vec<IpNet>, EnvError> {
+    get_trimmed_list_from(source, key)
+}
+
+/// An IP network: an address and a prefix length, e.g. `10.0.0.0/8`.
+///
+/// A bare address parses as a single-host network (`/32` or `/128`). Host
+/// bits may be set (`10.1.2.3/8`); [`IpNet::network`] clears them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IpNet {
+    addr: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpNet {
+    /// `None` when `prefix_len` exceeds 32 for IPv4 or 128 for IPv6.
+    pub fn new(addr: IpAddr, prefix_len: u8) -> Option<Self> {
+        (prefix_len <= max_prefix_len(addr)).then_some(Self { addr, prefix_len })
+    }
+
+    pub fn addr(&self) -> IpAddr {
+        self.addr
+    }
+
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    /// The address with all host bits cleared.
+    pub fn network(&self) -> IpAddr {
+        match self.addr {
+            IpAddr::V4(addr) => {
+                IpAddr::V4(Ipv4Addr::from(u32::from(addr) & mask32(self.prefix_len)))
+            }
+            IpAddr::V6(addr) => {
+                IpAddr::V6(Ipv6Addr::from(u128::from(addr) & mask128(self.prefix_len)))
+            }
+        }
+    }
+
+    /// Whether `ip` lies in this network. Addresses of the other family never do.
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = mask32(self.prefix_len);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = mask128(self.prefix_len);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+fn max_prefix_len(addr: IpAddr) -> u8 {
+    match addr {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128,
+    }
+}
+
+fn mask32(prefix_len: u8) -> u32 {
+    u32::MAX
+        .checked_shl(32 - u32::from(prefix_len))
+        .unwrap_or(0)
+}
+
+fn mask128(prefix_len: u8) -> u128 {
+    u128::MAX
+        .checked_shl(128 - u32::from(prefix_len))
+        .unwrap_or(0)
+}
+
+/// Why an [`IpNet`] failed to parse.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum ParseIpNetError {
+    #[error("invalid IP address `{0}`")]
+    InvalidAddress(String),
+    #[error("missing prefix length after `/`")]
+    MissingPrefix,
+    #[error("invalid prefix length `{0}`")]
+    InvalidPrefix(String),
+    #[error("prefix length {len} is out of range (expected 0-{max})")]
+    PrefixOutOfRange { len: u32, max: u8 },
+}
+
+impl FromStr for IpNet {
+    type Err = ParseIpNetError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (addr, len) = match s.split_once('/') {
+            Some((addr, len)) => (addr, Some(len)),
+            None => (s, None),
+        };
+        let addr: IpAddr = addr
+            .parse()
+            .map_err(|_| ParseIpNetError::InvalidAddress(addr.to_string()))?;
+        let max = max_prefix_len(addr);
+        let len = match len {
+            None => return Ok(IpNet::new(addr, max).expect("full-length prefix")),
+            Some("") => return Err(ParseIpNetError::MissingPrefix),
+            Some(len) => len
+                .parse::<u32>()
+                .map_err(|_| ParseIpNetError::InvalidPrefix(len.to_string()))?,
+        };
+        u8::try_from(len)
+            .ok()
+            .and_then(|prefix_len| IpNet::new(addr, prefix_len))
+            .ok_or(ParseIpNetError::PrefixOutOfRange { len, max })
+    }
+}
+
+impl fmt::Display for IpNet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix_len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test::with_vars;
+
+    #[test]
+    fn test_get_socket_addr_ipv4_and_ipv6() {
+        with_vars(
+            &[("BIND_V4", "0.0.0.0:8080"), ("BIND_V6", "[::1]:9000")],
+            || {
+                let v4 = get_socket_addr("BIND_V4").unwrap();
+                assert_eq!(v4.port(), 8080);
+                let v6 = get_socket_addr("BIND_V6").unwrap();
+                assert_eq!(v6.ip(), IpAddr::V6(Ipv6Addr::LOCALHOST));
+                assert_eq!(v6.port(), 9000);
+            },
+        );
+    }
+
+    #[test]
+    fn test_get_socket_addr_invalid() {
+        for value in ["localhost:80", "::1:80", "127.0.0.1", "127.0.0.1:99999"] {
+            with_vars(&[("BIND_BAD", value)], || {
+                assert!(
+                    matches!(
+                        get_socket_addr("BIND_BAD"),
+                        Err(EnvError::ParseError { .. })
+                    ),
+                    "expected `{}` to be rejected",
+                    value
+                );
+            });
+        }
+    }
+
+    #[test]
+    fn test_get_socket_addrs() {
+        with_vars(&[("BIND_LIST", "127.0.0.1:80, [::1]:443")], || {
+            let addrs = get_socket_addrs("BIND_LIST").unwrap();
+            assert_eq!(addrs.len(), 2);
+            assert_eq!(addrs[1].port(), 443);
+        });
+    }
+
+    #[test]
+    fn test_resolve_socket_addrs_localhost() {
+        with_vars(&[("BIND_RESOLVE", "localhost:8080,10.0.0.1:81")], || {
+            let addrs = resolve_socket_addrs("BIND_RESOLVE").unwrap();
+            assert!(
+                addrs
+                    .iter()
+                    .any(|a| a.ip().is_loopback() && a.port() == 8080)
+            );
+            assert!(addrs.iter().any(|a| a.port() == 81));
+        });
+    }
+
+    #[test]
+    fn test_get_ip_families() {
+        let vars = [
+            ("IP_V4", " 10.0.0.1 "),
+            ("IP_V6", "::1"),
+            ("IP_BAD", "10.0.0"),
+        ];
+        with_vars(&vars, || {
+            assert_eq!(
+                get_ip("IP_V4").unwrap(),
+                IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))
+            );
+            assert_eq!(get_ipv4("IP_V4").unwrap(), Ipv4Addr::new(10, 0, 0, 1));
+            assert_eq!(get_ipv6("IP_V6").unwrap(), Ipv6Addr::LOCALHOST);
+            assert!(get_ipv4("IP_V6").is_err());
+            assert!(get_ipv6("IP_V4").is_err());
+            assert!(matches!(get_ip("IP_BAD"), Err(EnvError::ParseError { .. })));
+        });
+    }
+
+    #[test]
+    fn test_ipnet_parse_and_contains() {
+        let net: IpNet = "10.1.2.3/8".parse().unwrap();
+        assert_eq!(net.prefix_len(), 8);
+        assert_eq!(net.network(), IpAddr::V4(Ipv4Addr::new(10, 0, 0, 0)));
+        assert!(net.contains("10.255.0.1".parse().unwrap()));
+        assert!(!net.contains("11.0.0.1".parse().unwrap()));
+        assert!(!net.contains("::1".parse().unwrap()));
+        assert_eq!(net.to_string(), "10.1.2.3/8");
+
+        let host: IpNet = "192.168.1.1".parse().unwrap();
+        assert_eq!(host.prefix_len(), 32);
+        assert!(host.contains("192.168.1.1".parse().unwrap()));
+        assert!(!host.contains("192.168.1.2".parse().unwrap()));
+
+        let all: IpNet = "0.0.0.0/0".parse().unwrap();
+        assert!(all.contains("8.8.8.8".parse().unwrap()));
+
+        let v6: IpNet = "fd00::/8".parse().unwrap();
+        assert!(v6.contains("fd12::1".parse().unwrap()));
+        assert!(!v6.contains("fe80::1".parse().unwrap()));
+
+        for (bad, err) in [
+            (
+                "10.0.0.0/33",
+                ParseIpNetError::PrefixOutOfRange { len: 33, max: 32 },
+            ),
+            (
+                "::/129",
+                ParseIpNetError::PrefixOutOfRange { len: 129, max: 128 },
+            ),
+            ("10.0.0.0/", ParseIpNetError::MissingPrefix),
+            (
+                "nope/8",
+                ParseIpNetError::InvalidAddress("nope".to_string()),
+            ),
+            (
+                "10.0.0.0/x",
+                ParseIpNetError::InvalidPrefix("x".to_string()),
+            ),
+        ] {
+            assert_eq!(bad.parse::<IpNet>(), Err(err), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_get_cidrs() {
+        with_vars(
+            &[
+                ("CIDR_LIST", "10.0.0.0/8, 192.168.0.0/16"),
+                ("CIDR_BAD", "10.0.0.0/8,x"),
+            ],
+            || {
+                let nets = get_cidrs("CIDR_LIST").unwrap();
+                assert_eq!(nets.len(), 2);
+                assert_eq!(
+                    nets[1],
+                    IpNet::new(IpAddr::V4(Ipv4Addr::new(192, 168, 0, 0)), 16).unwrap()
+                );
+                assert_eq!(
+                    get_cidrs("CIDR_BAD").unwrap_err().to_string(),
+                    "Failed to parse environment variable `CIDR_BAD` as IpNet: x (invalid IP address `x`)"
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn test_get_socket_addr_or_default() {
+        let default: SocketAddr = "127.0.0.1:8080".parse().unwrap();
+        with_vars(
+            &[("ADDR_OR_SET", "0.0.0.0:9000"), ("ADDR_OR_BAD", "nope")],
+            || {
+                assert_eq!(
+                    get_socket_addr_or_default("ADDR_OR_SET", default),
+                    "0.0.0.0:9000".parse::<SocketAddr>().unwrap()
+                );
+                assert_eq!(get_socket_addr_or_default("ADDR_OR_BAD", default), default);
+                assert_eq!(
+                    get_socket_addr_or_default("ADDR_OR_UNSET", default),
+                    default
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn test_get_port() {
+        let vars = [
+            ("PORT_OK", " 443 "),
+            ("PORT_ZERO", "0"),
+            ("PORT_HUGE", "70000"),
+            ("PORT_TEXT", "http"),
+        ];
+        with_vars(&vars, || {
+            assert_eq!(get_port("PORT_OK").unwrap(), 443);
+            assert!(matches!(
+                get_port("PORT_ZERO"),
+                Err(EnvError::Invalid { .. })
+            ));
+            match get_port("PORT_HUGE") {
+                Err(EnvError::Invalid { reason, .. }) => {
+                    assert_eq!(reason, "70000 is not a valid port (expected 1-65535)")
+                }
+                other => panic!("unexpected result: {:?}", other),
+            }
+            assert!(matches!(
+                get_port("PORT_TEXT"),
+                Err(EnvError::ParseError { .. })
+            ));
+            assert!(matches!(
+                get_port_with("PORT_OK", PortRange::Unprivileged),
+                Err(EnvError::Invalid { .. })
+            ));
+        });
+    }
+
+    #[test]
+    fn test_get_port_or_random() {
+        with_vars(&[("PORT_RANDOM_SET", "9000")], || {
+            assert_eq!(get_port_or_random("PORT_RANDOM_SET").unwrap(), 9000);
+            assert_ne!(get_port_or_random("PORT_RANDOM_UNSET").unwrap(), 0);
+        });
+    }
+}
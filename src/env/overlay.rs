@@ -0,0 +1,138 @@
+//! A process-wide layer of variables consulted before the real environment.
+//!
+//! Changing the process environment with `std::env::set_var` is unsound
+//! while other threads may read it. [`overrides`] keeps its values in memory
+//! instead, behind a lock, and every getter that reads the process
+//! environment (through [`ProcessEnv`](super::ProcessEnv)) sees them first.
+//! Tests and embedders can inject configuration without touching the real
+//! environment at all.
+//!
+//! The layer is shared by the whole process, so parallel tests should use
+//! distinct variable names.
+//!
+//! Example:
+//! ```
+//! use common_utils_rs::env::{get_parsed, overrides};
+//!
+//! overrides().set("OVERLAY_DOC_PORT", "9000");
+//! assert_eq!(get_parsed::<u16>("OVERLAY_DOC_PORT").unwrap(), 9000);
+//! overrides().restore("OVERLAY_DOC_PORT");
+//! assert!(get_parsed::<u16>("OVERLAY_DOC_PORT").is_err());
+//! ```
+use std::collections::BTreeMap;
+use std::sync::{PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// Overridden keys: `Some` replaces the real value, `None` hides it.
+static OVERRIDES: RwLock<BTreeMap<String, Option<String>>> = RwLock::new(BTreeMap::new());
+
+/// Handle to the process-wide override layer, returned by [`overrides`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Overrides;
+
+/// The process-wide override layer.
+pub fn overrides() -> Overrides {
+    Overrides
+}
+
+impl Overrides {
+    /// Make `key` read as `value`, whatever the real environment holds.
+    pub fn set(&self, key: &str, value: impl Into<String>) -> &Self {
+        write().insert(key.to_string(), Some(value.into()));
+        self
+    }
+
+    /// Make `key` read as unset, even if the real environment has it.
+    pub fn remove(&self, key: &str) -> &Self {
+        write().insert(key.to_string(), None);
+        self
+    }
+
+    /// Drop the override for `key`, so it reads from the real environment
+    /// again.
+    pub fn restore(&self, key: &str) -> &Self {
+        write().remove(key);
+        self
+    }
+
+    /// Drop every override.
+    pub fn clear(&self) {
+        write().clear();
+    }
+}
+
+/// `Some(value)` when `key` is overridden, where `value` is `None` for a
+/// removed variable.
+pub(crate) fn lookup(key: &str) -> Option<Option<String>> {
+    read().get(key).cloned()
+}
+
+/// Apply the layer to the real variables `vars`.
+pub(crate) fn apply(vars: impl Iterator<Item = (String, String)>) -> Vec<(String, String)> {
+    let overrides = read();
+    let mut applied: Vec<(String, String)> = vars
+        .filter(|(key, _)| !overrides.contains_key(key))
+        .collect();
+    applied.extend(
+        overrides
+            .iter()
+            .filter_map(|(key, value)| Some((key.clone(), value.clone()?))),
+    );
+    applied
+}
+
+// A panic while holding the lock can't leave the map half-updated, so a
+// poisoned lock is still safe to use.
+fn read() -> RwLockReadGuard<'static, BTreeMap<String, Option<String>>> {
+    OVERRIDES.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn write() -> RwLockWriteGuard<'static, BTreeMap<String, Option<String>>> {
+    OVERRIDES.write().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test::with_vars;
+    use crate::env::{EnvError, EnvSource, ProcessEnv, get_required};
+
+    #[test]
+    fn test_overrides_shadow_process_env() {
+        with_vars(&[("OVERLAY_REAL", "real")], || {
+            overrides()
+                .set("OVERLAY_REAL", "fake")
+                .set("OVERLAY_NEW", "new");
+            assert_eq!(get_required("OVERLAY_REAL").unwrap(), "fake");
+            assert_eq!(get_required("OVERLAY_NEW").unwrap(), "new");
+            assert!(ProcessEnv.keys().contains(&"OVERLAY_NEW".to_string()));
+            assert_eq!(
+                ProcessEnv
+                    .vars()
+                    .filter(|(key, _)| key == "OVERLAY_REAL")
+                    .collect::<Vec<_>>(),
+                [("OVERLAY_REAL".to_string(), "fake".to_string())]
+            );
+
+            overrides().remove("OVERLAY_REAL");
+            assert!(matches!(
+                get_required("OVERLAY_REAL"),
+                Err(EnvError::Missing(_))
+            ));
+            assert!(!ProcessEnv.keys().contains(&"OVERLAY_REAL".to_string()));
+
+            overrides().restore("OVERLAY_REAL").restore("OVERLAY_NEW");
+            assert_eq!(get_required("OVERLAY_REAL").unwrap(), "real");
+            assert!(get_required("OVERLAY_NEW").is_err());
+        });
+    }
+
+    #[test]
+    fn test_overrides_visible_from_other_threads() {
+        overrides().set("OVERLAY_THREADS", "shared");
+        let seen = std::thread::spawn(|| get_required("OVERLAY_THREADS").unwrap())
+            .join()
+            .unwrap();
+        assert_eq!(seen, "shared");
+        overrides().restore("OVERLAY_THREADS");
+    }
+}
@@ -0,0 +1,175 @@
+use std::fs::{self, OpenOptions};
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use super::env::{EnvError, get_required_os_from};
+use super::source::{EnvSource, ProcessEnv};
+
+fn invalid(key: &str, path: PathBuf, reason: impl Into<String>) -> EnvError {
+    EnvError::InvalidPath {
+        key: key.to_string(),
+        path,
+        reason: reason.into(),
+    }
+}
+
+/// Read a required variable as a path, without touching the filesystem.
+///
+/// The value doesn't need to be valid unicode.
+pub fn get_path(key: &str) -> Result<PathBuf, EnvError> {
+    get_path_from(&ProcessEnv, key)
+}
+
+pub(crate) fn get_path_from(source: &impl EnvSource, key: &str) -> Result<PathBuf, EnvError> {
+    get_required_os_from(source, key).map(PathBuf::from)
+}
+
+/// Read a path that must point to an existing regular file.
+pub fn get_existing_file(key: &str) -> Result<PathBuf, EnvError> {
+    get_existing_file_from(&ProcessEnv, key)
+}
+
+pub(crate) fn get_existing_file_from(
+    source: &impl EnvSource,
+    key: &str,
+) -> Result<PathBuf, EnvError> {
+    let path = get_path_from(source, key)?;
+    match fs::metadata(&path) {
+        Ok(meta) if meta.is_file() => Ok(path),
+        Ok(_) => Err(invalid(key, path, "is not a file")),
+        Err(e) => Err(invalid(key, path, format!("is not accessible: {}", e))),
+    }
+}
+
+/// Read a path that must point to an existing directory.
+pub fn get_existing_dir(key: &str) -> Result<PathBuf, EnvError> {
+    get_existing_dir_from(&ProcessEnv, key)
+}
+
+pub(crate) fn get_existing_dir_from(
+    source: &impl EnvSource,
+    key: &str,
+) -> Result<PathBuf, EnvError> {
+    let path = get_path_from(source, key)?;
+    match fs::metadata(&path) {
+        Ok(meta) if meta.is_dir() => Ok(path),
+        Ok(_) => Err(invalid(key, path, "is not a directory")),
+        Err(e) => Err(invalid(key, path, format!("is not accessible: {}", e))),
+    }
+}
+
+/// Read a path that must point to an existing directory the process can
+/// create files in.
+///
+/// Writability is checked by creating and removing a probe file, which is
+/// more reliable than inspecting permission bits. Each probe has its own
+/// name, so concurrent checks of the same directory don't collide.
+pub fn get_writable_dir(key: &str) -> Result<PathBuf, EnvError> {
+    get_writable_dir_from(&ProcessEnv, key)
+}
+
+pub(crate) fn get_writable_dir_from(
+    source: &impl EnvSource,
+    key: &str,
+) -> Result<PathBuf, EnvError> {
+    static PROBES: AtomicUsize = AtomicUsize::new(0);
+
+    let path = get_existing_dir_from(source, key)?;
+    let mut attempts = 0;
+    loop {
+        let probe = path.join(format!(
+            ".common-utils-rs-probe-{}-{}",
+            std::process::id(),
+            PROBES.fetch_add(1, Ordering::Relaxed)
+        ));
+        match OpenOptions::new().write(true).create_new(true).open(&probe) {
+            Ok(file) => {
+                // Close the probe first; Windows can't remove an open file.
+                drop(file);
+                let _ = fs::remove_file(&probe);
+                return Ok(path);
+            }
+            // Left behind by an earlier process with the same id.
+            Err(e) if e.kind() == ErrorKind::AlreadyExists && attempts < 16 => attempts += 1,
+            Err(e) => return Err(invalid(key, path, format!("is not writable: {}", e))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::EnvReader;
+    use crate::env::test::with_vars;
+    use std::env;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!(
+            "common-utils-rs-paths-{}-{}",
+            std::process::id(),
+            name
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_get_path() {
+        with_vars(&[("PATH_PLAIN", "/does/not/matter")], || {
+            assert_eq!(
+                get_path("PATH_PLAIN").unwrap(),
+                PathBuf::from("/does/not/matter")
+            );
+        });
+    }
+
+    #[test]
+    fn test_get_existing_file_and_dir() {
+        let dir = temp_dir("existing");
+        let file = dir.join("config.toml");
+        fs::write(&file, "").unwrap();
+
+        let vars = [
+            ("PATH_FILE", file.to_str().unwrap()),
+            ("PATH_DIR", dir.to_str().unwrap()),
+        ];
+        with_vars(&vars, || {
+            assert_eq!(get_existing_file("PATH_FILE").unwrap(), file);
+            assert_eq!(get_existing_dir("PATH_DIR").unwrap(), dir);
+            assert_eq!(get_writable_dir("PATH_DIR").unwrap(), dir);
+            assert_eq!(
+                EnvReader::with_prefix("PATH_")
+                    .get_writable_dir("DIR")
+                    .unwrap(),
+                dir
+            );
+            assert!(matches!(
+                get_existing_file("PATH_DIR"),
+                Err(EnvError::InvalidPath { .. })
+            ));
+            assert!(matches!(
+                get_existing_dir("PATH_FILE"),
+                Err(EnvError::InvalidPath { .. })
+            ));
+        });
+
+        let entries: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(entries, ["config.toml"]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_missing_path_reports_key_and_path() {
+        with_vars(&[("PATH_GONE", "/definitely/not/here")], || {
+            let err = get_existing_file("PATH_GONE").unwrap_err();
+            let message = err.to_string();
+            assert!(message.contains("PATH_GONE"));
+            assert!(message.contains("/definitely/not/here"));
+            assert!(matches!(err, EnvError::InvalidPath { .. }));
+        });
+    }
+}
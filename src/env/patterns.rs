@@ -0,0 +1,69 @@
+//! Regular expression getters (`regex` feature).
+pub use regex::Regex;
+
+use super::env::{EnvError, get_required_from};
+use super::source::{EnvSource, ProcessEnv};
+
+/// Read a required variable and compile it as a regular expression, e.g. a
+/// URL rewrite rule or a log filter.
+///
+/// The value is used as written, since whitespace may be part of the
+/// pattern. A pattern that fails to compile is a `ParseError` whose
+/// `source()` is the `regex::Error`.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::env::{get_regex, test::with_vars};
+/// with_vars(&[("REWRITE_FROM", r"^/api/v1/(.*)$")], || {
+///     let rule = get_regex("REWRITE_FROM").unwrap();
+///     assert_eq!(rule.replace("/api/v1/users", "/api/v2/$1"), "/api/v2/users");
+/// });
+/// ```
+pub fn get_regex(key: &str) -> Result<Regex, EnvError> {
+    get_regex_from(&ProcessEnv, key)
+}
+
+pub(crate) fn get_regex_from(source: &impl EnvSource, key: &str) -> Result<Regex, EnvError> {
+    let value = get_required_from(source, key)?;
+    Regex::new(&value).map_err(|e| {
+        EnvError::parse_error(key, value.as_str())
+            .with_expected("regular expression")
+            .with_source(e)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test::{with_vars, without_vars};
+
+    #[test]
+    fn test_get_regex() {
+        with_vars(&[("REGEX_FILTER", r"^health(check)?$")], || {
+            let filter = get_regex("REGEX_FILTER").unwrap();
+            assert!(filter.is_match("healthcheck"));
+            assert!(!filter.is_match("/healthcheck"));
+        });
+    }
+
+    #[test]
+    fn test_get_regex_invalid() {
+        with_vars(&[("REGEX_BAD", "(unclosed")], || {
+            let err = get_regex("REGEX_BAD").unwrap_err();
+            assert!(matches!(
+                &err,
+                EnvError::ParseError { key, expected, source: Some(_), .. }
+                    if key == "REGEX_BAD" && expected.as_deref() == Some("regular expression")
+            ));
+            assert!(err.to_string().starts_with(
+                "Failed to parse environment variable `REGEX_BAD` as regular expression: (unclosed"
+            ));
+        });
+        without_vars(&["REGEX_MISSING"], || {
+            assert!(matches!(
+                get_regex("REGEX_MISSING"),
+                Err(EnvError::Missing(_))
+            ));
+        });
+    }
+}
@@ -0,0 +1,147 @@
+use super::env::{EnvError, get_required_from};
+use super::source::{EnvSource, ProcessEnv};
+
+/// How a bare number without `%` is interpreted by [`get_percent_with`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PercentFormat {
+    /// Values up to `1` are fractions, larger values are percentages, so both
+    /// `0.8` and `80` mean 80%.
+    #[default]
+    Auto,
+    /// Bare numbers are fractions: `0.8` means 80%.
+    Fraction,
+    /// Bare numbers are percentages: `80` means 80%.
+    Percent,
+}
+
+/// Read a percentage as a fraction in `[0, 1]`.
+///
+/// Accepts `80%`, `0.8` and `80`; see [`PercentFormat::Auto`].
+///
+/// Example:
+/// ```
+/// use common_utils_rs::env::{get_percent, test::with_vars};
+/// with_vars(&[("SAMPLE_RATE", "25%")], || {
+///     assert_eq!(get_percent("SAMPLE_RATE").unwrap(), 0.25);
+/// });
+/// ```
+pub fn get_percent(key: &str) -> Result<f64, EnvError> {
+    get_percent_with(key, PercentFormat::Auto)
+}
+
+/// Like [`get_percent`] with a fixed interpretation of bare numbers.
+pub fn get_percent_with(key: &str, format: PercentFormat) -> Result<f64, EnvError> {
+    get_percent_with_from(&ProcessEnv, key, format)
+}
+
+pub(crate) fn get_percent_with_from(
+    source: &impl EnvSource,
+    key: &str,
+    format: PercentFormat,
+) -> Result<f64, EnvError> {
+    let value = get_required_from(source, key)?;
+    let trimmed = value.trim();
+    let parse_error = || EnvError::parse_error(key, value.as_str()).with_expected("percentage");
+
+    let fraction = match trimmed.strip_suffix('%') {
+        Some(number) => {
+            number
+                .trim_end()
+                .parse::<f64>()
+                .map_err(|_| parse_error())?
+                / 100.0
+        }
+        None => {
+            let number = trimmed.parse::<f64>().map_err(|_| parse_error())?;
+            match format {
+                PercentFormat::Fraction => number,
+                PercentFormat::Percent => number / 100.0,
+                PercentFormat::Auto if number > 1.0 => number / 100.0,
+                PercentFormat::Auto => number,
+            }
+        }
+    };
+
+    if !(0.0..=1.0).contains(&fraction) {
+        return Err(EnvError::Invalid {
+            key: key.to_string(),
+            reason: format!("`{}` is not between 0% and 100%", trimmed),
+        });
+    }
+    Ok(fraction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test::with_vars;
+
+    #[test]
+    fn test_get_percent_auto() {
+        let vars = [
+            ("PERCENT_SIGN", "80%"),
+            ("PERCENT_FRACTION", "0.8"),
+            ("PERCENT_BARE", "80"),
+            ("PERCENT_ONE", "1"),
+        ];
+        with_vars(&vars, || {
+            assert_eq!(get_percent("PERCENT_SIGN").unwrap(), 0.8);
+            assert_eq!(get_percent("PERCENT_FRACTION").unwrap(), 0.8);
+            assert_eq!(get_percent("PERCENT_BARE").unwrap(), 0.8);
+            assert_eq!(get_percent("PERCENT_ONE").unwrap(), 1.0);
+        });
+    }
+
+    #[test]
+    fn test_get_percent_with_format() {
+        with_vars(
+            &[("PERCENT_FMT", "0.5"), ("PERCENT_FMT_SIGN", "50 %")],
+            || {
+                assert_eq!(
+                    get_percent_with("PERCENT_FMT", PercentFormat::Fraction).unwrap(),
+                    0.5
+                );
+                assert_eq!(
+                    get_percent_with("PERCENT_FMT", PercentFormat::Percent).unwrap(),
+                    0.005
+                );
+                assert_eq!(
+                    get_percent_with("PERCENT_FMT_SIGN", PercentFormat::Fraction).unwrap(),
+                    0.5
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn test_get_percent_errors() {
+        let vars = [
+            ("PERCENT_HIGH", "120%"),
+            ("PERCENT_NEGATIVE", "-0.1"),
+            ("PERCENT_NAN", "NaN"),
+            ("PERCENT_TEXT", "lots"),
+        ];
+        with_vars(&vars, || {
+            assert!(matches!(
+                get_percent("PERCENT_HIGH"),
+                Err(EnvError::Invalid { .. })
+            ));
+            assert!(matches!(
+                get_percent("PERCENT_NEGATIVE"),
+                Err(EnvError::Invalid { .. })
+            ));
+            assert!(matches!(
+                get_percent("PERCENT_NAN"),
+                Err(EnvError::Invalid { .. })
+            ));
+            assert!(matches!(
+                get_percent("PERCENT_TEXT"),
+                Err(EnvError::ParseError { .. })
+            ));
+            assert!(matches!(
+                get_percent_with("PERCENT_HIGH", PercentFormat::Percent),
+                Err(EnvError::Invalid { .. })
+            ));
+        });
+    }
+}
@@ -0,0 +1,216 @@
+//! Profile overlays such as `APP_ENV=staging`.
+//!
+//! With a profile active, every lookup of `KEY` first tries the
+//! profile-specific `STAGING_KEY` and then the base `KEY`. The profile name
+//! is uppercased for keys, with `-` and `.` replaced by `_`.
+//!
+//! [`load_profile`] also reads `.env` and `.env.<profile>`, which gives this
+//! precedence for `DB_URL` when `APP_ENV=staging`:
+//!
+//! 1. `STAGING_DB_URL` from the process environment, `.env.staging`, then `.env`
+//! 2. `DB_URL` from the process environment, `.env.staging`, then `.env`
+//!
+//! Example:
+//! ```
+//! use std::collections::HashMap;
+//! use common_utils_rs::env::{EnvReader, Profiled};
+//!
+//! let vars = HashMap::from([
+//!     ("APP_ENV".to_string(), "staging".to_string()),
+//!     ("DB_URL".to_string(), "postgres://localhost/dev".to_string()),
+//!     ("STAGING_DB_URL".to_string(), "postgres://staging/app".to_string()),
+//! ]);
+//! let reader = EnvReader::from_source(Profiled::from_selector("APP_ENV", vars));
+//! assert_eq!(reader.get_required("DB_URL").unwrap(), "postgres://staging/app");
+//! ```
+use std::ffi::OsString;
+use std::io;
+use std::path::Path;
+
+use super::env::EnvError;
+use super::source::{EnvSource, FileSource, Layered, ProcessEnv};
+
+/// A source that prefers profile-specific variables over base ones.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Profiled<S> {
+    profile: Option<String>,
+    source: S,
+}
+
+impl<S: EnvSource> Profiled<S> {
+    /// Overlay `profile` on `source`.
+    pub fn new(profile: impl Into<String>, source: S) -> Self {
+        let profile = profile.into();
+        Self {
+            profile: Some(profile).filter(|p| !p.trim().is_empty()),
+            source,
+        }
+    }
+
+    /// Overlay the profile named by the `selector` variable of `source`, or
+    /// pass lookups through unchanged when it is unset or empty.
+    pub fn from_selector(selector: &str, source: S) -> Self {
+        let profile = source.get(selector).filter(|p| !p.trim().is_empty());
+        Self { profile, source }
+    }
+
+    /// The active profile, as configured.
+    pub fn profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
+    /// The profile-specific variable name for `key`, e.g. `STAGING_DB_URL`.
+    pub fn profile_key(&self, key: &str) -> Option<String> {
+        self.profile
+            .as_deref()
+            .map(|profile| format!("{}_{}", key_prefix(profile), key))
+    }
+
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+}
+
+fn key_prefix(profile: &str) -> String {
+    profile
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '-' | '.' => '_',
+            c => c.to_ascii_uppercase(),
+        })
+        .collect()
+}
+
+impl<S: EnvSource> EnvSource for Profiled<S> {
+    fn get(&self, key: &str) -> Option<String> {
+        self.profile_key(key)
+            .and_then(|profiled| self.source.get(&profiled))
+            .or_else(|| self.source.get(key))
+    }
+
+    fn get_os(&self, key: &str) -> Option<OsString> {
+        self.profile_key(key)
+            .and_then(|profiled| self.source.get_os(&profiled))
+            .or_else(|| self.source.get_os(key))
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.source.keys()
+    }
+}
+
+/// The source built by [`load_profile`].
+pub type ProfileSource = Profiled<Layered<ProcessEnv, Layered<FileSource, FileSource>>>;
+
+/// Select a profile from the `selector` variable and layer the process
+/// environment over `.env.<profile>` and `.env` in the current directory.
+///
+/// The selector itself may come from the process environment or `.env`.
+/// Missing files are skipped; the profile file name uses the lowercased
+/// profile. See the [module docs](self) for the full precedence.
+///
+/// Example:
+/// ```no_run
+/// use common_utils_rs::env::{load_profile, EnvReader};
+/// let reader = EnvReader::from_source(load_profile("APP_ENV").unwrap());
+/// let db_url = reader.get_required("DB_URL").unwrap();
+/// ```
+pub fn load_profile(selector: &str) -> Result<ProfileSource, EnvError> {
+    load_profile_in(selector, ".")
+}
+
+/// Like [`load_profile`], reading the dotenv files from `dir`.
+pub fn load_profile_in(selector: &str, dir: impl AsRef<Path>) -> Result<ProfileSource, EnvError> {
+    let dir = dir.as_ref();
+    let base = open_optional(&dir.join(".env"))?;
+    let profile = ProcessEnv
+        .get(selector)
+        .or_else(|| base.get(selector))
+        .filter(|p| !p.trim().is_empty());
+
+    let overlay = match &profile {
+        Some(profile) => {
+            open_optional(&dir.join(format!(".env.{}", profile.trim().to_lowercase())))?
+        }
+        None => FileSource::default(),
+    };
+    Ok(Profiled {
+        profile,
+        source: ProcessEnv.or(overlay.or(base)),
+    })
+}
+
+fn open_optional(path: &Path) -> Result<FileSource, EnvError> {
+    match FileSource::open(path) {
+        Err(EnvError::Io { source, .. }) if source.kind() == io::ErrorKind::NotFound => {
+            Ok(FileSource::default())
+        }
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test::fixtures::map;
+    use crate::env::test::{ScopedEnv, without_vars};
+    use std::{env, fs};
+
+    #[test]
+    fn test_profiled_prefers_profile_key() {
+        let vars = map(&[
+            ("MODE", "eu-staging"),
+            ("DB_URL", "base"),
+            ("EU_STAGING_DB_URL", "staging"),
+            ("PORT", "80"),
+        ]);
+        let profiled = Profiled::from_selector("MODE", vars.clone());
+        assert_eq!(profiled.profile(), Some("eu-staging"));
+        assert_eq!(profiled.get("DB_URL"), Some("staging".to_string()));
+        assert_eq!(profiled.get("PORT"), Some("80".to_string()));
+        assert_eq!(profiled.get("HOST"), None);
+
+        let plain = Profiled::from_selector("UNSET_MODE", vars);
+        assert_eq!(plain.profile(), None);
+        assert_eq!(plain.get("DB_URL"), Some("base".to_string()));
+        assert_eq!(Profiled::new(" ", map(&[])).profile(), None);
+    }
+
+    #[test]
+    fn test_load_profile_in_precedence() {
+        let dir = env::temp_dir().join(format!("common-utils-rs-profile-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join(".env"),
+            "PROFILE_T_SELECT=Staging\nPROFILE_T_A=base-file\nPROFILE_T_B=base-file\nSTAGING_PROFILE_T_C=profiled-base-file\nPROFILE_T_C=base-file\n",
+        )
+        .unwrap();
+        fs::write(dir.join(".env.staging"), "PROFILE_T_A=staging-file\n").unwrap();
+
+        {
+            let mut scope = ScopedEnv::new();
+            scope
+                .set("PROFILE_T_C", "process")
+                .remove("PROFILE_T_SELECT");
+            let source = load_profile_in("PROFILE_T_SELECT", &dir).unwrap();
+            assert_eq!(source.profile(), Some("Staging"));
+            assert_eq!(source.get("PROFILE_T_A"), Some("staging-file".to_string()));
+            assert_eq!(source.get("PROFILE_T_B"), Some("base-file".to_string()));
+            assert_eq!(
+                source.get("PROFILE_T_C"),
+                Some("profiled-base-file".to_string())
+            );
+        }
+
+        without_vars(&["PROFILE_T_SELECT"], || {
+            let empty = dir.join("empty");
+            fs::create_dir_all(&empty).unwrap();
+            let source = load_profile_in("PROFILE_T_SELECT", &empty).unwrap();
+            assert_eq!(source.profile(), None);
+            assert_eq!(source.get("PROFILE_T_A"), None);
+        });
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,171 @@
+//! A `figment` provider over `PREFIX__SECTION__KEY` variables (`figment`
+//! feature).
+//!
+//! [`provider`] applies the same nesting rules as [`get_nested`], so an
+//! existing figment stack can merge environment overrides that the typed
+//! getters also understand.
+//!
+//! Example:
+//! ```
+//! use figment::Figment;
+//! use serde::Deserialize;
+//! use common_utils_rs::env::{provider, test::with_vars};
+//!
+//! #[derive(Deserialize)]
+//! struct Database {
+//!     host: String,
+//!     port: u16,
+//! }
+//!
+//! #[derive(Deserialize)]
+//! struct Config {
+//!     database: Database,
+//! }
+//!
+//! let vars = [("PROVIDER_DOC__DATABASE__HOST", "db"), ("PROVIDER_DOC__DATABASE__PORT", "5432")];
+//! with_vars(&vars, || {
+//!     let config: Config = Figment::new().merge(provider("PROVIDER_DOC")).extract().unwrap();
+//!     assert_eq!(config.database.host, "db");
+//!     assert_eq!(config.database.port, 5432);
+//! });
+//! ```
+use figment::value::{Dict, Map, Value};
+use figment::{Error, Metadata, Profile, Provider};
+
+use super::nested::{EnvTree, NESTED_SEPARATOR, get_nested};
+
+/// Provides the `prefix__...` variables of the process environment to
+/// figment, built by [`provider`].
+#[derive(Debug, Clone)]
+pub struct EnvProvider {
+    prefix: String,
+    profile: Profile,
+}
+
+/// A figment provider for every `prefix__...` variable, read when the
+/// configuration is extracted.
+///
+/// Keys are lower-cased per level (`APP__DATABASE__HOST` becomes
+/// `database.host`) and values are parsed the way figment's own `Env`
+/// provider parses them, so `5432` is a number and `true` a boolean. When a
+/// variable has both a value and nested keys (`APP__DB` and `APP__DB__HOST`),
+/// the nested keys win.
+pub fn provider(prefix: &str) -> EnvProvider {
+    EnvProvider {
+        prefix: prefix.to_string(),
+        profile: Profile::Default,
+    }
+}
+
+impl EnvProvider {
+    /// Emit the values into `profile` instead of the default profile.
+    pub fn profile(mut self, profile: impl Into<Profile>) -> Self {
+        self.profile = profile.into();
+        self
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+}
+
+impl Provider for EnvProvider {
+    fn metadata(&self) -> Metadata {
+        let prefix = self.prefix.clone();
+        Metadata::named(format!("`{}` environment variables", self.prefix)).interpolater(
+            move |_: &Profile, keys: &[&str]| {
+                std::iter::once(prefix.clone())
+                    .chain(keys.iter().map(|key| key.to_uppercase()))
+                    .collect::<Vec<_>>()
+                    .join(NESTED_SEPARATOR)
+            },
+        )
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        Ok(self.profile.collect(to_dict(&get_nested(&self.prefix))))
+    }
+}
+
+fn to_dict(tree: &EnvTree) -> Dict {
+    tree.children()
+        .map(|(name, node)| (name.to_string(), to_value(node)))
+        .collect()
+}
+
+fn to_value(node: &EnvTree) -> Value {
+    match node.value() {
+        Some(value) if node.children().next().is_none() => {
+            value.parse().unwrap_or_else(|never| match never {})
+        }
+        _ => Value::from(to_dict(node)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test::with_vars;
+    use figment::Figment;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Database {
+        host: String,
+        port: u16,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Config {
+        name: String,
+        debug: bool,
+        database: Database,
+    }
+
+    #[test]
+    fn test_provider_nests_and_parses_values() {
+        let vars = [
+            ("PROVIDER_T__NAME", "svc"),
+            ("PROVIDER_T__DEBUG", "true"),
+            ("PROVIDER_T__DATABASE__HOST", "db"),
+            ("PROVIDER_T__DATABASE__PORT", "5432"),
+        ];
+        with_vars(&vars, || {
+            let config: Config = Figment::new()
+                .merge(provider("PROVIDER_T"))
+                .extract()
+                .unwrap();
+            assert_eq!(
+                config,
+                Config {
+                    name: "svc".to_string(),
+                    debug: true,
+                    database: Database {
+                        host: "db".to_string(),
+                        port: 5432
+                    },
+                }
+            );
+
+            let staging = Figment::new()
+                .merge(provider("PROVIDER_T").profile("staging"))
+                .select("staging");
+            assert_eq!(staging.extract_inner::<u16>("database.port").unwrap(), 5432);
+            assert!(
+                Figment::new()
+                    .merge(provider("PROVIDER_T").profile("staging"))
+                    .extract_inner::<u16>("database.port")
+                    .is_err()
+            );
+        });
+    }
+
+    #[test]
+    fn test_provider_metadata_names_variables() {
+        let metadata = provider("APP").metadata();
+        assert_eq!(
+            metadata.interpolate(&Profile::Default, &["database", "port"]),
+            "APP__DATABASE__PORT"
+        );
+    }
+}
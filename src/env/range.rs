@@ -0,0 +1,131 @@
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use super::env::{BoxError, EnvError, get_required_from, type_name};
+use super::source::{EnvSource, ProcessEnv};
+
+/// Read an inclusive range such as `8000-8100`, `8000..8100` or `8000..=8100`.
+///
+/// All three forms include the end value. A single value `8000` yields
+/// `8000..=8000`. Fails with [`EnvError::Invalid`] when start > end.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::env::{get_range, test::with_vars};
+/// with_vars(&[("PORT_RANGE", "8000-8100")], || {
+///     assert_eq!(get_range::<u16>("PORT_RANGE").unwrap(), 8000..=8100);
+/// });
+/// ```
+pub fn get_range<T>(key: &str) -> Result<RangeInclusive<T>, EnvError>
+where
+    T: FromStr + PartialOrd,
+    T::Err: Into<BoxError>,
+{
+    get_range_from(&ProcessEnv, key)
+}
+
+pub(crate) fn get_range_from<T>(
+    source: &impl EnvSource,
+    key: &str,
+) -> Result<RangeInclusive<T>, EnvError>
+where
+    T: FromStr + PartialOrd,
+    T::Err: Into<BoxError>,
+{
+    let value = get_required_from(source, key)?;
+    let parse_error = || {
+        EnvError::parse_error(key, value.as_str())
+            .with_expected(format!("range of {}", type_name::<T>()))
+    };
+
+    let (start, end) = split_range(&value).ok_or_else(parse_error)?;
+    let start: T = start
+        .trim()
+        .parse()
+        .map_err(|e| parse_error().with_source(e))?;
+    let end: T = end
+        .trim()
+        .parse()
+        .map_err(|e| parse_error().with_source(e))?;
+
+    if start > end {
+        return Err(EnvError::Invalid {
+            key: key.to_string(),
+            reason: format!("range `{}` starts after it ends", value.trim()),
+        });
+    }
+    Ok(start..=end)
+}
+
+/// Split on `..=`, `..` or the first `-` that is not a leading sign.
+fn split_range(value: &str) -> Option<(&str, &str)> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+    if let Some(parts) = value.split_once("..=") {
+        return Some(parts);
+    }
+    if let Some(parts) = value.split_once("..") {
+        return Some(parts);
+    }
+    // Skip the first character so a leading minus sign is not a separator.
+    let skip = value.chars().next()?.len_utf8();
+    match value[skip..].find('-') {
+        Some(i) => Some((&value[..skip + i], &value[skip + i + 1..])),
+        None => Some((value, value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test::{with_vars, without_vars};
+
+    #[test]
+    fn test_get_range_forms() {
+        let vars = [
+            ("RANGE_DASH", "8000-8100"),
+            ("RANGE_DOTS", "8000..8100"),
+            ("RANGE_DOTS_EQ", " 8000 ..= 8100 "),
+            ("RANGE_SINGLE", "8080"),
+        ];
+        with_vars(&vars, || {
+            assert_eq!(get_range::<u16>("RANGE_DASH").unwrap(), 8000..=8100);
+            assert_eq!(get_range::<u16>("RANGE_DOTS").unwrap(), 8000..=8100);
+            assert_eq!(get_range::<u16>("RANGE_DOTS_EQ").unwrap(), 8000..=8100);
+            assert_eq!(get_range::<u16>("RANGE_SINGLE").unwrap(), 8080..=8080);
+        });
+    }
+
+    #[test]
+    fn test_get_range_negative_bounds() {
+        with_vars(
+            &[("RANGE_NEG", "-10--2"), ("RANGE_NEG_DOTS", "-5..5")],
+            || {
+                assert_eq!(get_range::<i32>("RANGE_NEG").unwrap(), -10..=-2);
+                assert_eq!(get_range::<i32>("RANGE_NEG_DOTS").unwrap(), -5..=5);
+            },
+        );
+    }
+
+    #[test]
+    fn test_get_range_errors() {
+        with_vars(&[("RANGE_BACKWARDS", "9-1"), ("RANGE_BAD", "a-b")], || {
+            assert!(matches!(
+                get_range::<u8>("RANGE_BACKWARDS"),
+                Err(EnvError::Invalid { key, .. }) if key == "RANGE_BACKWARDS"
+            ));
+            assert!(matches!(
+                get_range::<u8>("RANGE_BAD"),
+                Err(EnvError::ParseError { .. })
+            ));
+        });
+        without_vars(&["RANGE_UNSET"], || {
+            assert!(matches!(
+                get_range::<u8>("RANGE_UNSET"),
+                Err(EnvError::Missing(_))
+            ));
+        });
+    }
+}
@@ -0,0 +1,640 @@
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::net::{IpAddr, SocketAddr};
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+use super::bytesize::ByteSize;
+use super::cron::{self, CronSchedule};
+use super::datetime;
+use super::duration;
+use super::encoding;
+use super::env::{self, BoxError, EnvError, ListOptions, MemoryUnits};
+use super::expand::{self, UndefinedPolicy};
+use super::fallback::{self, Found};
+use super::headers;
+#[cfg(feature = "json")]
+use super::json;
+#[cfg(any(feature = "log", feature = "env-filter"))]
+use super::level;
+use super::net::{self, IpNet, PortRange};
+use super::paths;
+#[cfg(feature = "regex")]
+use super::patterns::{self, Regex};
+use super::percent::{self, PercentFormat};
+use super::range;
+use super::secret::{self, Secret};
+use super::source::{EnvSource, ProcessEnv};
+#[cfg(feature = "url")]
+use super::urls::{self, Url};
+#[cfg(feature = "uuid")]
+use super::uuids::{self, Uuid};
+use super::validator::{self, Validate};
+use super::version::{self, Version, VersionReq};
+
+/// Reads environment variables scoped to a common key prefix.
+///
+/// Every lookup prepends the prefix to the requested key, so components of an
+/// application can read their own namespaced configuration without collisions.
+/// By default variables come from the process environment; use
+/// [`EnvReader::from_source`] or [`EnvReader::with_source`] to read from any
+/// other [`EnvSource`].
+///
+/// Example:
+/// ```
+/// use common_utils_rs::env::EnvReader;
+/// let reader = EnvReader::with_prefix("MYAPP_");
+/// let host = reader.get_or_default("HOST", "127.0.0.1"); // reads MYAPP_HOST
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnvReader<S = ProcessEnv> {
+    prefix: String,
+    source: S,
+    empty_is_missing: bool,
+    key_match: KeyMatch,
+}
+
+/// How [`EnvReader`] matches requested keys against variable names.
+///
+/// Windows treats variable names case-insensitively and some shells and
+/// launchers pass them through with inconsistent casing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KeyMatch {
+    /// Only the exact name is looked up.
+    #[default]
+    Exact,
+    /// Try the exact name, then its uppercase form.
+    Upper,
+    /// Try the exact name, then any variable whose name differs only in
+    /// case. When several match, the lexicographically first name wins.
+    /// Requires a source that implements [`EnvSource::keys`].
+    Insensitive,
+}
+
+impl EnvReader {
+    /// Create a reader without a prefix.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a reader that prepends `prefix` to every key.
+    pub fn with_prefix(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            source: ProcessEnv,
+            empty_is_missing: false,
+            key_match: KeyMatch::Exact,
+        }
+    }
+}
+
+impl<S: EnvSource> EnvReader<S> {
+    /// Create a reader without a prefix that reads from `source`.
+    pub fn from_source(source: S) -> Self {
+        Self {
+            prefix: String::new(),
+            source,
+            empty_is_missing: false,
+            key_match: KeyMatch::Exact,
+        }
+    }
+
+    /// Replace the source, keeping the prefix.
+    pub fn with_source<T: EnvSource>(self, source: T) -> EnvReader<T> {
+        EnvReader {
+            prefix: self.prefix,
+            source,
+            empty_is_missing: self.empty_is_missing,
+            key_match: self.key_match,
+        }
+    }
+
+    /// Treat variables set to an empty string as unset, so `FOO=""` yields
+    /// [`EnvError::Missing`] or the default instead of `""`.
+    pub fn empty_is_missing(mut self, enabled: bool) -> Self {
+        self.empty_is_missing = enabled;
+        self
+    }
+
+    /// Choose how keys are matched against variable names; see [`KeyMatch`].
+    ///
+    /// Example:
+    /// ```
+    /// use std::collections::HashMap;
+    /// use common_utils_rs::env::{EnvReader, KeyMatch};
+    ///
+    /// let vars = HashMap::from([("Path".to_string(), "/bin".to_string())]);
+    /// let reader = EnvReader::from_source(vars).key_match(KeyMatch::Insensitive);
+    /// assert_eq!(reader.get_required("PATH").unwrap(), "/bin");
+    /// ```
+    pub fn key_match(mut self, key_match: KeyMatch) -> Self {
+        self.key_match = key_match;
+        self
+    }
+
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Full variable name for `key`, including the prefix.
+    pub fn key(&self, key: &str) -> String {
+        format!("{}{}", self.prefix, key)
+    }
+
+    fn view(&self) -> View<'_, S> {
+        View {
+            source: &self.source,
+            empty_is_missing: self.empty_is_missing,
+            key_match: self.key_match,
+        }
+    }
+
+    pub fn get_required(&self, key: &str) -> Result<String, EnvError> {
+        env::get_required_from(&self.view(), &self.key(key))
+    }
+
+    pub fn get_required_os(&self, key: &str) -> Result<OsString, EnvError> {
+        env::get_required_os_from(&self.view(), &self.key(key))
+    }
+
+    pub fn get_nonempty(&self, key: &str) -> Result<String, EnvError> {
+        env::get_nonempty_from(&self.view(), &self.key(key))
+    }
+
+    pub fn get_or_default(&self, key: &str, default: &str) -> String {
+        env::get_or_default_from(&self.view(), &self.key(key), default)
+    }
+
+    pub fn get_parsed<T>(&self, key: &str) -> Result<T, EnvError>
+    where
+        T: FromStr,
+        T::Err: Into<BoxError>,
+    {
+        env::get_parsed_from(&self.view(), &self.key(key))
+    }
+
+    pub fn get_parsed_or_default<T>(&self, key: &str, default: T) -> T
+    where
+        T: FromStr,
+    {
+        env::get_parsed_or_default_from(&self.view(), &self.key(key), default)
+    }
+
+    pub fn get_parsed_validated<T, V>(&self, key: &str, validator: V) -> Result<T, EnvError>
+    where
+        T: FromStr,
+        T::Err: Into<BoxError>,
+        V: Validate<T>,
+    {
+        validator::get_parsed_validated_from(&self.view(), &self.key(key), validator)
+    }
+
+    /// Read `key`, defaulting to `fallback_key`; both are prefixed.
+    pub fn get_parsed_or_from<T>(&self, key: &str, fallback_key: &str) -> Result<Found<T>, EnvError>
+    where
+        T: FromStr,
+        T::Err: Into<BoxError>,
+    {
+        fallback::get_parsed_or_from_from(&self.view(), &self.key(key), &self.key(fallback_key))
+    }
+
+    pub fn get_enum<T>(&self, key: &str) -> Result<T, EnvError>
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        env::get_enum_from(&self.view(), &self.key(key))
+    }
+
+    pub fn get_one_of<'a>(&self, key: &str, allowed: &[&'a str]) -> Result<&'a str, EnvError> {
+        env::get_one_of_from(&self.view(), &self.key(key), allowed)
+    }
+
+    pub fn get_bool(&self, key: &str, default: bool) -> bool {
+        env::get_bool_from(&self.view(), &self.key(key), default)
+    }
+
+    pub fn get_bool_strict(&self, key: &str) -> Result<bool, EnvError> {
+        env::get_bool_strict_from(&self.view(), &self.key(key))
+    }
+
+    pub fn get_bool_opt(&self, key: &str) -> Result<Option<bool>, EnvError> {
+        env::get_bool_opt_from(&self.view(), &self.key(key))
+    }
+
+    pub fn get_list(&self, key: &str) -> Result<Vec<String>, EnvError> {
+        env::get_list_from(&self.view(), &self.key(key))
+    }
+
+    pub fn get_list_with(&self, key: &str, options: &ListOptions) -> Result<Vec<String>, EnvError> {
+        env::get_list_with_from(&self.view(), &self.key(key), options)
+    }
+
+    pub fn get_parsed_list<T>(&self, key: &str) -> Result<Vec<T>, EnvError>
+    where
+        T: FromStr,
+        T::Err: Into<BoxError>,
+    {
+        env::get_parsed_list_from(&self.view(), &self.key(key))
+    }
+
+    pub fn get_map(&self, key: &str) -> Result<HashMap<String, String>, EnvError> {
+        env::get_map_with_from(&self.view(), &self.key(key), ",", "=")
+    }
+
+    pub fn get_weighted_list(&self, key: &str) -> Result<Vec<(String, u32)>, EnvError> {
+        env::get_weighted_list_from(&self.view(), &self.key(key))
+    }
+
+    pub fn get_headers(&self, key: &str) -> Result<Vec<(String, String)>, EnvError> {
+        headers::get_headers_from(&self.view(), &self.key(key))
+    }
+
+    /// Every variable under this reader's prefix followed by `prefix`, keyed
+    /// by the rest of the name.
+    pub fn get_all_with_prefix(&self, prefix: &str) -> HashMap<String, String> {
+        env::get_all_with_prefix_from(&self.view(), &self.key(prefix))
+    }
+
+    pub fn get_range<T>(&self, key: &str) -> Result<RangeInclusive<T>, EnvError>
+    where
+        T: FromStr + PartialOrd,
+        T::Err: Into<BoxError>,
+    {
+        range::get_range_from(&self.view(), &self.key(key))
+    }
+
+    pub fn get_percent(&self, key: &str) -> Result<f64, EnvError> {
+        percent::get_percent_with_from(&self.view(), &self.key(key), PercentFormat::Auto)
+    }
+
+    pub fn get_memory_size(&self, key: &str) -> Result<ByteSize, EnvError> {
+        env::get_memory_size_from(&self.view(), &self.key(key))
+    }
+
+    pub fn get_memory_size_with(
+        &self,
+        key: &str,
+        units: MemoryUnits,
+    ) -> Result<ByteSize, EnvError> {
+        env::get_memory_size_with_from(&self.view(), &self.key(key), units)
+    }
+
+    pub fn get_memory_size_or_default(&self, key: &str, default: ByteSize) -> ByteSize {
+        self.get_memory_size(key).unwrap_or(default)
+    }
+
+    pub fn get_duration(&self, key: &str) -> Result<Duration, EnvError> {
+        duration::get_duration_from(&self.view(), &self.key(key))
+    }
+
+    pub fn get_duration_or_default(&self, key: &str, default: Duration) -> Duration {
+        self.get_duration(key).unwrap_or(default)
+    }
+
+    pub fn get_datetime(&self, key: &str) -> Result<SystemTime, EnvError> {
+        datetime::get_datetime_from(&self.view(), &self.key(key))
+    }
+
+    pub fn get_unix_timestamp(&self, key: &str) -> Result<SystemTime, EnvError> {
+        datetime::get_unix_timestamp_from(&self.view(), &self.key(key))
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn get_chrono_datetime(
+        &self,
+        key: &str,
+    ) -> Result<chrono::DateTime<chrono::FixedOffset>, EnvError> {
+        datetime::get_chrono_datetime_from(&self.view(), &self.key(key))
+    }
+
+    pub fn get_cron(&self, key: &str) -> Result<CronSchedule, EnvError> {
+        cron::get_cron_from(&self.view(), &self.key(key))
+    }
+
+    /// Expand `${VAR}` references in the prefixed variable. Referenced names
+    /// are used as written, without the prefix.
+    pub fn get_expanded(&self, key: &str) -> Result<String, EnvError> {
+        expand::get_expanded_with_from(&self.view(), &self.key(key), UndefinedPolicy::Error)
+    }
+
+    pub fn get_port(&self, key: &str) -> Result<u16, EnvError> {
+        net::get_port_with_from(&self.view(), &self.key(key), PortRange::Any)
+    }
+
+    pub fn get_port_with(&self, key: &str, range: PortRange) -> Result<u16, EnvError> {
+        net::get_port_with_from(&self.view(), &self.key(key), range)
+    }
+
+    pub fn get_socket_addr(&self, key: &str) -> Result<SocketAddr, EnvError> {
+        net::get_socket_addr_from(&self.view(), &self.key(key))
+    }
+
+    pub fn get_socket_addr_or_default(&self, key: &str, default: SocketAddr) -> SocketAddr {
+        self.get_socket_addr(key).unwrap_or(default)
+    }
+
+    pub fn get_socket_addrs(&self, key: &str) -> Result<Vec<SocketAddr>, EnvError> {
+        net::get_socket_addrs_from(&self.view(), &self.key(key))
+    }
+
+    pub fn get_ip(&self, key: &str) -> Result<IpAddr, EnvError> {
+        net::get_ip_from(&self.view(), &self.key(key))
+    }
+
+    pub fn get_cidr(&self, key: &str) -> Result<IpNet, EnvError> {
+        net::get_cidr_from(&self.view(), &self.key(key))
+    }
+
+    pub fn get_cidrs(&self, key: &str) -> Result<Vec<IpNet>, EnvError> {
+        net::get_cidrs_from(&self.view(), &self.key(key))
+    }
+
+    pub fn get_semver(&self, key: &str) -> Result<Version, EnvError> {
+        version::get_semver_from(&self.view(), &self.key(key))
+    }
+
+    pub fn get_semver_matching(&self, key: &str, requirement: &str) -> Result<Version, EnvError> {
+        version::get_semver_matching_from(&self.view(), &self.key(key), requirement)
+    }
+
+    pub fn get_semver_req(&self, key: &str) -> Result<VersionReq, EnvError> {
+        version::get_semver_req_from(&self.view(), &self.key(key))
+    }
+
+    pub fn get_path(&self, key: &str) -> Result<PathBuf, EnvError> {
+        paths::get_path_from(&self.view(), &self.key(key))
+    }
+
+    pub fn get_existing_file(&self, key: &str) -> Result<PathBuf, EnvError> {
+        paths::get_existing_file_from(&self.view(), &self.key(key))
+    }
+
+    pub fn get_existing_dir(&self, key: &str) -> Result<PathBuf, EnvError> {
+        paths::get_existing_dir_from(&self.view(), &self.key(key))
+    }
+
+    pub fn get_writable_dir(&self, key: &str) -> Result<PathBuf, EnvError> {
+        paths::get_writable_dir_from(&self.view(), &self.key(key))
+    }
+
+    pub fn get_secret(&self, key: &str) -> Result<Secret<String>, EnvError> {
+        secret::get_secret_from(&self.view(), &self.key(key))
+    }
+
+    pub fn get_required_or_file(&self, key: &str) -> Result<String, EnvError> {
+        secret::get_required_or_file_from(&self.view(), &self.key(key))
+    }
+
+    pub fn get_base64(&self, key: &str) -> Result<Vec<u8>, EnvError> {
+        encoding::get_base64_from(&self.view(), &self.key(key))
+    }
+
+    pub fn get_hex(&self, key: &str) -> Result<Vec<u8>, EnvError> {
+        encoding::get_hex_from(&self.view(), &self.key(key))
+    }
+
+    pub fn get_hex_exact(&self, key: &str, len: usize) -> Result<Vec<u8>, EnvError> {
+        encoding::get_hex_exact_from(&self.view(), &self.key(key), len)
+    }
+
+    /// Check that every prefixed key is set, reporting all missing ones.
+    pub fn require_all(&self, keys: &[&str]) -> Result<(), EnvError> {
+        let keys: Vec<String> = keys.iter().map(|key| self.key(key)).collect();
+        let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+        validator::require_all_from(&self.view(), &keys)
+    }
+
+    #[cfg(feature = "json")]
+    pub fn get_json<T>(&self, key: &str) -> Result<T, EnvError>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        json::get_json_from(&self.view(), &self.key(key))
+    }
+
+    #[cfg(feature = "log")]
+    pub fn get_log_level(&self, key: &str) -> Result<level::LevelFilter, EnvError> {
+        level::get_log_level_from(&self.view(), &self.key(key))
+    }
+
+    #[cfg(feature = "env-filter")]
+    pub fn get_env_filter(&self, key: &str) -> Result<level::EnvFilter, EnvError> {
+        level::get_env_filter_from(&self.view(), &self.key(key))
+    }
+
+    #[cfg(feature = "regex")]
+    pub fn get_regex(&self, key: &str) -> Result<Regex, EnvError> {
+        patterns::get_regex_from(&self.view(), &self.key(key))
+    }
+
+    #[cfg(feature = "uuid")]
+    pub fn get_uuid(&self, key: &str) -> Result<Uuid, EnvError> {
+        uuids::get_uuid_from(&self.view(), &self.key(key))
+    }
+
+    #[cfg(feature = "url")]
+    pub fn get_url(&self, key: &str) -> Result<Url, EnvError> {
+        urls::get_url_from(&self.view(), &self.key(key))
+    }
+
+    #[cfg(feature = "url")]
+    pub fn get_url_or_default(&self, key: &str, default: Url) -> Url {
+        self.get_url(key).unwrap_or(default)
+    }
+}
+
+/// The reader's source with its lookup options applied.
+struct View<'a, S> {
+    source: &'a S,
+    empty_is_missing: bool,
+    key_match: KeyMatch,
+}
+
+impl<S: EnvSource> View<'_, S> {
+    /// Name of the variable that `key` resolves to under `key_match`.
+    fn resolve(&self, key: &str) -> Option<String> {
+        if self.source.get_os(key).is_some() {
+            return Some(key.to_string());
+        }
+        match self.key_match {
+            KeyMatch::Exact => None,
+            KeyMatch::Upper => {
+                let upper = key.to_uppercase();
+                self.source.get_os(&upper).map(|_| upper)
+            }
+            KeyMatch::Insensitive => {
+                let wanted = key.to_uppercase();
+                self.source
+                    .keys()
+                    .into_iter()
+                    .filter(|name| name.to_uppercase() == wanted)
+                    .min()
+            }
+        }
+    }
+}
+
+impl<S: EnvSource> EnvSource for View<'_, S> {
+    fn get(&self, key: &str) -> Option<String> {
+        self.source
+            .get(&self.resolve(key)?)
+            .filter(|value| !(self.empty_is_missing && value.is_empty()))
+    }
+
+    fn get_os(&self, key: &str) -> Option<OsString> {
+        self.source
+            .get_os(&self.resolve(key)?)
+            .filter(|value| !(self.empty_is_missing && value.is_empty()))
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.source.keys()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test::{with_vars, without_vars};
+
+    #[test]
+    fn test_reader_key_applies_prefix() {
+        let reader = EnvReader::with_prefix("MYAPP_");
+        assert_eq!(reader.prefix(), "MYAPP_");
+        assert_eq!(reader.key("HOST"), "MYAPP_HOST");
+        assert_eq!(EnvReader::new().key("HOST"), "HOST");
+    }
+
+    #[test]
+    fn test_reader_get_required_uses_prefix() {
+        with_vars(&[("READER_A_PORT", "8080")], || {
+            let reader = EnvReader::with_prefix("READER_A_");
+            assert_eq!(reader.get_required("PORT").unwrap(), "8080");
+            let port: u16 = reader.get_parsed("PORT").unwrap();
+            assert_eq!(port, 8080);
+        });
+    }
+
+    #[test]
+    fn test_reader_missing_reports_full_key() {
+        without_vars(&["READER_B_MISSING"], || {
+            let reader = EnvReader::with_prefix("READER_B_");
+            match reader.get_required("MISSING") {
+                Err(EnvError::Missing(key)) => assert_eq!(key, "READER_B_MISSING"),
+                other => panic!("unexpected result: {:?}", other),
+            }
+        });
+    }
+
+    #[test]
+    fn test_reader_prefixes_do_not_collide() {
+        with_vars(
+            &[("READER_C1_DEBUG", "true"), ("READER_C2_DEBUG", "false")],
+            || {
+                assert!(EnvReader::with_prefix("READER_C1_").get_bool("DEBUG", false));
+                assert!(!EnvReader::with_prefix("READER_C2_").get_bool("DEBUG", true));
+            },
+        );
+    }
+
+    #[test]
+    fn test_reader_from_map_source() {
+        let source: HashMap<String, String> = [("SVC_PORT", "9000"), ("SVC_TIMEOUT", "5s")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let reader = EnvReader::with_prefix("SVC_").with_source(source);
+        assert_eq!(reader.prefix(), "SVC_");
+        assert_eq!(reader.get_parsed::<u16>("PORT").unwrap(), 9000);
+        assert_eq!(
+            reader.get_duration("TIMEOUT").unwrap(),
+            Duration::from_secs(5)
+        );
+        assert!(matches!(
+            reader.get_required("HOST"),
+            Err(EnvError::Missing(key)) if key == "SVC_HOST"
+        ));
+
+        let all = reader.get_all_with_prefix("");
+        assert_eq!(all.len(), 2);
+        assert_eq!(all["TIMEOUT"], "5s");
+    }
+
+    #[test]
+    fn test_reader_require_all_uses_prefix() {
+        let source: HashMap<String, String> = [("SVC_A".to_string(), "1".to_string())]
+            .into_iter()
+            .collect();
+        let reader = EnvReader::with_prefix("SVC_").with_source(source);
+        assert!(reader.require_all(&["A"]).is_ok());
+        assert!(matches!(
+            reader.require_all(&["A", "B"]),
+            Err(EnvError::Missing(key)) if key == "SVC_B"
+        ));
+    }
+
+    #[test]
+    fn test_reader_empty_is_missing() {
+        let source: HashMap<String, String> =
+            [("EMPTY".to_string(), String::new())].into_iter().collect();
+        let reader = EnvReader::from_source(source);
+        assert_eq!(reader.get_required("EMPTY").unwrap(), "");
+
+        let reader = reader.empty_is_missing(true);
+        assert!(matches!(
+            reader.get_required("EMPTY"),
+            Err(EnvError::Missing(_))
+        ));
+        assert_eq!(reader.get_or_default("EMPTY", "x"), "x");
+        assert_eq!(reader.get_parsed_or_default("EMPTY", 3u8), 3);
+        assert!(reader.require_all(&["EMPTY"]).is_err());
+    }
+
+    #[test]
+    fn test_reader_key_match() {
+        let source: HashMap<String, String> =
+            [("APP_PORT", "80"), ("app_host", "a"), ("App_Host", "b")]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+
+        let exact = EnvReader::from_source(source.clone());
+        assert!(matches!(
+            exact.get_required("app_port"),
+            Err(EnvError::Missing(_))
+        ));
+
+        let upper = EnvReader::from_source(source.clone()).key_match(KeyMatch::Upper);
+        assert_eq!(upper.get_required("app_port").unwrap(), "80");
+        assert!(upper.get_required("APP_HOST").is_err());
+
+        let insensitive = EnvReader::from_source(source).key_match(KeyMatch::Insensitive);
+        assert_eq!(insensitive.get_required("app_host").unwrap(), "a");
+        assert_eq!(insensitive.get_required("APP_HOST").unwrap(), "b");
+        assert_eq!(insensitive.get_parsed::<u16>("App_Port").unwrap(), 80);
+        assert!(matches!(
+            insensitive.get_required("APP_USER"),
+            Err(EnvError::Missing(key)) if key == "APP_USER"
+        ));
+    }
+
+    #[test]
+    fn test_reader_defaults_and_lists() {
+        without_vars(&["READER_D_NAME", "READER_D_LIMIT"], || {
+            let reader = EnvReader::with_prefix("READER_D_");
+            assert_eq!(reader.get_or_default("NAME", "fallback"), "fallback");
+            assert_eq!(reader.get_parsed_or_default("LIMIT", 5u32), 5);
+        });
+        with_vars(&[("READER_D_TAGS", "x, y")], || {
+            let reader = EnvReader::with_prefix("READER_D_");
+            assert_eq!(reader.get_list("TAGS").unwrap(), vec!["x", "y"]);
+        });
+    }
+}
@@ -0,0 +1,525 @@
+//! Declarative descriptions of the variables an application reads.
+//!
+//! A [`Schema`] lists each variable once, with its type, default,
+//! description and whether it is required. The same declaration validates
+//! the environment and renders the help text and `.env.example` template, so
+//! they cannot drift apart. With [`Schema::strict`], variables under the
+//! application's prefix that the schema doesn't declare are errors too.
+//!
+//! [`VarSpec::new`] checks values with `FromStr`. Booleans, durations and
+//! memory sizes have their own constructors that accept the same spellings
+//! as [`get_bool_strict`](super::get_bool_strict),
+//! [`get_duration`](super::get_duration) and
+//! [`get_memory_size`](super::get_memory_size), such as `yes`, `30s` and
+//! `512MB`.
+//!
+//! Example:
+//! ```
+//! use common_utils_rs::env::{EnvReader, Schema, VarSpec, test::with_vars};
+//!
+//! let schema = Schema::new()
+//!     .var(VarSpec::new::<String>("SCHEMA_DOC_DATABASE_URL").required().description("Postgres connection string"))
+//!     .var(VarSpec::new::<u16>("SCHEMA_DOC_PORT").default("8080").description("HTTP port"));
+//!
+//! with_vars(&[("SCHEMA_DOC_DATABASE_URL", "postgres://localhost/app")], || {
+//!     let reader = EnvReader::from_source(schema.load().unwrap());
+//!     assert_eq!(reader.get_parsed::<u16>("SCHEMA_DOC_PORT").unwrap(), 8080);
+//! });
+//! print!("{}", schema.help());
+//! ```
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use super::dotenv::quote_value;
+use super::duration::parse_duration_value;
+use super::env::{
+    BoxError, EnvError, MemoryUnits, parse_bool_value, parse_memory_size_value, type_name,
+};
+use super::source::{EnvSource, ProcessEnv};
+use super::validator::{Check, parse_check};
+
+/// One declared variable of a [`Schema`].
+pub struct VarSpec {
+    name: String,
+    type_name: String,
+    default: Option<String>,
+    description: String,
+    required: bool,
+    check: Check,
+}
+
+impl VarSpec {
+    /// Declare `name`, whose value must parse as `T`. Variables are optional
+    /// and undocumented until configured otherwise.
+    pub fn new<T>(name: &str) -> Self
+    where
+        T: FromStr,
+        T::Err: Into<BoxError>,
+    {
+        Self {
+            name: name.to_string(),
+            type_name: type_name::<T>(),
+            default: None,
+            description: String::new(),
+            required: false,
+            check: Box::new(|key, value| parse_check::<T>(key, value)),
+        }
+    }
+
+    /// Declare `name`, checked by `parser`, which is given the variable name
+    /// and value and should report errors against that name. `type_name`
+    /// is shown in [`Schema::help`] and [`Schema::render_example`].
+    pub fn with_parser<F>(name: &str, type_name: &str, parser: F) -> Self
+    where
+        F: Fn(&str, &str) -> Result<(), EnvError> + Send + Sync + 'static,
+    {
+        Self {
+            name: name.to_string(),
+            type_name: type_name.to_string(),
+            default: None,
+            description: String::new(),
+            required: false,
+            check: Box::new(parser),
+        }
+    }
+
+    /// Declare a boolean such as `true`, `1`, `yes`, `on` or `enabled`.
+    pub fn bool(name: &str) -> Self {
+        Self::with_parser(name, "bool", |key, value| {
+            parse_bool_value(key, value).map(drop)
+        })
+    }
+
+    /// Declare a duration such as `30s` or `1h30m`.
+    pub fn duration(name: &str) -> Self {
+        Self::with_parser(name, "duration", |key, value| {
+            parse_duration_value(key, value.to_string()).map(drop)
+        })
+    }
+
+    /// Declare a memory size such as `512MB` or `2Gi`, with binary units.
+    pub fn memory_size(name: &str) -> Self {
+        Self::with_parser(name, "memory size", |key, value| {
+            parse_memory_size_value(key, value, MemoryUnits::Binary).map(drop)
+        })
+    }
+
+    /// The variable must be set, unless it has a default.
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// Use `value` when the variable is unset.
+    pub fn default(mut self, value: impl Into<String>) -> Self {
+        self.default = Some(value.into());
+        self
+    }
+
+    pub fn description(mut self, text: impl Into<String>) -> Self {
+        self.description = text.into();
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn type_name(&self) -> &str {
+        &self.type_name
+    }
+
+    pub fn default_value(&self) -> Option<&str> {
+        self.default.as_deref()
+    }
+
+    pub fn description_text(&self) -> &str {
+        &self.description
+    }
+
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+}
+
+impl std::fmt::Debug for VarSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VarSpec")
+            .field("name", &self.name)
+            .field("type_name", &self.type_name)
+            .field("default", &self.default)
+            .field("description", &self.description)
+            .field("required", &self.required)
+            .finish()
+    }
+}
+
+/// The variables an application reads, declared in one place.
+#[derive(Debug, Default)]
+pub struct Schema {
+    vars: Vec<VarSpec>,
+    strict_prefix: Option<String>,
+}
+
+impl Schema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn var(mut self, var: VarSpec) -> Self {
+        self.vars.push(var);
+        self
+    }
+
+    /// Also reject variables that start with `prefix` but aren't declared,
+    /// such as a mistyped `APP_TIMEOUTT` that would otherwise be ignored in
+    /// favour of the default.
+    pub fn strict(mut self, prefix: impl Into<String>) -> Self {
+        self.strict_prefix = Some(prefix.into());
+        self
+    }
+
+    /// The declared variables, in declaration order.
+    pub fn vars(&self) -> &[VarSpec] {
+        &self.vars
+    }
+
+    /// Check every declared variable and return their values, with defaults
+    /// filled in. Unset optional variables without a default are left out.
+    ///
+    /// All problems are reported together, as with
+    /// [`Validator`](super::validator::Validator). The result is an
+    /// [`EnvSource`], so it can back an [`EnvReader`](super::reader::EnvReader).
+    pub fn load(&self) -> Result<HashMap<String, String>, EnvError> {
+        self.load_from(&ProcessEnv)
+    }
+
+    /// Like [`Schema::load`], reading variables from `source`.
+    pub fn load_from(&self, source: &impl EnvSource) -> Result<HashMap<String, String>, EnvError> {
+        let mut values = HashMap::new();
+        let mut errors = Vec::new();
+        for var in &self.vars {
+            let value = match source.get(&var.name).or_else(|| var.default.clone()) {
+                Some(value) => value,
+                None if var.required => {
+                    errors.push(EnvError::Missing(var.name.clone()));
+                    continue;
+                }
+                None => continue,
+            };
+            match (var.check)(&var.name, &value) {
+                Ok(()) => {
+                    values.insert(var.name.clone(), value);
+                }
+                Err(error) => errors.push(error),
+            }
+        }
+        errors.extend(self.undeclared(source));
+        EnvError::aggregate(errors)?;
+        Ok(values)
+    }
+
+    /// An error for each variable under the strict prefix that isn't
+    /// declared, suggesting the closest declared name.
+    fn undeclared(&self, source: &impl EnvSource) -> Vec<EnvError> {
+        let Some(prefix) = &self.strict_prefix else {
+            return Vec::new();
+        };
+        let mut keys: Vec<String> = source
+            .keys()
+            .into_iter()
+            .filter(|key| key.starts_with(prefix.as_str()))
+            .filter(|key| !self.vars.iter().any(|var| &var.name == key))
+            .collect();
+        keys.sort();
+        keys.dedup();
+        keys.into_iter()
+            .map(|key| {
+                let suggestion = self
+                    .vars
+                    .iter()
+                    .map(|var| (edit_distance(&key, &var.name), &var.name))
+                    .filter(|(distance, _)| *distance <= 2)
+                    .min();
+                let reason = match suggestion {
+                    Some((_, name)) => format!("not declared (did you mean `{}`?)", name),
+                    None => "not declared".to_string(),
+                };
+                EnvError::Invalid { key, reason }
+            })
+            .collect()
+    }
+
+    /// A usage table with one row per variable, suitable for `--help`.
+    pub fn help(&self) -> String {
+        let header = ["NAME", "TYPE", "REQUIRED", "DEFAULT", "DESCRIPTION"].map(String::from);
+        let rows: Vec<[String; 5]> = self
+            .vars
+            .iter()
+            .map(|var| {
+                [
+                    var.name.clone(),
+                    var.type_name.clone(),
+                    if var.required { "yes" } else { "no" }.to_string(),
+                    var.default.clone().unwrap_or_else(|| "-".to_string()),
+                    var.description.clone(),
+                ]
+            })
+            .collect();
+
+        let mut widths = header.clone().map(|cell| cell.chars().count());
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let mut out = String::new();
+        for row in std::iter::once(&header).chain(&rows) {
+            let mut line = String::new();
+            for (i, cell) in row.iter().enumerate() {
+                if i + 1 == row.len() {
+                    line.push_str(cell);
+                } else {
+                    line.push_str(&format!("{:<width$}  ", cell, width = widths[i]));
+                }
+            }
+            out.push_str(line.trim_end());
+            out.push('\n');
+        }
+        out
+    }
+
+    /// A commented `.env` template listing every variable, for checking in
+    /// as `.env.example`.
+    ///
+    /// Variables with a default are set to it, required ones are left empty
+    /// and optional ones without a default are commented out.
+    pub fn render_example(&self) -> String {
+        let mut out = String::new();
+        for var in &self.vars {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            for line in var.description.lines() {
+                out.push_str(format!("# {}", line).trim_end());
+                out.push('\n');
+            }
+            let (detail, assignment) = match (&var.default, var.required) {
+                (Some(default), _) => (
+                    format!("default: {}", quote_value(default)),
+                    format!("{}={}", var.name, quote_value(default)),
+                ),
+                (None, true) => ("required".to_string(), format!("{}=", var.name)),
+                (None, false) => ("optional".to_string(), format!("# {}=", var.name)),
+            };
+            out.push_str(&format!(
+                "# {}, {}\n{}\n",
+                var.type_name, detail, assignment
+            ));
+        }
+        out
+    }
+}
+
+/// The number of single-character insertions, deletions and substitutions
+/// that turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::dotenv;
+    use crate::env::test::fixtures::map;
+
+    fn schema() -> Schema {
+        Schema::new()
+            .var(
+                VarSpec::new::<String>("DATABASE_URL")
+                    .required()
+                    .description("Postgres connection string"),
+            )
+            .var(
+                VarSpec::new::<u16>("PORT")
+                    .default("8080")
+                    .description("HTTP port"),
+            )
+            .var(VarSpec::bool("DEBUG"))
+    }
+
+    #[test]
+    fn test_schema_load_fills_defaults() {
+        let values = schema()
+            .load_from(&map(&[("DATABASE_URL", "postgres://db/app")]))
+            .unwrap();
+        assert_eq!(
+            values,
+            map(&[("DATABASE_URL", "postgres://db/app"), ("PORT", "8080")])
+        );
+    }
+
+    #[test]
+    fn test_schema_load_reports_every_error() {
+        match schema().load_from(&map(&[("PORT", "http"), ("DEBUG", "maybe")])) {
+            Err(EnvError::Multiple(errors)) => {
+                assert_eq!(errors.len(), 3);
+                assert!(matches!(&errors[0], EnvError::Missing(key) if key == "DATABASE_URL"));
+                assert!(matches!(
+                    &errors[1],
+                    EnvError::ParseError { key, expected, .. }
+                        if key == "PORT" && expected.as_deref() == Some("u16")
+                ));
+                assert!(matches!(&errors[2], EnvError::Invalid { key, .. } if key == "DEBUG"));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_schema_strict_reports_undeclared() {
+        let schema = Schema::new()
+            .var(VarSpec::duration("APP_TIMEOUT").default("30s"))
+            .var(VarSpec::new::<u16>("APP_PORT"))
+            .strict("APP_");
+        let source = map(&[
+            ("APP_TIMEOUTT", "45s"),
+            ("APP_PORT", "8080"),
+            ("APP_EXTRA", "1"),
+            ("OTHER_TIMEOUTT", "1"),
+        ]);
+        match schema.load_from(&source) {
+            Err(EnvError::Multiple(errors)) => {
+                let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+                assert_eq!(
+                    messages,
+                    [
+                        "Invalid value for environment variable `APP_EXTRA`: not declared",
+                        "Invalid value for environment variable `APP_TIMEOUTT`: not declared \
+                         (did you mean `APP_TIMEOUT`?)",
+                    ]
+                );
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let lenient = Schema::new().var(VarSpec::new::<u16>("APP_PORT"));
+        assert!(lenient.load_from(&source).is_ok());
+    }
+
+    #[test]
+    fn test_schema_uses_crate_parsers() {
+        let schema = Schema::new()
+            .var(VarSpec::bool("APP_DEBUG"))
+            .var(VarSpec::duration("APP_TIMEOUT").default("30s"))
+            .var(VarSpec::memory_size("APP_CACHE"));
+        let good = map(&[("APP_DEBUG", "yes"), ("APP_CACHE", "512MB")]);
+        assert!(schema.load_from(&good).is_ok());
+
+        let bad = map(&[
+            ("APP_DEBUG", "maybe"),
+            ("APP_TIMEOUT", "soon"),
+            ("APP_CACHE", "lots"),
+        ]);
+        match schema.load_from(&bad) {
+            Err(EnvError::Multiple(errors)) => {
+                let keys: Vec<&str> = errors
+                    .iter()
+                    .map(|error| match error {
+                        EnvError::Invalid { key, .. } | EnvError::ParseError { key, .. } => {
+                            key.as_str()
+                        }
+                        other => panic!("unexpected error: {:?}", other),
+                    })
+                    .collect();
+                assert_eq!(keys, ["APP_DEBUG", "APP_TIMEOUT", "APP_CACHE"]);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let custom =
+            Schema::new().var(VarSpec::with_parser(
+                "APP_MODE",
+                "mode",
+                |key, value| match value {
+                    "fast" | "safe" => Ok(()),
+                    _ => Err(EnvError::Invalid {
+                        key: key.to_string(),
+                        reason: "expected fast or safe".to_string(),
+                    }),
+                },
+            ));
+        assert!(custom.load_from(&map(&[("APP_MODE", "fast")])).is_ok());
+        assert!(custom.load_from(&map(&[("APP_MODE", "slow")])).is_err());
+        assert_eq!(custom.vars()[0].type_name(), "mode");
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("APP_TIMEOUTT", "APP_TIMEOUT"), 1);
+        assert_eq!(edit_distance("APP_PROT", "APP_PORT"), 2);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("same", "same"), 0);
+    }
+
+    #[test]
+    fn test_schema_render_example() {
+        let schema = schema().var(
+            VarSpec::new::<String>("GREETING")
+                .default("hello # world")
+                .description("Shown on start\nto every user"),
+        );
+        let example = schema.render_example();
+        assert_eq!(
+            example,
+            "# Postgres connection string\n\
+             # String, required\n\
+             DATABASE_URL=\n\
+             \n\
+             # HTTP port\n\
+             # u16, default: 8080\n\
+             PORT=8080\n\
+             \n\
+             # bool, optional\n\
+             # DEBUG=\n\
+             \n\
+             # Shown on start\n\
+             # to every user\n\
+             # String, default: \"hello # world\"\n\
+             GREETING=\"hello # world\"\n"
+        );
+
+        let parsed: HashMap<String, String> =
+            dotenv::parse(&example).unwrap().into_iter().collect();
+        assert_eq!(
+            parsed,
+            map(&[
+                ("DATABASE_URL", ""),
+                ("PORT", "8080"),
+                ("GREETING", "hello # world")
+            ])
+        );
+    }
+
+    #[test]
+    fn test_schema_help() {
+        assert_eq!(
+            schema().help(),
+            "NAME          TYPE    REQUIRED  DEFAULT  DESCRIPTION\n\
+             DATABASE_URL  String  yes       -        Postgres connection string\n\
+             PORT          u16     no        8080     HTTP port\n\
+             DEBUG         bool    no        -\n"
+        );
+    }
+}
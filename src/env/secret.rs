@@ -0,0 +1,208 @@
+use std::fmt;
+use std::fs;
+
+use super::env::{EnvError, get_required_from};
+use super::source::{EnvSource, ProcessEnv};
+
+pub(crate) const REDACTED: &str = "***redacted***";
+
+/// Wrapper for sensitive values that never prints its contents.
+///
+/// `Debug` and `Display` both render `***redacted***`; the value is only
+/// reachable through an explicit [`Secret::expose`] call.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::env::Secret;
+/// let key = Secret::new("hunter2".to_string());
+/// assert_eq!(format!("{:?}", key), "Secret(***redacted***)");
+/// assert_eq!(key.expose(), "hunter2");
+/// ```
+#[derive(Clone, Default)]
+pub struct Secret<T> {
+    value: T,
+}
+
+impl<T> Secret<T> {
+    pub fn new(value: T) -> Self {
+        Self { value }
+    }
+
+    /// Access the wrapped value.
+    pub fn expose(&self) -> &T {
+        &self.value
+    }
+
+    /// Unwrap the value, giving up the redaction.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+/// Partial renderings for logs (`strings` feature), built on
+/// [`mask_middle`](crate::strings::mask_middle).
+#[cfg(feature = "strings")]
+impl<T: AsRef<str>> Secret<T> {
+    /// The value with all but its first `keep_start` and last `keep_end`
+    /// characters masked, e.g. `sk_l********9f3a`.
+    pub fn mask_middle(&self, keep_start: usize, keep_end: usize) -> String {
+        crate::strings::mask_middle(self.value.as_ref(), keep_start, keep_end)
+    }
+
+    /// The value with all but its last `n` characters masked.
+    pub fn mask_all_but_last(&self, n: usize) -> String {
+        crate::strings::mask_all_but_last(self.value.as_ref(), n)
+    }
+}
+
+impl<T> From<T> for Secret<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secret({})", REDACTED)
+    }
+}
+
+impl<T> fmt::Display for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+/// Read a required variable as a [`Secret`].
+pub fn get_secret(key: &str) -> Result<Secret<String>, EnvError> {
+    get_secret_from(&ProcessEnv, key)
+}
+
+pub(crate) fn get_secret_from(
+    source: &impl EnvSource,
+    key: &str,
+) -> Result<Secret<String>, EnvError> {
+    get_required_from(source, key).map(Secret::new)
+}
+
+/// Read `KEY`, or if unset, read the file named by `KEY_FILE`.
+///
+/// This is the convention used for Docker and Kubernetes secrets. A single
+/// trailing newline in the file is removed.
+pub fn get_required_or_file(key: &str) -> Result<String, EnvError> {
+    get_required_or_file_from(&ProcessEnv, key)
+}
+
+pub(crate) fn get_required_or_file_from(
+    source: &impl EnvSource,
+    key: &str,
+) -> Result<String, EnvError> {
+    if let Some(value) = source.get(key) {
+        return Ok(value);
+    }
+
+    let file_key = format!("{}_FILE", key);
+    let path = source
+        .get(&file_key)
+        .ok_or_else(|| EnvError::Missing(key.to_string()))?;
+    let mut content = fs::read_to_string(&path).map_err(|source| EnvError::Io { path, source })?;
+
+    if content.ends_with('\n') {
+        content.pop();
+        if content.ends_with('\r') {
+            content.pop();
+        }
+    }
+    Ok(content)
+}
+
+/// Like [`get_required_or_file`], wrapped in a [`Secret`].
+pub fn get_secret_or_file(key: &str) -> Result<Secret<String>, EnvError> {
+    get_required_or_file_from(&ProcessEnv, key).map(Secret::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test::fixtures::temp_file;
+    use crate::env::test::{ScopedEnv, with_vars, without_vars};
+
+    #[test]
+    fn test_secret_is_redacted() {
+        let secret = Secret::new("sk-live-123".to_string());
+        assert_eq!(secret.to_string(), "***redacted***");
+        assert!(!format!("{:?}", secret).contains("sk-live"));
+        assert!(!format!("{:#?}", Some(secret.clone())).contains("sk-live"));
+        assert_eq!(secret.expose(), "sk-live-123");
+        assert_eq!(secret.into_inner(), "sk-live-123");
+    }
+
+    #[cfg(feature = "strings")]
+    #[test]
+    fn test_secret_masking() {
+        let secret = Secret::new("sk_live_51H8f3a".to_string());
+        assert_eq!(secret.mask_middle(3, 4), "sk_********8f3a");
+        assert_eq!(secret.mask_all_but_last(2), "*************3a");
+    }
+
+    #[test]
+    fn test_get_secret() {
+        with_vars(&[("SECRET_KEY", "token")], || {
+            let secret = get_secret("SECRET_KEY").unwrap();
+            assert_eq!(secret.expose(), "token");
+        });
+        without_vars(&["SECRET_KEY"], || {
+            assert!(matches!(
+                get_secret("SECRET_KEY"),
+                Err(EnvError::Missing(_))
+            ));
+        });
+    }
+
+    #[test]
+    fn test_get_required_or_file_prefers_variable() {
+        let path = temp_file("prefer", "from-file\n");
+        let mut scope = ScopedEnv::new();
+        scope
+            .set("SECRET_FILE_A", "from-env")
+            .set("SECRET_FILE_A_FILE", path.to_str().unwrap());
+        assert_eq!(get_required_or_file("SECRET_FILE_A").unwrap(), "from-env");
+
+        scope.remove("SECRET_FILE_A");
+        assert_eq!(get_required_or_file("SECRET_FILE_A").unwrap(), "from-file");
+        assert_eq!(
+            get_secret_or_file("SECRET_FILE_A").unwrap().expose(),
+            "from-file"
+        );
+        drop(scope);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_get_required_or_file_trims_one_newline() {
+        let path = temp_file("newline", "line one\nline two\r\n\n");
+        with_vars(&[("SECRET_FILE_B_FILE", path.to_str().unwrap())], || {
+            assert_eq!(
+                get_required_or_file("SECRET_FILE_B").unwrap(),
+                "line one\nline two\r\n"
+            );
+        });
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_get_required_or_file_errors() {
+        without_vars(&["SECRET_FILE_C", "SECRET_FILE_C_FILE"], || {
+            assert!(matches!(
+                get_required_or_file("SECRET_FILE_C"),
+                Err(EnvError::Missing(k)) if k == "SECRET_FILE_C"
+            ));
+        });
+        with_vars(&[("SECRET_FILE_C_FILE", "/definitely/not/here")], || {
+            assert!(matches!(
+                get_required_or_file("SECRET_FILE_C"),
+                Err(EnvError::Io { .. })
+            ));
+        });
+    }
+}
@@ -0,0 +1,480 @@
+//! Point-in-time copies of environment variables for debugging dumps and
+//! comparisons.
+//!
+//! Example:
+//! ```
+//! use common_utils_rs::env::{snapshot_with_prefix, test::with_vars};
+//! let vars = [("SNAP_DOC_HOST", "db"), ("SNAP_DOC_PASSWORD", "hunter2")];
+//! with_vars(&vars, || {
+//!     let snapshot = snapshot_with_prefix("SNAP_DOC_").redact("SNAP_DOC_PASSWORD");
+//!     assert_eq!(
+//!         snapshot.to_json(),
+//!         "{\n  \"SNAP_DOC_HOST\": \"db\",\n  \"SNAP_DOC_PASSWORD\": \"***redacted***\"\n}"
+//!     );
+//! });
+//! ```
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+
+use super::secret::REDACTED;
+use super::source::ProcessEnv;
+
+/// A sorted copy of environment variables.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Snapshot {
+    vars: BTreeMap<String, String>,
+}
+
+impl Snapshot {
+    /// Capture the whole process environment.
+    ///
+    /// Variables whose names or values are not valid Unicode are skipped.
+    pub fn capture() -> Self {
+        snapshot_with_prefix("")
+    }
+
+    /// Keep the entries of `vars` whose names start with `prefix`.
+    pub fn from_vars<I, K, V>(prefix: &str, vars: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let vars = vars
+            .into_iter()
+            .map(|(k, v)| (k.into(), v.into()))
+            .filter(|(k, _): &(String, String)| k.starts_with(prefix))
+            .collect();
+        Self { vars }
+    }
+
+    /// Replace the value of `key` with `***redacted***`, if present.
+    pub fn redact(self, key: &str) -> Self {
+        self.redact_where(|k| k == key)
+    }
+
+    /// Replace the value of every key matching `predicate`.
+    pub fn redact_where<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&str) -> bool,
+    {
+        for (key, value) in self.vars.iter_mut() {
+            if predicate(key) {
+                *value = REDACTED.to_string();
+            }
+        }
+        self
+    }
+
+    /// Replace the value of every key matched by `rules`.
+    pub fn redact_with(self, rules: &RedactionRules) -> Self {
+        self.redact_where(|key| rules.matches(key))
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.vars.get(key).map(String::as_str)
+    }
+
+    pub fn vars(&self) -> &BTreeMap<String, String> {
+        &self.vars
+    }
+
+    pub fn len(&self) -> usize {
+        self.vars.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vars.is_empty()
+    }
+
+    /// What changed going from `self` to `other`: keys only in `other` are
+    /// added, keys only in `self` are removed.
+    ///
+    /// Example:
+    /// ```
+    /// use common_utils_rs::env::Snapshot;
+    /// let before = Snapshot::from_vars("", [("HOST", "a"), ("DEBUG", "1")]);
+    /// let after = Snapshot::from_vars("", [("HOST", "b"), ("PORT", "80")]);
+    /// assert_eq!(before.diff(&after).to_string(), "+ PORT=80\n- DEBUG=1\n~ HOST: a -> b\n");
+    /// ```
+    pub fn diff(&self, other: &Snapshot) -> SnapshotDiff {
+        let mut diff = SnapshotDiff::default();
+        for (key, old) in &self.vars {
+            match other.vars.get(key) {
+                None => {
+                    diff.removed.insert(key.clone(), old.clone());
+                }
+                Some(new) if new != old => {
+                    diff.changed.insert(key.clone(), (old.clone(), new.clone()));
+                }
+                Some(_) => {}
+            }
+        }
+        for (key, new) in &other.vars {
+            if !self.vars.contains_key(key) {
+                diff.added.insert(key.clone(), new.clone());
+            }
+        }
+        diff
+    }
+
+    /// Render as a pretty-printed JSON object.
+    pub fn to_json(&self) -> String {
+        if self.vars.is_empty() {
+            return "{}".to_string();
+        }
+        let entries: Vec<String> = self
+            .vars
+            .iter()
+            .map(|(k, v)| format!("  {}: {}", quote(k), quote(v)))
+            .collect();
+        format!("{{\n{}\n}}", entries.join(",\n"))
+    }
+
+    /// Render as a TOML document with one top-level key per variable.
+    pub fn to_toml(&self) -> String {
+        self.vars
+            .iter()
+            .map(|(k, v)| format!("{} = {}\n", toml_key(k), quote(v)))
+            .collect()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Snapshot {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(&self.vars)
+    }
+}
+
+/// Differences between two [`Snapshot`]s, from [`Snapshot::diff`].
+///
+/// `Display` renders one line per key: `+ KEY=value` for added, `- KEY=value`
+/// for removed and `~ KEY: old -> new` for changed variables. Redact both
+/// snapshots first if the output is logged.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SnapshotDiff {
+    added: BTreeMap<String, String>,
+    removed: BTreeMap<String, String>,
+    changed: BTreeMap<String, (String, String)>,
+}
+
+impl SnapshotDiff {
+    pub fn added(&self) -> &BTreeMap<String, String> {
+        &self.added
+    }
+
+    pub fn removed(&self) -> &BTreeMap<String, String> {
+        &self.removed
+    }
+
+    /// Changed keys with their `(old, new)` values.
+    pub fn changed(&self) -> &BTreeMap<String, (String, String)> {
+        &self.changed
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl fmt::Display for SnapshotDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (key, value) in &self.added {
+            writeln!(f, "+ {}={}", key, value)?;
+        }
+        for (key, value) in &self.removed {
+            writeln!(f, "- {}={}", key, value)?;
+        }
+        for (key, (old, new)) in &self.changed {
+            writeln!(f, "~ {}: {} -> {}", key, old, new)?;
+        }
+        Ok(())
+    }
+}
+
+/// Glob patterns for variable names whose values must not be shown.
+///
+/// `*` matches any run of characters and matching ignores case. The default
+/// rules cover the usual suffixes: `*_KEY`, `*_SECRET`, `*_TOKEN`,
+/// `*_PASSWORD` and `*_PASS`.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::env::RedactionRules;
+/// let rules = RedactionRules::default().pattern("*_DSN");
+/// assert!(rules.matches("APP_API_KEY"));
+/// assert!(rules.matches("sentry_dsn"));
+/// assert!(!rules.matches("APP_KEYSPACE"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedactionRules {
+    patterns: Vec<String>,
+}
+
+impl RedactionRules {
+    /// Rules that match nothing.
+    pub fn none() -> Self {
+        Self {
+            patterns: Vec::new(),
+        }
+    }
+
+    /// Also redact names matching `pattern`.
+    pub fn pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.patterns.push(pattern.into());
+        self
+    }
+
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    pub fn matches(&self, key: &str) -> bool {
+        let key = key.to_ascii_uppercase();
+        self.patterns
+            .iter()
+            .any(|pattern| glob_match(&pattern.to_ascii_uppercase(), &key))
+    }
+}
+
+impl Default for RedactionRules {
+    fn default() -> Self {
+        ["*_KEY", "*_SECRET", "*_TOKEN", "*_PASSWORD", "*_PASS"]
+            .into_iter()
+            .fold(Self::none(), Self::pattern)
+    }
+}
+
+/// Match `text` against a pattern where `*` stands for any run of characters.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let Some((first, rest)) = pattern.split_once('*') else {
+        return pattern == text;
+    };
+    let Some(mut remaining) = text.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts: Vec<&str> = rest.split('*').collect();
+    let last = parts.pop().unwrap_or_default();
+    for part in parts {
+        match remaining.find(part) {
+            Some(index) => remaining = &remaining[index + part.len()..],
+            None => return false,
+        }
+    }
+    remaining.len() >= last.len() && remaining.ends_with(last)
+}
+
+/// Log every process variable under `prefix`, masking the values of names
+/// matched by `rules`.
+///
+/// Lines go to `log` and/or `tracing` at info level when those features are
+/// enabled, and to stderr otherwise.
+///
+/// Example:
+/// ```no_run
+/// use common_utils_rs::env::{log_config, RedactionRules};
+/// log_config("APP_", &RedactionRules::default());
+/// ```
+pub fn log_config(prefix: &str, rules: &RedactionRules) {
+    let snapshot = snapshot_with_prefix(prefix).redact_with(rules);
+    for (key, value) in snapshot.vars() {
+        emit(key, value);
+    }
+}
+
+#[cfg(any(feature = "log", feature = "tracing"))]
+fn emit(key: &str, value: &str) {
+    #[cfg(feature = "log")]
+    log::info!("{}={}", key, value);
+    #[cfg(feature = "tracing")]
+    tracing::info!(key, value, "configuration");
+}
+
+#[cfg(not(any(feature = "log", feature = "tracing")))]
+fn emit(key: &str, value: &str) {
+    eprintln!("{}={}", key, value);
+}
+
+/// Capture every process variable whose name starts with `prefix`.
+///
+/// Variables whose names or values are not valid Unicode are skipped.
+pub fn snapshot_with_prefix(prefix: &str) -> Snapshot {
+    Snapshot::from_vars(prefix, ProcessEnv.vars())
+}
+
+/// Double-quoted string with the escapes JSON and TOML basic strings share.
+fn quote(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn toml_key(key: &str) -> String {
+    let bare = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if bare { key.to_string() } else { quote(key) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test::{ScopedEnv, with_vars};
+
+    fn sample() -> Snapshot {
+        Snapshot::from_vars(
+            "APP_",
+            [
+                ("APP_PORT", "8080"),
+                ("APP_HOST", "localhost"),
+                ("APP_TOKEN", "abc"),
+                ("OTHER", "ignored"),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_from_vars_filters_and_sorts() {
+        let snapshot = sample();
+        assert_eq!(snapshot.len(), 3);
+        let keys: Vec<_> = snapshot.vars().keys().cloned().collect();
+        assert_eq!(keys, vec!["APP_HOST", "APP_PORT", "APP_TOKEN"]);
+        assert_eq!(snapshot.get("OTHER"), None);
+    }
+
+    #[test]
+    fn test_redaction() {
+        let snapshot = sample().redact_where(|k| k.ends_with("_TOKEN"));
+        assert_eq!(snapshot.get("APP_TOKEN"), Some("***redacted***"));
+        assert_eq!(snapshot.get("APP_HOST"), Some("localhost"));
+        assert_eq!(
+            sample().redact("APP_HOST").get("APP_HOST"),
+            Some("***redacted***")
+        );
+    }
+
+    #[test]
+    fn test_redaction_rules() {
+        let rules = RedactionRules::default();
+        assert!(rules.matches("APP_API_KEY"));
+        assert!(rules.matches("app_token"));
+        assert!(rules.matches("DB_PASSWORD"));
+        assert!(!rules.matches("APP_KEYSPACE"));
+        assert!(!rules.matches("TOKEN"));
+        assert!(!RedactionRules::none().matches("APP_API_KEY"));
+
+        let custom = RedactionRules::none().pattern("*PRIVATE*").pattern("EXACT");
+        assert!(custom.matches("MY_PRIVATE_CERT"));
+        assert!(custom.matches("exact"));
+        assert!(!custom.matches("EXACTLY"));
+
+        let snapshot = sample().redact_with(&rules);
+        assert_eq!(snapshot.get("APP_TOKEN"), Some("***redacted***"));
+        assert_eq!(snapshot.get("APP_PORT"), Some("8080"));
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*", ""));
+        assert!(glob_match("A*B*C", "AxxBxxC"));
+        assert!(glob_match("A*B*C", "ABC"));
+        assert!(!glob_match("A*B*C", "AC"));
+        assert!(!glob_match("AB*BA", "ABA"));
+    }
+
+    #[test]
+    fn test_log_config() {
+        with_vars(
+            &[("LOGCFG_T_HOST", "db"), ("LOGCFG_T_TOKEN", "s3cr3t")],
+            || {
+                log_config("LOGCFG_T_", &RedactionRules::default());
+            },
+        );
+    }
+
+    #[test]
+    fn test_to_json() {
+        assert_eq!(Snapshot::default().to_json(), "{}");
+        let snapshot = Snapshot::from_vars("", [("A", "say \"hi\"\n"), ("B", "c:\\tmp")]);
+        assert_eq!(
+            snapshot.to_json(),
+            "{\n  \"A\": \"say \\\"hi\\\"\\n\",\n  \"B\": \"c:\\\\tmp\"\n}"
+        );
+    }
+
+    #[test]
+    fn test_to_toml() {
+        let snapshot = Snapshot::from_vars("", [("APP_PORT", "8080"), ("weird.key", "\u{1}")]);
+        assert_eq!(
+            snapshot.to_toml(),
+            "APP_PORT = \"8080\"\n\"weird.key\" = \"\\u0001\"\n"
+        );
+    }
+
+    #[test]
+    fn test_diff() {
+        let before = sample();
+        let after = Snapshot::from_vars(
+            "",
+            [
+                ("APP_PORT", "9090"),
+                ("APP_HOST", "localhost"),
+                ("APP_DEBUG", "1"),
+            ],
+        );
+        let diff = before.diff(&after);
+        assert_eq!(diff.added().get("APP_DEBUG").map(String::as_str), Some("1"));
+        assert_eq!(
+            diff.removed().get("APP_TOKEN").map(String::as_str),
+            Some("abc")
+        );
+        assert_eq!(
+            diff.changed().get("APP_PORT"),
+            Some(&("8080".to_string(), "9090".to_string()))
+        );
+        assert_eq!(diff.changed().len(), 1);
+        assert!(!diff.is_empty());
+        assert!(before.diff(&before).is_empty());
+        assert_eq!(before.diff(&before).to_string(), "");
+    }
+
+    #[test]
+    fn test_capture() {
+        let mut scope = ScopedEnv::new();
+        scope.set("SNAP_CAPTURE", "before");
+        let before = Snapshot::capture();
+        assert_eq!(before.get("SNAP_CAPTURE"), Some("before"));
+
+        scope.set("SNAP_CAPTURE", "after");
+        let diff = before.diff(&Snapshot::capture());
+        assert_eq!(
+            diff.changed().get("SNAP_CAPTURE"),
+            Some(&("before".to_string(), "after".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_snapshot_with_prefix() {
+        with_vars(&[("SNAP_T_A", "1"), ("SNAP_T_B", "2")], || {
+            let snapshot = snapshot_with_prefix("SNAP_T_");
+            assert_eq!(snapshot.len(), 2);
+            assert_eq!(snapshot.get("SNAP_T_B"), Some("2"));
+        });
+    }
+}
@@ -0,0 +1,293 @@
+//! Abstractions over where variables come from.
+//!
+//! The free getters such as [`get_required`](super::get_required) always
+//! read the process environment, with [`overrides`](super::overrides)
+//! applied.
+//! [`EnvReader::from_source`](super::EnvReader::from_source) runs the same
+//! getters against any [`EnvSource`], such as a `HashMap` in tests or a
+//! dotenv file layered over the process environment.
+//!
+//! Example:
+//! ```
+//! use std::collections::HashMap;
+//! use common_utils_rs::env::{EnvReader, EnvSource, ProcessEnv};
+//!
+//! let overrides = HashMap::from([("PORT".to_string(), "9000".to_string())]);
+//! let reader = EnvReader::from_source(overrides.or(ProcessEnv));
+//! let port: u16 = reader.get_parsed("PORT").unwrap();
+//! assert_eq!(port, 9000);
+//! ```
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::env;
+use std::ffi::OsString;
+use std::hash::BuildHasher;
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use super::access;
+use super::dotenv;
+use super::env::EnvError;
+use super::overlay;
+
+/// A source of variables.
+pub trait EnvSource {
+    /// Value of `key`, or `None` when it is not set.
+    fn get(&self, key: &str) -> Option<String>;
+
+    /// Raw value of `key`, which may not be valid unicode. Only sources that
+    /// can hold such values need to override this.
+    fn get_os(&self, key: &str) -> Option<OsString> {
+        self.get(key).map(OsString::from)
+    }
+
+    /// Names of all variables, for lookups that can't go straight to
+    /// [`EnvSource::get`] such as case-insensitive matching. Sources that
+    /// can't enumerate their keys return an empty list.
+    fn keys(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Consult `self` first and `fallback` for keys `self` doesn't have.
+    fn or<S>(self, fallback: S) -> Layered<Self, S>
+    where
+        Self: Sized,
+        S: EnvSource,
+    {
+        Layered::new(self, fallback)
+    }
+}
+
+/// The environment of the current process, as seen through
+/// [`overrides`](super::overrides).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProcessEnv;
+
+impl ProcessEnv {
+    /// Every variable of the process whose name and value are valid Unicode.
+    pub fn vars(&self) -> impl Iterator<Item = (String, String)> {
+        let real = env::vars_os()
+            .filter_map(|(k, v)| Some((k.into_string().ok()?, v.into_string().ok()?)));
+        overlay::apply(real).into_iter()
+    }
+}
+
+impl EnvSource for ProcessEnv {
+    fn get(&self, key: &str) -> Option<String> {
+        let value = match overlay::lookup(key) {
+            Some(value) => value,
+            None => env::var(key).ok(),
+        };
+        access::record(key, value.is_some());
+        value
+    }
+
+    fn get_os(&self, key: &str) -> Option<OsString> {
+        let value = match overlay::lookup(key) {
+            Some(value) => value.map(OsString::from),
+            None => env::var_os(key),
+        };
+        access::record(key, value.is_some());
+        value
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.vars().map(|(key, _)| key).collect()
+    }
+}
+
+impl<H: BuildHasher> EnvSource for HashMap<String, String, H> {
+    fn get(&self, key: &str) -> Option<String> {
+        HashMap::get(self, key).cloned()
+    }
+
+    fn keys(&self) -> Vec<String> {
+        HashMap::keys(self).cloned().collect()
+    }
+}
+
+impl EnvSource for BTreeMap<String, String> {
+    fn get(&self, key: &str) -> Option<String> {
+        BTreeMap::get(self, key).cloned()
+    }
+
+    fn keys(&self) -> Vec<String> {
+        BTreeMap::keys(self).cloned().collect()
+    }
+}
+
+impl<S: EnvSource + ?Sized> EnvSource for &S {
+    fn get(&self, key: &str) -> Option<String> {
+        (**self).get(key)
+    }
+
+    fn get_os(&self, key: &str) -> Option<OsString> {
+        (**self).get_os(key)
+    }
+
+    fn keys(&self) -> Vec<String> {
+        (**self).keys()
+    }
+}
+
+impl<S: EnvSource + ?Sized> EnvSource for Box<S> {
+    fn get(&self, key: &str) -> Option<String> {
+        (**self).get(key)
+    }
+
+    fn get_os(&self, key: &str) -> Option<OsString> {
+        (**self).get_os(key)
+    }
+
+    fn keys(&self) -> Vec<String> {
+        (**self).keys()
+    }
+}
+
+impl<S: EnvSource + ?Sized> EnvSource for Rc<S> {
+    fn get(&self, key: &str) -> Option<String> {
+        (**self).get(key)
+    }
+
+    fn get_os(&self, key: &str) -> Option<OsString> {
+        (**self).get_os(key)
+    }
+
+    fn keys(&self) -> Vec<String> {
+        (**self).keys()
+    }
+}
+
+impl<S: EnvSource + ?Sized> EnvSource for Arc<S> {
+    fn get(&self, key: &str) -> Option<String> {
+        (**self).get(key)
+    }
+
+    fn get_os(&self, key: &str) -> Option<OsString> {
+        (**self).get_os(key)
+    }
+
+    fn keys(&self) -> Vec<String> {
+        (**self).keys()
+    }
+}
+
+/// Variables read from a dotenv file, without touching the process environment.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileSource {
+    vars: HashMap<String, String>,
+}
+
+impl FileSource {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, EnvError> {
+        Ok(Self {
+            vars: dotenv::read_from(path)?,
+        })
+    }
+
+    pub fn vars(&self) -> &HashMap<String, String> {
+        &self.vars
+    }
+}
+
+impl EnvSource for FileSource {
+    fn get(&self, key: &str) -> Option<String> {
+        self.vars.get(key).cloned()
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.vars.keys().cloned().collect()
+    }
+}
+
+/// Two sources where the first takes precedence. Built with [`EnvSource::or`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Layered<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> Layered<A, B> {
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+}
+
+impl<A: EnvSource, B: EnvSource> EnvSource for Layered<A, B> {
+    fn get(&self, key: &str) -> Option<String> {
+        self.first.get(key).or_else(|| self.second.get(key))
+    }
+
+    fn get_os(&self, key: &str) -> Option<OsString> {
+        self.first.get_os(key).or_else(|| self.second.get_os(key))
+    }
+
+    /// Keys of both layers, each once: the first layer's in its order,
+    /// then those only the second layer has.
+    fn keys(&self) -> Vec<String> {
+        let mut keys = self.first.keys();
+        let mut seen: HashSet<String> = keys.iter().cloned().collect();
+        for key in self.second.keys() {
+            if seen.insert(key.clone()) {
+                keys.push(key);
+            }
+        }
+        keys
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test::fixtures::{map, temp_file};
+    use crate::env::test::with_vars;
+    use std::fs;
+
+    #[test]
+    fn test_map_sources() {
+        let hash = map(&[("A", "1")]);
+        assert_eq!(EnvSource::get(&hash, "A"), Some("1".to_string()));
+        assert_eq!(EnvSource::get(&hash, "B"), None);
+
+        let tree: BTreeMap<_, _> = hash.clone().into_iter().collect();
+        assert_eq!(EnvSource::get(&tree, "A"), Some("1".to_string()));
+
+        let boxed: Box<dyn EnvSource> = Box::new(hash);
+        assert_eq!(boxed.get("A"), Some("1".to_string()));
+    }
+
+    #[test]
+    fn test_process_env_source() {
+        with_vars(&[("SOURCE_PROCESS", "yes")], || {
+            assert_eq!(ProcessEnv.get("SOURCE_PROCESS"), Some("yes".to_string()));
+        });
+    }
+
+    #[test]
+    fn test_layered_precedence() {
+        let layered = map(&[("A", "top")]).or(map(&[("A", "bottom"), ("B", "bottom")]));
+        assert_eq!(layered.get("A"), Some("top".to_string()));
+        assert_eq!(layered.get("B"), Some("bottom".to_string()));
+        assert_eq!(layered.get("C"), None);
+
+        let mut keys = layered.keys();
+        keys.sort();
+        assert_eq!(keys, vec!["A", "B"]);
+
+        let ordered = map(&[("B", "top")]).or(map(&[("A", "x")]).or(map(&[("B", "x")])));
+        assert_eq!(ordered.keys(), vec!["B", "A"]);
+    }
+
+    #[test]
+    fn test_file_source() {
+        let path = temp_file("source.env", "FILE_SOURCE_KEY=from-file\n");
+        let source = FileSource::open(&path).unwrap();
+        assert_eq!(source.get("FILE_SOURCE_KEY"), Some("from-file".to_string()));
+        assert_eq!(source.vars().len(), 1);
+        fs::remove_file(path).unwrap();
+        assert!(matches!(
+            FileSource::open("/definitely/not/here"),
+            Err(EnvError::Io { .. })
+        ));
+    }
+}
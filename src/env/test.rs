@@ -0,0 +1,178 @@
+//! Helpers for tests that need to modify the process environment.
+//!
+//! All changes go through a process-wide mutex so tests running in parallel
+//! don't observe each other's variables, and previous values are restored
+//! when the guard is dropped, even if the test panics. Threads that read the
+//! environment without going through these helpers can still race with
+//! them; [`overrides`](super::overrides) avoids that by never touching the
+//! real environment.
+//!
+//! Example:
+//! ```
+//! use common_utils_rs::env::{get_required, test::with_vars};
+//! with_vars(&[("API_HOST", "localhost")], || {
+//!     assert_eq!(get_required("API_HOST").unwrap(), "localhost");
+//! });
+//! ```
+use std::env;
+use std::ffi::{OsStr, OsString};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+/// Guard that sets or removes variables and restores them on drop.
+///
+/// Holding a `ScopedEnv` locks out every other `ScopedEnv` in the process,
+/// so don't create a second one (or call [`with_vars`]) while one is alive
+/// on the same thread.
+pub struct ScopedEnv {
+    saved: Vec<(String, Option<OsString>)>,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl ScopedEnv {
+    pub fn new() -> Self {
+        Self {
+            saved: Vec::new(),
+            // A panicking test poisons the lock, but the guard it held has
+            // already restored the environment, so it is safe to continue.
+            _lock: ENV_LOCK.lock().unwrap_or_else(PoisonError::into_inner),
+        }
+    }
+
+    /// Set `key`; `value` may be any OS string, including non-UTF-8 ones.
+    pub fn set(&mut self, key: &str, value: impl AsRef<OsStr>) -> &mut Self {
+        self.save(key);
+        // SAFETY: every mutation made through this module holds ENV_LOCK.
+        unsafe {
+            env::set_var(key, value);
+        }
+        self
+    }
+
+    pub fn remove(&mut self, key: &str) -> &mut Self {
+        self.save(key);
+        // SAFETY: every mutation made through this module holds ENV_LOCK.
+        unsafe {
+            env::remove_var(key);
+        }
+        self
+    }
+
+    fn save(&mut self, key: &str) {
+        if !self.saved.iter().any(|(k, _)| k == key) {
+            self.saved.push((key.to_string(), env::var_os(key)));
+        }
+    }
+}
+
+impl Default for ScopedEnv {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for ScopedEnv {
+    fn drop(&mut self) {
+        for (key, value) in self.saved.drain(..).rev() {
+            // SAFETY: the lock is still held until this guard is dropped.
+            unsafe {
+                match value {
+                    Some(value) => env::set_var(&key, value),
+                    None => env::remove_var(&key),
+                }
+            }
+        }
+    }
+}
+
+/// Run `f` with the given variables set, restoring previous values afterwards.
+pub fn with_vars<F, R>(vars: &[(&str, &str)], f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    let mut scope = ScopedEnv::new();
+    for (key, value) in vars {
+        scope.set(key, value);
+    }
+    f()
+}
+
+/// Run `f` with the given variables unset, restoring previous values afterwards.
+pub fn without_vars<F, R>(keys: &[&str], f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    let mut scope = ScopedEnv::new();
+    for key in keys {
+        scope.remove(key);
+    }
+    f()
+}
+
+/// Fixtures shared by the crate's own unit tests.
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    use std::{env, fs};
+
+    /// `pairs` as an owned map, usable as an [`EnvSource`](crate::env::EnvSource).
+    pub(crate) fn map(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    /// Write `content` to a file in the temp directory whose name includes
+    /// `name` and the process id.
+    pub(crate) fn temp_file(name: &str, content: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("common-utils-rs-{}-{}", std::process::id(), name));
+        fs::write(&path, content).unwrap();
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::panic;
+
+    #[test]
+    fn test_with_vars_sets_and_restores() {
+        with_vars(&[("SCOPED_OUTER", "before")], || {
+            assert_eq!(env::var("SCOPED_OUTER").unwrap(), "before");
+        });
+        assert!(env::var("SCOPED_OUTER").is_err());
+    }
+
+    #[test]
+    fn test_scoped_env_restores_previous_value() {
+        let mut scope = ScopedEnv::new();
+        scope.set("SCOPED_PREV", "original");
+        {
+            // Re-setting the same key keeps the first saved value.
+            scope.set("SCOPED_PREV", "changed").remove("SCOPED_OTHER");
+            assert_eq!(env::var("SCOPED_PREV").unwrap(), "changed");
+        }
+        drop(scope);
+        assert!(env::var("SCOPED_PREV").is_err());
+    }
+
+    #[test]
+    fn test_with_vars_restores_on_panic() {
+        let result = panic::catch_unwind(|| {
+            with_vars(&[("SCOPED_PANIC", "set")], || panic!("boom"));
+        });
+        assert!(result.is_err());
+        without_vars(&[], || assert!(env::var("SCOPED_PANIC").is_err()));
+    }
+
+    #[test]
+    fn test_without_vars() {
+        with_vars(&[("SCOPED_REMOVED", "x")], || ());
+        let seen = without_vars(&["SCOPED_REMOVED"], || env::var("SCOPED_REMOVED").is_ok());
+        assert!(!seen);
+    }
+}
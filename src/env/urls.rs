@@ -0,0 +1,113 @@
+//! URL getters (`url` feature).
+pub use url::Url;
+
+use super::access;
+use super::env::{EnvError, get_required_from};
+use super::source::{EnvSource, ProcessEnv};
+
+/// Read a required variable and parse it as an absolute URL.
+pub fn get_url(key: &str) -> Result<Url, EnvError> {
+    get_url_from(&ProcessEnv, key)
+}
+
+/// Like [`get_url`], returning `default` when the variable is unset or
+/// cannot be parsed.
+pub fn get_url_or_default(key: &str, default: Url) -> Url {
+    let value = get_url(key);
+    access::record_default(key, || default.to_string());
+    value.unwrap_or(default)
+}
+
+pub(crate) fn get_url_from(source: &impl EnvSource, key: &str) -> Result<Url, EnvError> {
+    let value = get_required_from(source, key)?;
+    Url::parse(&value).map_err(|e| {
+        EnvError::parse_error(key, value.as_str())
+            .with_expected("URL")
+            .with_source(e)
+    })
+}
+
+/// Like [`get_url`], but the scheme must be one of `schemes`
+/// (e.g. `get_url_with_schemes("CALLBACK_URL", &["https"])`).
+pub fn get_url_with_schemes(key: &str, schemes: &[&str]) -> Result<Url, EnvError> {
+    get_url_with_schemes_from(&ProcessEnv, key, schemes)
+}
+
+pub(crate) fn get_url_with_schemes_from(
+    source: &impl EnvSource,
+    key: &str,
+    schemes: &[&str],
+) -> Result<Url, EnvError> {
+    let url = get_url_from(source, key)?;
+    if schemes.iter().any(|s| s.eq_ignore_ascii_case(url.scheme())) {
+        Ok(url)
+    } else {
+        Err(EnvError::Invalid {
+            key: key.to_string(),
+            reason: format!(
+                "scheme `{}` is not allowed, expected one of: {}",
+                url.scheme(),
+                schemes.join(", ")
+            ),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test::with_vars;
+
+    #[test]
+    fn test_get_url() {
+        with_vars(&[("URL_KEY", "https://example.org:8443/path?q=1")], || {
+            let url = get_url("URL_KEY").unwrap();
+            assert_eq!(url.scheme(), "https");
+            assert_eq!(url.host_str(), Some("example.org"));
+            assert_eq!(url.port(), Some(8443));
+        });
+    }
+
+    #[test]
+    fn test_get_url_invalid() {
+        with_vars(&[("URL_BAD", "not a url")], || {
+            assert!(matches!(
+                get_url("URL_BAD"),
+                Err(EnvError::ParseError { .. })
+            ));
+        });
+    }
+
+    #[test]
+    fn test_get_url_with_schemes() {
+        with_vars(&[("URL_SCHEME", "http://example.org")], || {
+            assert!(get_url_with_schemes("URL_SCHEME", &["http", "https"]).is_ok());
+            match get_url_with_schemes("URL_SCHEME", &["https"]) {
+                Err(EnvError::Invalid { key, reason }) => {
+                    assert_eq!(key, "URL_SCHEME");
+                    assert!(reason.contains("`http`"));
+                }
+                other => panic!("unexpected result: {:?}", other),
+            }
+        });
+    }
+
+    #[test]
+    fn test_get_url_or_default() {
+        let default = Url::parse("http://localhost:8080").unwrap();
+        with_vars(
+            &[
+                ("URL_OR_SET", "https://example.org"),
+                ("URL_OR_BAD", "nope"),
+            ],
+            || {
+                assert_eq!(
+                    get_url_or_default("URL_OR_SET", default.clone()).as_str(),
+                    "https://example.org/"
+                );
+                assert_eq!(get_url_or_default("URL_OR_BAD", default.clone()), default);
+                assert_eq!(get_url_or_default("URL_OR_UNSET", default.clone()), default);
+            },
+        );
+    }
+}
@@ -0,0 +1,77 @@
+//! UUID getters (`uuid` feature).
+pub use uuid::Uuid;
+
+use super::env::{EnvError, get_required_from};
+use super::source::{EnvSource, ProcessEnv};
+
+/// Read a required variable and parse it as a UUID, e.g. a tenant or
+/// instance ID.
+///
+/// Hyphenated, simple (32 hex digits), braced and `urn:uuid:` forms are
+/// accepted; surrounding whitespace is ignored.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::env::{get_uuid, test::with_vars};
+/// with_vars(&[("TENANT_ID", "67e55044-10b1-426f-9247-bb680e5fe0c8")], || {
+///     let tenant = get_uuid("TENANT_ID").unwrap();
+///     assert_eq!(tenant.get_version_num(), 4);
+/// });
+/// ```
+pub fn get_uuid(key: &str) -> Result<Uuid, EnvError> {
+    get_uuid_from(&ProcessEnv, key)
+}
+
+pub(crate) fn get_uuid_from(source: &impl EnvSource, key: &str) -> Result<Uuid, EnvError> {
+    let value = get_required_from(source, key)?;
+    Uuid::parse_str(value.trim()).map_err(|e| {
+        EnvError::parse_error(key, value.as_str())
+            .with_expected("UUID")
+            .with_source(e)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test::{with_vars, without_vars};
+
+    #[test]
+    fn test_get_uuid() {
+        let expected = Uuid::from_u128(0x67e55044_10b1_426f_9247_bb680e5fe0c8);
+        with_vars(
+            &[
+                ("UUID_HYPHENATED", " 67e55044-10b1-426f-9247-bb680e5fe0c8 "),
+                ("UUID_SIMPLE", "67E5504410B1426F9247BB680E5FE0C8"),
+                ("UUID_URN", "urn:uuid:67e55044-10b1-426f-9247-bb680e5fe0c8"),
+            ],
+            || {
+                assert_eq!(get_uuid("UUID_HYPHENATED").unwrap(), expected);
+                assert_eq!(get_uuid("UUID_SIMPLE").unwrap(), expected);
+                assert_eq!(get_uuid("UUID_URN").unwrap(), expected);
+            },
+        );
+    }
+
+    #[test]
+    fn test_get_uuid_invalid() {
+        with_vars(&[("UUID_BAD", "67e55044-10b1-426f-9247")], || {
+            let err = get_uuid("UUID_BAD").unwrap_err();
+            assert!(matches!(
+                &err,
+                EnvError::ParseError { key, expected, source: Some(_), .. }
+                    if key == "UUID_BAD" && expected.as_deref() == Some("UUID")
+            ));
+            assert!(
+                err.to_string()
+                    .starts_with("Failed to parse environment variable `UUID_BAD` as UUID: ")
+            );
+        });
+        without_vars(&["UUID_MISSING"], || {
+            assert!(matches!(
+                get_uuid("UUID_MISSING"),
+                Err(EnvError::Missing(_))
+            ));
+        });
+    }
+}
@@ -0,0 +1,628 @@
+use std::fmt::Display;
+use std::marker::PhantomData;
+use std::ops::{Bound, RangeBounds};
+use std::str::FromStr;
+
+use super::env::{BoxError, EnvError, get_parsed_from, parse_value};
+use super::source::{EnvSource, ProcessEnv};
+
+/// Checks the value of a key, returning the error to report.
+pub(crate) type Check = Box<dyn Fn(&str, &str) -> Result<(), EnvError> + Send + Sync>;
+
+struct Rule {
+    key: String,
+    required: bool,
+    check: Option<Check>,
+}
+
+/// Checks many variables at once and reports every problem together.
+///
+/// Example:
+/// ```no_run
+/// use common_utils_rs::env::Validator;
+/// Validator::new()
+///     .required("DATABASE_URL")
+///     .parsed::<u16>("PORT")
+///     .optional_parsed::<usize>("WORKERS")
+///     .validate()
+///     .expect("invalid configuration");
+/// ```
+#[derive(Default)]
+pub struct Validator {
+    rules: Vec<Rule>,
+}
+
+impl Validator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The variable must be set.
+    pub fn required(self, key: &str) -> Self {
+        self.rule(key, true, None)
+    }
+
+    /// The variable must be set and parse as `T`.
+    pub fn parsed<T>(self, key: &str) -> Self
+    where
+        T: FromStr,
+        T::Err: Into<BoxError>,
+    {
+        self.rule(
+            key,
+            true,
+            Some(Box::new(|key, value| parse_check::<T>(key, value))),
+        )
+    }
+
+    /// If the variable is set, it must parse as `T`.
+    pub fn optional_parsed<T>(self, key: &str) -> Self
+    where
+        T: FromStr,
+        T::Err: Into<BoxError>,
+    {
+        self.rule(
+            key,
+            false,
+            Some(Box::new(|key, value| parse_check::<T>(key, value))),
+        )
+    }
+
+    /// The variable must be set and satisfy `check`. Otherwise an
+    /// [`EnvError::Invalid`] is reported with `reason`, which should read
+    /// well after the variable name, e.g. "must be debug or release".
+    pub fn check<F>(self, key: &str, check: F, reason: impl Into<String>) -> Self
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        let reason = reason.into();
+        self.rule(
+            key,
+            true,
+            Some(Box::new(move |key, value| {
+                if check(value) {
+                    Ok(())
+                } else {
+                    Err(EnvError::Invalid {
+                        key: key.to_string(),
+                        reason: reason.clone(),
+                    })
+                }
+            })),
+        )
+    }
+
+    fn rule(mut self, key: &str, required: bool, check: Option<Check>) -> Self {
+        self.rules.push(Rule {
+            key: key.to_string(),
+            required,
+            check,
+        });
+        self
+    }
+
+    /// Every problem found, in declaration order.
+    pub fn errors(&self) -> Vec<EnvError> {
+        self.errors_from(&ProcessEnv)
+    }
+
+    /// Like [`Validator::errors`], reading variables from `source`.
+    pub fn errors_from(&self, source: &impl EnvSource) -> Vec<EnvError> {
+        let mut errors = Vec::new();
+        for rule in &self.rules {
+            match source.get(&rule.key) {
+                Some(value) => {
+                    if let Some(Err(error)) =
+                        rule.check.as_ref().map(|check| check(&rule.key, &value))
+                    {
+                        errors.push(error);
+                    }
+                }
+                None if rule.required => errors.push(EnvError::Missing(rule.key.clone())),
+                None => {}
+            }
+        }
+        errors
+    }
+
+    /// `Ok(())` when every rule passes, otherwise all errors combined.
+    pub fn validate(&self) -> Result<(), EnvError> {
+        EnvError::aggregate(self.errors())
+    }
+
+    /// Like [`Validator::validate`], reading variables from `source`.
+    pub fn validate_from(&self, source: &impl EnvSource) -> Result<(), EnvError> {
+        EnvError::aggregate(self.errors_from(source))
+    }
+}
+
+pub(crate) fn parse_check<T>(key: &str, value: &str) -> Result<(), EnvError>
+where
+    T: FromStr,
+    T::Err: Into<BoxError>,
+{
+    parse_value::<T>(key, value.to_string()).map(drop)
+}
+
+/// A check on a parsed value, for [`get_parsed_validated`].
+///
+/// Implemented for the built-in validators such as [`in_range`] and for any
+/// `Fn(&T) -> Result<(), String>` closure. The error is a reason that reads
+/// well after the variable name, e.g. "must not be empty".
+pub trait Validate<T: ?Sized> {
+    fn validate(&self, value: &T) -> Result<(), String>;
+
+    /// Require both `self` and `other` to pass, checking `self` first.
+    fn and<V>(self, other: V) -> And<Self, V, T>
+    where
+        Self: Sized,
+        V: Validate<T>,
+    {
+        And(self, other, PhantomData)
+    }
+}
+
+impl<T: ?Sized, F> Validate<T> for F
+where
+    F: Fn(&T) -> Result<(), String>,
+{
+    fn validate(&self, value: &T) -> Result<(), String> {
+        self(value)
+    }
+}
+
+/// Two validators that must both pass. Built with [`Validate::and`].
+#[derive(Debug, Clone, Copy)]
+pub struct And<A, B, T: ?Sized>(A, B, PhantomData<fn(&T)>);
+
+impl<T: ?Sized, A: Validate<T>, B: Validate<T>> Validate<T> for And<A, B, T> {
+    fn validate(&self, value: &T) -> Result<(), String> {
+        self.0.validate(value)?;
+        self.1.validate(value)
+    }
+}
+
+/// Value must lie within `range`, e.g. `in_range(1024..=65535)` or `in_range(1..)`.
+pub fn in_range<T: Clone>(range: impl RangeBounds<T>) -> InRange<T> {
+    InRange {
+        start: range.start_bound().cloned(),
+        end: range.end_bound().cloned(),
+    }
+}
+
+/// Validator returned by [`in_range`].
+#[derive(Debug, Clone)]
+pub struct InRange<T> {
+    start: Bound<T>,
+    end: Bound<T>,
+}
+
+impl<T: PartialOrd + Display> Validate<T> for InRange<T> {
+    fn validate(&self, value: &T) -> Result<(), String> {
+        if (self.start.as_ref(), self.end.as_ref()).contains(value) {
+            return Ok(());
+        }
+        let expected = match (&self.start, &self.end) {
+            (Bound::Included(start), Bound::Included(end)) => {
+                format!("between {} and {}", start, end)
+            }
+            (Bound::Included(start), Bound::Excluded(end)) => {
+                format!("at least {} and less than {}", start, end)
+            }
+            (Bound::Excluded(start), Bound::Included(end)) => {
+                format!("greater than {} and at most {}", start, end)
+            }
+            (Bound::Excluded(start), Bound::Excluded(end)) => {
+                format!("greater than {} and less than {}", start, end)
+            }
+            (Bound::Included(start), Bound::Unbounded) => format!("at least {}", start),
+            (Bound::Excluded(start), Bound::Unbounded) => format!("greater than {}", start),
+            (Bound::Unbounded, Bound::Included(end)) => format!("at most {}", end),
+            (Bound::Unbounded, Bound::Excluded(end)) => format!("less than {}", end),
+            (Bound::Unbounded, Bound::Unbounded) => {
+                unreachable!("unbounded ranges contain every value")
+            }
+        };
+        Err(format!("must be {}, got {}", expected, value))
+    }
+}
+
+/// Value must contain something other than whitespace.
+pub fn non_empty() -> NonEmpty {
+    NonEmpty
+}
+
+/// Validator returned by [`non_empty`].
+#[derive(Debug, Clone, Copy)]
+pub struct NonEmpty;
+
+impl<T: AsRef<str> + ?Sized> Validate<T> for NonEmpty {
+    fn validate(&self, value: &T) -> Result<(), String> {
+        if value.as_ref().trim().is_empty() {
+            return Err("must not be empty".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Value must be at least `len` characters long.
+pub fn min_len(len: usize) -> MinLen {
+    MinLen(len)
+}
+
+/// Validator returned by [`min_len`].
+#[derive(Debug, Clone, Copy)]
+pub struct MinLen(usize);
+
+impl<T: AsRef<str> + ?Sized> Validate<T> for MinLen {
+    fn validate(&self, value: &T) -> Result<(), String> {
+        let len = value.as_ref().chars().count();
+        if len < self.0 {
+            return Err(format!(
+                "must be at least {} characters long, got {}",
+                self.0, len
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Value must equal one of `allowed`, e.g. `one_of(["debug", "release"])`.
+pub fn one_of<A>(allowed: impl IntoIterator<Item = A>) -> OneOf<A> {
+    OneOf(allowed.into_iter().collect())
+}
+
+/// Validator returned by [`one_of`].
+#[derive(Debug, Clone)]
+pub struct OneOf<A>(Vec<A>);
+
+impl<T, A> Validate<T> for OneOf<A>
+where
+    T: PartialEq<A> + Display + ?Sized,
+    A: Display,
+{
+    fn validate(&self, value: &T) -> Result<(), String> {
+        if self.0.iter().any(|allowed| value == allowed) {
+            return Ok(());
+        }
+        let allowed: Vec<String> = self.0.iter().map(ToString::to_string).collect();
+        Err(format!(
+            "must be one of: {}, got `{}`",
+            allowed.join(", "),
+            value
+        ))
+    }
+}
+
+/// Value must match the regular expression `pattern` (`regex` feature).
+///
+/// The pattern is not anchored; use `^...$` to match the whole value. Meant
+/// for patterns written in the source; use [`try_matches_regex`] for
+/// patterns from config files or schemas.
+///
+/// # Panics
+///
+/// Panics if `pattern` is not a valid regular expression.
+#[cfg(feature = "regex")]
+pub fn matches_regex(pattern: &str) -> MatchesRegex {
+    match try_matches_regex(pattern) {
+        Ok(validator) => validator,
+        Err(e) => panic!("invalid pattern for matches_regex: {}", e),
+    }
+}
+
+/// Like [`matches_regex`], returning an error for an invalid `pattern`
+/// instead of panicking.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::env::try_matches_regex;
+/// assert!(try_matches_regex("^[a-z]+$").is_ok());
+/// assert!(try_matches_regex("^[a-z+$").is_err());
+/// ```
+#[cfg(feature = "regex")]
+pub fn try_matches_regex(pattern: &str) -> Result<MatchesRegex, regex::Error> {
+    regex::Regex::new(pattern).map(MatchesRegex)
+}
+
+/// Validator returned by [`matches_regex`].
+#[cfg(feature = "regex")]
+#[derive(Debug, Clone)]
+pub struct MatchesRegex(regex::Regex);
+
+#[cfg(feature = "regex")]
+impl<T: AsRef<str> + ?Sized> Validate<T> for MatchesRegex {
+    fn validate(&self, value: &T) -> Result<(), String> {
+        if !self.0.is_match(value.as_ref()) {
+            return Err(format!("must match `{}`", self.0.as_str()));
+        }
+        Ok(())
+    }
+}
+
+/// Read a required variable and check it with `validator`.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::env::{get_validated, min_len, non_empty, test::with_vars, Validate};
+/// with_vars(&[("API_USER", "  ")], || {
+///     assert!(get_validated("API_USER", non_empty().and(min_len(3))).is_err());
+/// });
+/// ```
+pub fn get_validated<V>(key: &str, validator: V) -> Result<String, EnvError>
+where
+    V: Validate<String>,
+{
+    get_parsed_validated(key, validator)
+}
+
+/// Parse a required variable and check it with `validator`, reporting a
+/// failed check as [`EnvError::Invalid`].
+///
+/// Example:
+/// ```
+/// use common_utils_rs::env::{get_parsed_validated, in_range, test::with_vars};
+/// with_vars(&[("PORT", "80")], || {
+///     let err = get_parsed_validated::<u16, _>("PORT", in_range(1024..=65535)).unwrap_err();
+///     assert_eq!(
+///         err.to_string(),
+///         "Invalid value for environment variable `PORT`: must be between 1024 and 65535, got 80"
+///     );
+/// });
+/// ```
+pub fn get_parsed_validated<T, V>(key: &str, validator: V) -> Result<T, EnvError>
+where
+    T: FromStr,
+    T::Err: Into<BoxError>,
+    V: Validate<T>,
+{
+    get_parsed_validated_from(&ProcessEnv, key, validator)
+}
+
+pub(crate) fn get_parsed_validated_from<T, V>(
+    source: &impl EnvSource,
+    key: &str,
+    validator: V,
+) -> Result<T, EnvError>
+where
+    T: FromStr,
+    T::Err: Into<BoxError>,
+    V: Validate<T>,
+{
+    let value = get_parsed_from(source, key)?;
+    validator
+        .validate(&value)
+        .map_err(|reason| EnvError::Invalid {
+            key: key.to_string(),
+            reason,
+        })?;
+    Ok(value)
+}
+
+/// Check that every variable in `keys` is set, reporting all missing ones
+/// in a single error.
+///
+/// Example:
+/// ```no_run
+/// use common_utils_rs::env::require_all;
+/// require_all(&["DATABASE_URL", "REDIS_URL", "SECRET_KEY"]).expect("incomplete environment");
+/// ```
+pub fn require_all(keys: &[&str]) -> Result<(), EnvError> {
+    require_all_from(&ProcessEnv, keys)
+}
+
+pub(crate) fn require_all_from(source: &impl EnvSource, keys: &[&str]) -> Result<(), EnvError> {
+    EnvError::aggregate(
+        keys.iter()
+            .filter(|key| source.get(key).is_none())
+            .map(|key| EnvError::Missing(key.to_string()))
+            .collect(),
+    )
+}
+
+/// Panic at startup unless every listed variable is set.
+///
+/// The panic message lists each missing variable on its own line.
+///
+/// Example:
+/// ```no_run
+/// common_utils_rs::assert_required!("DATABASE_URL", "REDIS_URL");
+/// ```
+#[macro_export]
+macro_rules! assert_required {
+    ($($key:expr),+ $(,)?) => {
+        if let ::std::result::Result::Err(error) = $crate::env::require_all(&[$($key),+]) {
+            ::std::panic!("{}", error);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test::{ScopedEnv, with_vars};
+
+    #[test]
+    fn test_validator_passes() {
+        let vars = [
+            ("VALIDATOR_A_URL", "postgres://db"),
+            ("VALIDATOR_A_PORT", "5432"),
+        ];
+        with_vars(&vars, || {
+            let result = Validator::new()
+                .required("VALIDATOR_A_URL")
+                .parsed::<u16>("VALIDATOR_A_PORT")
+                .optional_parsed::<u32>("VALIDATOR_A_UNSET")
+                .validate();
+            assert!(result.is_ok());
+        });
+    }
+
+    #[test]
+    fn test_validator_reports_every_problem() {
+        let mut scope = ScopedEnv::new();
+        scope
+            .remove("VALIDATOR_B_URL")
+            .set("VALIDATOR_B_PORT", "http")
+            .set("VALIDATOR_B_WORKERS", "-1")
+            .set("VALIDATOR_B_MODE", "fast");
+
+        let validator = Validator::new()
+            .required("VALIDATOR_B_URL")
+            .parsed::<u16>("VALIDATOR_B_PORT")
+            .optional_parsed::<usize>("VALIDATOR_B_WORKERS")
+            .check(
+                "VALIDATOR_B_MODE",
+                |v| v == "debug" || v == "release",
+                "must be debug or release",
+            );
+
+        let errors = validator.errors();
+        assert_eq!(errors.len(), 4);
+        assert!(matches!(&errors[0], EnvError::Missing(k) if k == "VALIDATOR_B_URL"));
+        assert!(
+            matches!(&errors[1], EnvError::ParseError { key, .. } if key == "VALIDATOR_B_PORT")
+        );
+        assert_eq!(
+            errors[3].to_string(),
+            "Invalid value for environment variable `VALIDATOR_B_MODE`: must be debug or release"
+        );
+        assert!(matches!(validator.validate(), Err(EnvError::Multiple(e)) if e.len() == 4));
+    }
+
+    #[test]
+    fn test_validator_against_source() {
+        let source: std::collections::HashMap<String, String> =
+            [("PORT".to_string(), "80".to_string())]
+                .into_iter()
+                .collect();
+        let validator = Validator::new().parsed::<u16>("PORT").required("HOST");
+        let errors = validator.errors_from(&source);
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(&errors[0], EnvError::Missing(k) if k == "HOST"));
+        assert!(validator.validate_from(&source).is_err());
+    }
+
+    #[test]
+    fn test_require_all_lists_every_missing_key() {
+        let mut scope = ScopedEnv::new();
+        scope
+            .set("REQUIRE_A", "1")
+            .remove("REQUIRE_B")
+            .remove("REQUIRE_C");
+
+        assert!(require_all(&["REQUIRE_A"]).is_ok());
+        assert!(matches!(
+            require_all(&["REQUIRE_A", "REQUIRE_B"]),
+            Err(EnvError::Missing(key)) if key == "REQUIRE_B"
+        ));
+        match require_all(&["REQUIRE_A", "REQUIRE_B", "REQUIRE_C"]) {
+            Err(EnvError::Multiple(errors)) => assert_eq!(errors.len(), 2),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_assert_required_panics_with_each_key() {
+        let mut scope = ScopedEnv::new();
+        scope.remove("ASSERT_REQ_X").remove("ASSERT_REQ_Y");
+        let result = std::panic::catch_unwind(|| {
+            crate::assert_required!("ASSERT_REQ_X", "ASSERT_REQ_Y");
+        });
+        let payload = result.unwrap_err();
+        let message = payload.downcast_ref::<String>().unwrap();
+        assert!(message.contains("\n  - Environment variable `ASSERT_REQ_X` is not set"));
+        assert!(message.contains("\n  - Environment variable `ASSERT_REQ_Y` is not set"));
+    }
+
+    #[test]
+    fn test_in_range_messages() {
+        assert!(in_range(1..=3).validate(&3).is_ok());
+        assert_eq!(
+            in_range(1024..=65535).validate(&80u16).unwrap_err(),
+            "must be between 1024 and 65535, got 80"
+        );
+        assert_eq!(
+            in_range(1..).validate(&0).unwrap_err(),
+            "must be at least 1, got 0"
+        );
+        assert_eq!(
+            in_range(..10).validate(&10).unwrap_err(),
+            "must be less than 10, got 10"
+        );
+        assert!(in_range(0.0..1.0).validate(&0.5).is_ok());
+    }
+
+    #[test]
+    fn test_string_validators() {
+        assert!(non_empty().validate(" x ").is_ok());
+        assert_eq!(
+            non_empty().validate(" \t").unwrap_err(),
+            "must not be empty"
+        );
+        assert!(min_len(3).validate("äöü").is_ok());
+        assert_eq!(
+            min_len(8).validate("hunter").unwrap_err(),
+            "must be at least 8 characters long, got 6"
+        );
+        let modes = one_of(["debug", "release"]);
+        assert!(modes.validate(&"debug".to_string()).is_ok());
+        assert_eq!(
+            modes.validate(&"fast".to_string()).unwrap_err(),
+            "must be one of: debug, release, got `fast`"
+        );
+        assert!(one_of([1, 2, 4]).validate(&4).is_ok());
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_matches_regex() {
+        let slug = matches_regex("^[a-z0-9-]+$");
+        assert!(slug.validate("my-app-2").is_ok());
+        assert_eq!(
+            slug.validate("My App").unwrap_err(),
+            "must match `^[a-z0-9-]+$`"
+        );
+        assert!(
+            try_matches_regex("^v[0-9]+$")
+                .unwrap()
+                .validate("v2")
+                .is_ok()
+        );
+        assert!(try_matches_regex("(unclosed").is_err());
+    }
+
+    #[test]
+    fn test_get_parsed_validated() {
+        let vars = [
+            ("VALIDATED_PORT", "8080"),
+            ("VALIDATED_LOW", "80"),
+            ("VALIDATED_TEXT", "http"),
+            ("VALIDATED_NAME", "ab"),
+        ];
+        with_vars(&vars, || {
+            let port: u16 = get_parsed_validated("VALIDATED_PORT", in_range(1024..=65535)).unwrap();
+            assert_eq!(port, 8080);
+            assert!(matches!(
+                get_parsed_validated::<u16, _>("VALIDATED_LOW", in_range(1024..)),
+                Err(EnvError::Invalid { key, .. }) if key == "VALIDATED_LOW"
+            ));
+            assert!(matches!(
+                get_parsed_validated::<u16, _>("VALIDATED_TEXT", in_range(1024..)),
+                Err(EnvError::ParseError { .. })
+            ));
+
+            let even = |v: &u16| match v % 2 {
+                0 => Ok(()),
+                _ => Err("must be even".to_string()),
+            };
+            assert!(get_parsed_validated("VALIDATED_PORT", in_range(1..).and(even)).is_ok());
+            match get_validated("VALIDATED_NAME", non_empty().and(min_len(3))) {
+                Err(EnvError::Invalid { reason, .. }) => {
+                    assert_eq!(reason, "must be at least 3 characters long, got 2")
+                }
+                other => panic!("unexpected result: {:?}", other),
+            }
+        });
+    }
+}
@@ -0,0 +1,638 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use super::env::{EnvError, get_required_from, parse_value};
+use super::source::{EnvSource, ProcessEnv};
+
+/// A semantic version such as `1.4.2`, `2.0.0-rc.1` or `1.0.0+build.5`.
+///
+/// Parsing is lenient for configuration use: a leading `v` is allowed and a
+/// missing minor or patch component defaults to `0`. Ordering follows the
+/// SemVer precedence rules, with build metadata only breaking ties.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    /// Pre-release identifiers without the leading `-`, e.g. `rc.1`.
+    pub pre: String,
+    /// Build metadata without the leading `+`.
+    pub build: String,
+}
+
+impl Version {
+    pub const fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+            pre: String::new(),
+            build: String::new(),
+        }
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        !self.pre.is_empty()
+    }
+}
+
+/// Why a [`Version`] or [`VersionReq`] failed to parse.
+///
+/// Only the reason is kept; the getters report the variable name and value.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[error("{reason}")]
+pub struct ParseVersionError {
+    pub reason: String,
+}
+
+impl ParseVersionError {
+    fn new(reason: impl Into<String>) -> Self {
+        Self {
+            reason: reason.into(),
+        }
+    }
+}
+
+impl FromStr for Version {
+    type Err = ParseVersionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let partial = parse_partial(s)?;
+        Ok(Version {
+            major: partial.major,
+            minor: partial.minor.unwrap_or(0),
+            patch: partial.patch.unwrap_or(0),
+            pre: partial.pre,
+            build: partial.build,
+        })
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if !self.pre.is_empty() {
+            write!(f, "-{}", self.pre)?;
+        }
+        if !self.build.is_empty() {
+            write!(f, "+{}", self.build)?;
+        }
+        Ok(())
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| cmp_pre(&self.pre, &other.pre))
+            .then_with(|| self.build.cmp(&other.build))
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A version requirement such as `>=1.2, <2` or `^1.4`.
+///
+/// Supports `=`, `>`, `>=`, `<`, `<=`, `~`, `^` and `*` with Cargo's
+/// semantics. A bare version means `^`. Comma-separated comparators must all
+/// match. As in Cargo, a pre-release version only matches if some comparator
+/// names the same `MAJOR.MINOR.PATCH` with a pre-release of its own, so
+/// `>=1.2.3-alpha` matches `1.2.3-beta` but not `1.2.4-alpha`, and `^1.2.3`
+/// matches no pre-release at all.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionReq {
+    comparators: Vec<Comparator>,
+}
+
+impl VersionReq {
+    pub fn matches(&self, version: &Version) -> bool {
+        self.comparators.iter().all(|c| c.matches(version))
+            && (!version.is_prerelease()
+                || self
+                    .comparators
+                    .iter()
+                    .any(|c| c.allows_prerelease_of(version)))
+    }
+}
+
+impl FromStr for VersionReq {
+    type Err = ParseVersionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let comparators = s
+            .split(',')
+            .map(str::trim)
+            .filter(|part| *part != "*")
+            .map(Comparator::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(VersionReq { comparators })
+    }
+}
+
+impl fmt::Display for VersionReq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.comparators.is_empty() {
+            return f.write_str("*");
+        }
+        let parts: Vec<String> = self.comparators.iter().map(|c| c.to_string()).collect();
+        f.write_str(&parts.join(", "))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Exact,
+    Greater,
+    GreaterEq,
+    Less,
+    LessEq,
+    Tilde,
+    Caret,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Partial {
+    major: u64,
+    minor: Option<u64>,
+    patch: Option<u64>,
+    pre: String,
+    build: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Comparator {
+    op: Op,
+    version: Partial,
+}
+
+impl Comparator {
+    fn parse(s: &str) -> Result<Self, ParseVersionError> {
+        let (op, rest) = [
+            (">=", Op::GreaterEq),
+            ("<=", Op::LessEq),
+            (">", Op::Greater),
+            ("<", Op::Less),
+            ("=", Op::Exact),
+            ("~", Op::Tilde),
+            ("^", Op::Caret),
+        ]
+        .iter()
+        .find_map(|(prefix, op)| s.strip_prefix(prefix).map(|rest| (*op, rest)))
+        .unwrap_or((Op::Caret, s));
+        Ok(Comparator {
+            op,
+            version: parse_partial(rest)?,
+        })
+    }
+
+    fn matches(&self, v: &Version) -> bool {
+        match self.op {
+            Op::Exact => self.matches_exact(v),
+            Op::Greater => self.matches_greater(v),
+            Op::GreaterEq => self.matches_exact(v) || self.matches_greater(v),
+            Op::Less => self.matches_less(v),
+            Op::LessEq => self.matches_exact(v) || self.matches_less(v),
+            Op::Tilde => self.matches_tilde(v),
+            Op::Caret => self.matches_caret(v),
+        }
+    }
+
+    /// Whether this comparator opts in to pre-releases of `v`'s
+    /// `MAJOR.MINOR.PATCH`.
+    fn allows_prerelease_of(&self, v: &Version) -> bool {
+        let c = &self.version;
+        c.major == v.major
+            && c.minor == Some(v.minor)
+            && c.patch == Some(v.patch)
+            && !c.pre.is_empty()
+    }
+
+    fn matches_exact(&self, v: &Version) -> bool {
+        let c = &self.version;
+        v.major == c.major
+            && c.minor.is_none_or(|minor| v.minor == minor)
+            && c.patch
+                .is_none_or(|patch| v.patch == patch && v.pre == c.pre)
+    }
+
+    fn matches_greater(&self, v: &Version) -> bool {
+        let c = &self.version;
+        if v.major != c.major {
+            return v.major > c.major;
+        }
+        let Some(minor) = c.minor else { return false };
+        if v.minor != minor {
+            return v.minor > minor;
+        }
+        let Some(patch) = c.patch else { return false };
+        if v.patch != patch {
+            return v.patch > patch;
+        }
+        cmp_pre(&v.pre, &c.pre) == Ordering::Greater
+    }
+
+    fn matches_less(&self, v: &Version) -> bool {
+        let c = &self.version;
+        if v.major != c.major {
+            return v.major < c.major;
+        }
+        let Some(minor) = c.minor else { return false };
+        if v.minor != minor {
+            return v.minor < minor;
+        }
+        let Some(patch) = c.patch else { return false };
+        if v.patch != patch {
+            return v.patch < patch;
+        }
+        cmp_pre(&v.pre, &c.pre) == Ordering::Less
+    }
+
+    fn matches_tilde(&self, v: &Version) -> bool {
+        let c = &self.version;
+        if v.major != c.major || c.minor.is_some_and(|minor| v.minor != minor) {
+            return false;
+        }
+        match c.patch {
+            Some(patch) if v.patch != patch => v.patch > patch,
+            _ => cmp_pre(&v.pre, &c.pre) != Ordering::Less,
+        }
+    }
+
+    fn matches_caret(&self, v: &Version) -> bool {
+        let c = &self.version;
+        if v.major != c.major {
+            return false;
+        }
+        let Some(minor) = c.minor else { return true };
+        let Some(patch) = c.patch else {
+            return if c.major > 0 {
+                v.minor >= minor
+            } else {
+                v.minor == minor
+            };
+        };
+
+        if c.major > 0 {
+            if v.minor != minor {
+                return v.minor > minor;
+            }
+            if v.patch != patch {
+                return v.patch > patch;
+            }
+        } else if minor > 0 {
+            if v.minor != minor {
+                return false;
+            }
+            if v.patch != patch {
+                return v.patch > patch;
+            }
+        } else if v.minor != minor || v.patch != patch {
+            return false;
+        }
+        cmp_pre(&v.pre, &c.pre) != Ordering::Less
+    }
+}
+
+impl fmt::Display for Comparator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let op = match self.op {
+            Op::Exact => "=",
+            Op::Greater => ">",
+            Op::GreaterEq => ">=",
+            Op::Less => "<",
+            Op::LessEq => "<=",
+            Op::Tilde => "~",
+            Op::Caret => "^",
+        };
+        let c = &self.version;
+        write!(f, "{}{}", op, c.major)?;
+        if let Some(minor) = c.minor {
+            write!(f, ".{}", minor)?;
+        }
+        if let Some(patch) = c.patch {
+            write!(f, ".{}", patch)?;
+        }
+        if !c.pre.is_empty() {
+            write!(f, "-{}", c.pre)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse `[v]MAJOR[.MINOR[.PATCH]][-PRE][+BUILD]`. Pre-release and build
+/// metadata require all three numeric components.
+fn parse_partial(s: &str) -> Result<Partial, ParseVersionError> {
+    let s = s.trim();
+    let s = s.strip_prefix(['v', 'V']).unwrap_or(s);
+    if s.is_empty() {
+        return Err(ParseVersionError::new("empty version"));
+    }
+    let (s, build) = match s.split_once('+') {
+        Some((s, build)) => (
+            s,
+            valid_identifiers(build, true).ok_or_else(|| {
+                ParseVersionError::new(format!("invalid build metadata `{}`", build))
+            })?,
+        ),
+        None => (s, ""),
+    };
+    let (core, pre) = match s.split_once('-') {
+        Some((core, pre)) => (
+            core,
+            valid_identifiers(pre, false)
+                .ok_or_else(|| ParseVersionError::new(format!("invalid pre-release `{}`", pre)))?,
+        ),
+        None => (s, ""),
+    };
+
+    let number = |part: &str| -> Result<u64, ParseVersionError> {
+        if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+            Err(ParseVersionError::new(format!(
+                "invalid version number `{}`",
+                part
+            )))
+        } else if part.len() > 1 && part.starts_with('0') {
+            Err(ParseVersionError::new(format!(
+                "version number `{}` has a leading zero",
+                part
+            )))
+        } else {
+            part.parse().map_err(|_| {
+                ParseVersionError::new(format!("version number `{}` is too large", part))
+            })
+        }
+    };
+    let mut parts = core.split('.');
+    let major = number(parts.next().unwrap_or(""))?;
+    let minor = parts.next().map(number).transpose()?;
+    let patch = parts.next().map(number).transpose()?;
+    if parts.next().is_some() {
+        return Err(ParseVersionError::new("more than three version numbers"));
+    }
+    if (!pre.is_empty() || !build.is_empty()) && patch.is_none() {
+        return Err(ParseVersionError::new(
+            "pre-release and build metadata need MAJOR.MINOR.PATCH",
+        ));
+    }
+
+    Ok(Partial {
+        major,
+        minor,
+        patch,
+        pre: pre.to_string(),
+        build: build.to_string(),
+    })
+}
+
+/// `s` if it is dot-separated identifiers of ASCII alphanumerics and `-`.
+/// Numeric identifiers may only have leading zeros in build metadata.
+fn valid_identifiers(s: &str, allow_leading_zeros: bool) -> Option<&str> {
+    s.split('.')
+        .all(|id| {
+            let numeric = id.bytes().all(|b| b.is_ascii_digit());
+            let leading_zero = numeric && id.len() > 1 && id.starts_with('0');
+            !id.is_empty()
+                && id.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+                && (allow_leading_zeros || !leading_zero)
+        })
+        .then_some(s)
+}
+
+/// SemVer pre-release precedence: a release sorts after any pre-release,
+/// numeric identifiers compare numerically and sort before alphanumeric ones.
+fn cmp_pre(a: &str, b: &str) -> Ordering {
+    match (a.is_empty(), b.is_empty()) {
+        (true, true) => return Ordering::Equal,
+        (true, false) => return Ordering::Greater,
+        (false, true) => return Ordering::Less,
+        (false, false) => {}
+    }
+    let mut left = a.split('.');
+    let mut right = b.split('.');
+    loop {
+        let ordering = match (left.next(), right.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) => match (x.parse::<u64>(), y.parse::<u64>()) {
+                (Ok(x), Ok(y)) => x.cmp(&y),
+                (Ok(_), Err(_)) => Ordering::Less,
+                (Err(_), Ok(_)) => Ordering::Greater,
+                (Err(_), Err(_)) => x.cmp(y),
+            },
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+/// Read a semantic version such as `1.4.2`.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::env::{get_semver, test::with_vars, Version};
+/// with_vars(&[("API_VERSION", "v2.1")], || {
+///     assert_eq!(get_semver("API_VERSION").unwrap(), Version::new(2, 1, 0));
+/// });
+/// ```
+pub fn get_semver(key: &str) -> Result<Version, EnvError> {
+    get_semver_from(&ProcessEnv, key)
+}
+
+/// Read a version and check it against `requirement`, e.g. `">=1.2, <2"`.
+///
+/// Fails with [`EnvError::Invalid`] when the version does not match or the
+/// requirement itself is malformed.
+pub fn get_semver_matching(key: &str, requirement: &str) -> Result<Version, EnvError> {
+    get_semver_matching_from(&ProcessEnv, key, requirement)
+}
+
+/// Read a version requirement such as `^1.4`.
+pub fn get_semver_req(key: &str) -> Result<VersionReq, EnvError> {
+    get_semver_req_from(&ProcessEnv, key)
+}
+
+pub(crate) fn get_semver_from(source: &impl EnvSource, key: &str) -> Result<Version, EnvError> {
+    parse_value(key, get_required_from(source, key)?)
+}
+
+pub(crate) fn get_semver_req_from(
+    source: &impl EnvSource,
+    key: &str,
+) -> Result<VersionReq, EnvError> {
+    parse_value(key, get_required_from(source, key)?)
+}
+
+pub(crate) fn get_semver_matching_from(
+    source: &impl EnvSource,
+    key: &str,
+    requirement: &str,
+) -> Result<Version, EnvError> {
+    let req: VersionReq = requirement.parse().map_err(|e| EnvError::Invalid {
+        key: key.to_string(),
+        reason: format!("invalid version requirement `{}`: {}", requirement, e),
+    })?;
+    let version = get_semver_from(source, key)?;
+    if !req.matches(&version) {
+        return Err(EnvError::Invalid {
+            key: key.to_string(),
+            reason: format!("version {} does not satisfy `{}`", version, req),
+        });
+    }
+    Ok(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test::with_vars;
+
+    fn v(s: &str) -> Version {
+        s.parse().unwrap()
+    }
+
+    fn req(s: &str) -> VersionReq {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_version_parse_and_display() {
+        assert_eq!(v("1.2.3"), Version::new(1, 2, 3));
+        assert_eq!(v("v1.2"), Version::new(1, 2, 0));
+        assert_eq!(v("3"), Version::new(3, 0, 0));
+        let full = v("1.0.0-rc.1+build.5");
+        assert_eq!(full.pre, "rc.1");
+        assert_eq!(full.build, "build.5");
+        assert!(full.is_prerelease());
+        assert_eq!(full.to_string(), "1.0.0-rc.1+build.5");
+        assert_eq!(v("1.2.3-0.rc-1+build.007").build, "build.007");
+
+        for bad in [
+            "",
+            "1.2.3.4",
+            "01.2.3",
+            "1.x",
+            "1.2-rc",
+            "1.2.3-",
+            "1.2.3-a..b",
+            "1.2.3-01",
+            "1.2.3-rc.007",
+        ] {
+            assert!(bad.parse::<Version>().is_err(), "{}", bad);
+        }
+        assert_eq!(
+            "1.x".parse::<Version>().unwrap_err().to_string(),
+            "invalid version number `x`"
+        );
+        assert_eq!(
+            "1.2.3-rc.007".parse::<Version>().unwrap_err().reason,
+            "invalid pre-release `rc.007`"
+        );
+    }
+
+    #[test]
+    fn test_get_semver_error_names_variable() {
+        with_vars(&[("APP_VERSION", "1.x"), ("APP_REQ", ">=1, ~")], || {
+            assert_eq!(
+                get_semver("APP_VERSION").unwrap_err().to_string(),
+                "Failed to parse environment variable `APP_VERSION` as Version: 1.x (invalid version number `x`)"
+            );
+            match get_semver_req("APP_REQ") {
+                Err(EnvError::ParseError { key, .. }) => assert_eq!(key, "APP_REQ"),
+                other => panic!("unexpected {:?}", other),
+            }
+        });
+    }
+
+    #[test]
+    fn test_version_precedence() {
+        let ordered = [
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+            "1.0.1",
+            "1.10.0",
+            "2.0.0",
+        ];
+        for pair in ordered.windows(2) {
+            assert!(v(pair[0]) < v(pair[1]), "{} < {}", pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    fn test_version_req_operators() {
+        assert!(req(">=1.2").matches(&v("1.2.0")));
+        assert!(req(">=1.2").matches(&v("3.0.0")));
+        assert!(!req(">=1.2").matches(&v("1.1.9")));
+        assert!(req(">1.2").matches(&v("1.3.0")));
+        assert!(!req(">1.2").matches(&v("1.2.9")));
+        assert!(req("<2").matches(&v("1.99.0")));
+        assert!(!req("<2").matches(&v("2.0.0")));
+        assert!(req("<=1.2").matches(&v("1.2.7")));
+        assert!(req("=1.2").matches(&v("1.2.7")));
+        assert!(!req("=1.2.3").matches(&v("1.2.4")));
+        assert!(req("~1.2.3").matches(&v("1.2.9")));
+        assert!(!req("~1.2.3").matches(&v("1.3.0")));
+        assert!(req("^1.2.3").matches(&v("1.9.0")));
+        assert!(!req("^1.2.3").matches(&v("2.0.0")));
+        assert!(req("0.2.3").matches(&v("0.2.5")));
+        assert!(!req("0.2.3").matches(&v("0.3.0")));
+        assert!(!req("^0.0.3").matches(&v("0.0.4")));
+        assert!(req(">=1.2, <2").matches(&v("1.5.0")));
+        assert!(!req(">=1.2, <2").matches(&v("2.1.0")));
+        assert!(req("*").matches(&v("0.0.1")));
+        assert_eq!(req(">= 1.2 , <2").to_string(), ">=1.2, <2");
+
+        for bad in ["", ">=", "1.2.x", ">=1, foo", "=1.2.3-01"] {
+            assert!(bad.parse::<VersionReq>().is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_version_req_prereleases() {
+        assert!(!req("^1.2.3").matches(&v("1.2.4-alpha")));
+        assert!(!req(">=1.2").matches(&v("1.5.0-rc.1")));
+        assert!(!req("*").matches(&v("1.0.0-beta")));
+        assert!(req("^1.2.3-alpha").matches(&v("1.2.3-beta")));
+        assert!(req("^1.2.3-alpha").matches(&v("1.2.5")));
+        assert!(!req("^1.2.3-alpha").matches(&v("1.2.4-alpha")));
+        assert!(!req("^1.2.3-beta").matches(&v("1.2.3-alpha")));
+        assert!(req(">=1.2.3-alpha, <2").matches(&v("1.2.3-rc.1")));
+        assert!(req("=2.0.0-rc.1").matches(&v("2.0.0-rc.1")));
+        assert!(!req("~1.2").matches(&v("1.2.3-alpha")));
+    }
+
+    #[test]
+    fn test_get_semver_matching() {
+        with_vars(&[("SEMVER_APP", "1.4.0"), ("SEMVER_REQ", "^1.2")], || {
+            assert_eq!(get_semver("SEMVER_APP").unwrap(), Version::new(1, 4, 0));
+            assert!(get_semver_matching("SEMVER_APP", ">=1.2").is_ok());
+            match get_semver_matching("SEMVER_APP", ">=2") {
+                Err(EnvError::Invalid { reason, .. }) => {
+                    assert_eq!(reason, "version 1.4.0 does not satisfy `>=2`")
+                }
+                other => panic!("unexpected result: {:?}", other),
+            }
+            assert!(matches!(
+                get_semver_matching("SEMVER_APP", "nonsense"),
+                Err(EnvError::Invalid { .. })
+            ));
+            let requirement = get_semver_req("SEMVER_REQ").unwrap();
+            assert!(requirement.matches(&Version::new(1, 9, 9)));
+        });
+    }
+}
@@ -0,0 +1,216 @@
+//! Reload notifications for `.env` files (`watch` feature).
+//!
+//! Example:
+//! ```no_run
+//! use common_utils_rs::env::{watch, Change};
+//!
+//! let _watcher = watch(".env", |changes| {
+//!     for change in changes {
+//!         if let Change::Changed { key, .. } = change {
+//!             println!("{} was rotated", key);
+//!         }
+//!     }
+//! })
+//! .expect("failed to watch .env");
+//! // Keep `_watcher` alive for as long as changes should be reported.
+//! ```
+use std::collections::{BTreeSet, HashMap};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use super::dotenv;
+use super::env::EnvError;
+
+/// A variable that differs between two reads of a watched file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    Added {
+        key: String,
+        value: String,
+    },
+    Changed {
+        key: String,
+        old: String,
+        new: String,
+    },
+    Removed {
+        key: String,
+        old: String,
+    },
+}
+
+impl Change {
+    pub fn key(&self) -> &str {
+        match self {
+            Change::Added { key, .. }
+            | Change::Changed { key, .. }
+            | Change::Removed { key, .. } => key,
+        }
+    }
+}
+
+/// Handle returned by [`watch`]. Watching stops when it is dropped.
+pub struct EnvWatcher {
+    path: PathBuf,
+    _watcher: RecommendedWatcher,
+}
+
+impl EnvWatcher {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl std::fmt::Debug for EnvWatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EnvWatcher")
+            .field("path", &self.path)
+            .finish()
+    }
+}
+
+/// Watch a dotenv file and call `callback` with the variables that changed
+/// each time it is rewritten.
+///
+/// The file must be readable when watching starts. Its directory is watched
+/// rather than the file itself, so editors and secret managers that replace
+/// the file (by rename or symlink swap) are picked up. If a reload fails,
+/// for example because the file is briefly missing or half-written, it is
+/// skipped and the next event is compared against the last good contents.
+/// Files rewritten in place can be observed while truncated, so rotate them
+/// atomically (write a temporary file, then rename it) where possible.
+///
+/// The callback runs on a background thread and is only called with a
+/// non-empty list of changes, sorted by key. The process environment is not
+/// modified.
+pub fn watch<F>(path: impl AsRef<Path>, mut callback: F) -> Result<EnvWatcher, EnvError>
+where
+    F: FnMut(Vec<Change>) + Send + 'static,
+{
+    let path = path.as_ref().to_path_buf();
+    let mut current = dotenv::read_from(&path)?;
+    let file_name = path.file_name().map(|name| name.to_os_string());
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+
+    let reload_path = path.clone();
+    let io_error = |e: notify::Error| EnvError::Io {
+        path: path.display().to_string(),
+        source: io::Error::other(e),
+    };
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else { return };
+        let relevant = !matches!(event.kind, EventKind::Access(_))
+            && event
+                .paths
+                .iter()
+                .any(|changed| changed.file_name() == file_name.as_deref());
+        if !relevant {
+            return;
+        }
+        let Ok(next) = dotenv::read_from(&reload_path) else {
+            return;
+        };
+        let changes = diff(&current, &next);
+        current = next;
+        if !changes.is_empty() {
+            callback(changes);
+        }
+    })
+    .map_err(io_error)?;
+    watcher
+        .watch(&dir, RecursiveMode::NonRecursive)
+        .map_err(io_error)?;
+
+    Ok(EnvWatcher {
+        path,
+        _watcher: watcher,
+    })
+}
+
+fn diff(old: &HashMap<String, String>, new: &HashMap<String, String>) -> Vec<Change> {
+    let keys: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    keys.into_iter()
+        .filter_map(|key| match (old.get(key), new.get(key)) {
+            (None, Some(value)) => Some(Change::Added {
+                key: key.clone(),
+                value: value.clone(),
+            }),
+            (Some(old), None) => Some(Change::Removed {
+                key: key.clone(),
+                old: old.clone(),
+            }),
+            (Some(old), Some(new)) if old != new => Some(Change::Changed {
+                key: key.clone(),
+                old: old.clone(),
+                new: new.clone(),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::test::fixtures::map;
+    use std::sync::mpsc;
+    use std::time::Duration;
+    use std::{env, fs};
+
+    #[test]
+    fn test_diff() {
+        let old = map(&[("A", "1"), ("B", "2"), ("C", "3")]);
+        let new = map(&[("A", "1"), ("B", "20"), ("D", "4")]);
+        assert_eq!(
+            diff(&old, &new),
+            vec![
+                Change::Changed {
+                    key: "B".to_string(),
+                    old: "2".to_string(),
+                    new: "20".to_string()
+                },
+                Change::Removed {
+                    key: "C".to_string(),
+                    old: "3".to_string()
+                },
+                Change::Added {
+                    key: "D".to_string(),
+                    value: "4".to_string()
+                },
+            ]
+        );
+        assert!(diff(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn test_watch_reports_rewrites() {
+        let dir = env::temp_dir().join(format!("common-utils-rs-watch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(".env");
+        fs::write(&path, "TOKEN=old\n").unwrap();
+
+        let (sender, receiver) = mpsc::channel();
+        let watcher = watch(&path, move |changes| {
+            let _ = sender.send(changes);
+        })
+        .unwrap();
+        assert_eq!(watcher.path(), path);
+
+        fs::write(&path, "TOKEN=new\n").unwrap();
+        let changes = receiver.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].key(), "TOKEN");
+
+        drop(watcher);
+        fs::remove_dir_all(dir).unwrap();
+        assert!(matches!(
+            watch("/definitely/not/here/.env", |_| {}),
+            Err(EnvError::Io { .. })
+        ));
+    }
+}
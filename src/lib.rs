@@ -2,8 +2,16 @@ pub fn add(left: u64, right: u64) -> u64 {
     left + right
 }
 
+// Lets `#[derive(FromEnv)]` refer to `::common_utils_rs` in this crate's own tests.
+#[cfg(all(test, feature = "derive"))]
+extern crate self as common_utils_rs;
+
 #[cfg(feature = "env")]
 pub mod env;
+#[cfg(feature = "strings")]
+pub mod strings;
+#[cfg(feature = "time")]
+pub mod time;
 
 #[cfg(test)]
 mod tests {
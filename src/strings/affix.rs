@@ -0,0 +1,100 @@
+//! Prefixes and suffixes shared by a group of strings, e.g. to shorten
+//! paths in logs or group related keys.
+
+/// The longest prefix shared by every string, cut on a char boundary. Empty
+/// if there are no strings.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::common_prefix;
+/// let keys = ["APP_DB_HOST", "APP_DB_PORT", "APP_DB_NAME"];
+/// assert_eq!(common_prefix(keys), "APP_DB_");
+/// ```
+pub fn common_prefix<'a>(strings: impl IntoIterator<Item = &'a str>) -> &'a str {
+    strings
+        .into_iter()
+        .reduce(|prefix, next| {
+            let end = prefix
+                .char_indices()
+                .zip(next.chars())
+                .find(|((_, a), b)| a != b)
+                .map_or(prefix.len().min(next.len()), |((index, _), _)| index);
+            &prefix[..end]
+        })
+        .unwrap_or("")
+}
+
+/// The longest suffix shared by every string, cut on a char boundary. Empty
+/// if there are no strings.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::common_suffix;
+/// assert_eq!(common_suffix(["access.log", "error.log"]), ".log");
+/// ```
+pub fn common_suffix<'a>(strings: impl IntoIterator<Item = &'a str>) -> &'a str {
+    strings
+        .into_iter()
+        .reduce(|suffix, next| {
+            let len: usize = suffix
+                .chars()
+                .rev()
+                .zip(next.chars().rev())
+                .take_while(|(a, b)| a == b)
+                .map(|(a, _)| a.len_utf8())
+                .sum();
+            &suffix[suffix.len() - len..]
+        })
+        .unwrap_or("")
+}
+
+/// Every string with the [`common_prefix`] of all of them removed.
+///
+/// The prefix is not limited to whole path components: `/srv/app-a` and
+/// `/srv/app-b` become `a` and `b`.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::strip_common_prefix;
+/// let paths = ["/var/log/app/api.log", "/var/log/app/worker/jobs.log"];
+/// assert_eq!(strip_common_prefix(paths), ["api.log", "worker/jobs.log"]);
+/// ```
+pub fn strip_common_prefix<'a>(strings: impl IntoIterator<Item = &'a str>) -> Vec<&'a str> {
+    let strings: Vec<&str> = strings.into_iter().collect();
+    let len = common_prefix(strings.iter().copied()).len();
+    strings.into_iter().map(|s| &s[len..]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_common_prefix() {
+        assert_eq!(common_prefix([]), "");
+        assert_eq!(common_prefix(["only"]), "only");
+        assert_eq!(common_prefix(["abc", "abd", "ab"]), "ab");
+        assert_eq!(common_prefix(["abc", "xyz"]), "");
+        assert_eq!(common_prefix(["", "abc"]), "");
+        // `é` and `è` share their first UTF-8 byte but not a char.
+        assert_eq!(common_prefix(["café", "cafè"]), "caf");
+        let owned = ["src/lib.rs".to_string(), "src/main.rs".to_string()];
+        assert_eq!(common_prefix(owned.iter().map(String::as_str)), "src/");
+    }
+
+    #[test]
+    fn test_common_suffix() {
+        assert_eq!(common_suffix([]), "");
+        assert_eq!(common_suffix(["test_a.rs", "lib_b.rs"]), ".rs");
+        assert_eq!(common_suffix(["x", "y"]), "");
+        assert_eq!(common_suffix(["naïve", "ve"]), "ve");
+        assert_eq!(common_suffix(["über", "æber"]), "ber");
+    }
+
+    #[test]
+    fn test_strip_common_prefix() {
+        assert_eq!(strip_common_prefix(["same", "same"]), ["", ""]);
+        assert_eq!(strip_common_prefix(["a", "b"]), ["a", "b"]);
+        assert!(strip_common_prefix([]).is_empty());
+    }
+}
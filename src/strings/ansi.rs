@@ -0,0 +1,105 @@
+//! ANSI escape sequences and terminal column widths, for aligning tables
+//! and progress output.
+use unicode_width::UnicodeWidthStr;
+
+/// `s` without ANSI escape sequences: colors and cursor movement (CSI,
+/// `ESC [ ... m`), hyperlinks and titles (OSC, `ESC ] ... BEL`) and other
+/// two-character escapes.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::strip_ansi;
+/// assert_eq!(strip_ansi("\x1b[1;31merror\x1b[0m: failed"), "error: failed");
+/// ```
+pub fn strip_ansi(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for_each_visible(s, |text| out.push_str(text));
+    out
+}
+
+/// The number of terminal columns `s` takes up: ANSI escape sequences take
+/// none, and East Asian wide characters and most emoji take two.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::display_width;
+/// assert_eq!(display_width("\x1b[32m✔\x1b[0m done"), 6);
+/// assert_eq!(display_width("日本語"), 6);
+/// ```
+pub fn display_width(s: &str) -> usize {
+    let mut width = 0;
+    for_each_visible(s, |text| width += text.width());
+    width
+}
+
+/// Call `f` with each run of `s` between escape sequences.
+fn for_each_visible(s: &str, mut f: impl FnMut(&str)) {
+    let mut rest = s;
+    while let Some(start) = rest.find(['\x1b', '\u{9b}']) {
+        f(&rest[..start]);
+        rest = &rest[start..];
+        let len = escape_len(rest);
+        rest = &rest[len..];
+    }
+    f(rest);
+}
+
+/// The length in bytes of the escape sequence at the start of `s`, which
+/// starts with ESC or the single-character CSI. An unterminated sequence
+/// runs to the end of `s`.
+fn escape_len(s: &str) -> usize {
+    let (body, offset) = match s.strip_prefix('\u{9b}') {
+        Some(body) => (body, '\u{9b}'.len_utf8()),
+        None => match s[1..].chars().next() {
+            Some('[') => (&s[2..], 2),
+            Some(']') => {
+                // OSC, ended by BEL or ST (`ESC \`).
+                let body = &s[2..];
+                return match body.find(['\x07', '\x1b']) {
+                    Some(end) if body[end..].starts_with('\x07') => 2 + end + 1,
+                    Some(end) if body[end..].starts_with("\x1b\\") => 2 + end + 2,
+                    Some(end) => 2 + end,
+                    None => s.len(),
+                };
+            }
+            Some(c) => return 1 + c.len_utf8(),
+            None => return 1,
+        },
+    };
+    // CSI: parameter and intermediate bytes, then a final byte in `@`..=`~`.
+    let end = body
+        .find(|c: char| !('\x20'..'\x40').contains(&c))
+        .unwrap_or(body.len());
+    let terminated = body[end..].starts_with(|c: char| ('@'..='~').contains(&c));
+    offset + end + usize::from(terminated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(strip_ansi("plain"), "plain");
+        assert_eq!(strip_ansi("\x1b[38;5;208morange\x1b[m"), "orange");
+        assert_eq!(strip_ansi("\x1b[2K\x1b[1Gprogress"), "progress");
+        assert_eq!(
+            strip_ansi("\x1b]8;;https://example.com\x07link\x1b]8;;\x1b\\ after"),
+            "link after"
+        );
+        assert_eq!(strip_ansi("\x1b7saved\x1b8"), "saved");
+        assert_eq!(strip_ansi("\u{9b}1mbold"), "bold");
+        assert_eq!(strip_ansi("ünï\x1b[1mcödé"), "ünïcödé");
+        assert_eq!(strip_ansi("cut\x1b[31"), "cut");
+        assert_eq!(strip_ansi("end\x1b"), "end");
+    }
+
+    #[test]
+    fn test_display_width() {
+        assert_eq!(display_width(""), 0);
+        assert_eq!(display_width("\x1b[1mbold\x1b[0m"), 4);
+        assert_eq!(display_width("한국어 text"), 11);
+        assert_eq!(display_width("🦀"), 2);
+        assert_eq!(display_width("cafe\u{301}"), 4);
+    }
+}
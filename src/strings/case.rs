@@ -0,0 +1,149 @@
+//! Conversions between identifier conventions such as `snake_case` and
+//! `camelCase`.
+//!
+//! Every conversion first splits the input into words: at any character
+//! that isn't a letter or digit, before an uppercase letter that follows a
+//! lowercase letter or digit (`fooBar`, `v2Beta`), and before the last
+//! capital of an acronym (`HTTPServer` is `HTTP` + `Server`). Digits stay
+//! with the word before them, so `utf8Decoder` is `utf8` + `Decoder`.
+
+/// `http_server_url`
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::to_snake_case;
+/// assert_eq!(to_snake_case("HTTPServerURL"), "http_server_url");
+/// assert_eq!(to_snake_case("maxRetries2"), "max_retries2");
+/// ```
+pub fn to_snake_case(s: &str) -> String {
+    join_lower(s, "_")
+}
+
+/// `http-server-url`
+pub fn to_kebab_case(s: &str) -> String {
+    join_lower(s, "-")
+}
+
+/// `HTTP_SERVER_URL`, as used for environment variables.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::to_screaming_snake_case;
+/// assert_eq!(to_screaming_snake_case("database.maxConnections"), "DATABASE_MAX_CONNECTIONS");
+/// ```
+pub fn to_screaming_snake_case(s: &str) -> String {
+    split_words(s)
+        .iter()
+        .map(|word| word.to_uppercase())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// `httpServerUrl`; acronyms are capitalized like any other word.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::to_camel_case;
+/// assert_eq!(to_camel_case("HTTP_SERVER_URL"), "httpServerUrl");
+/// ```
+pub fn to_camel_case(s: &str) -> String {
+    let mut out = String::new();
+    for (index, word) in split_words(s).iter().enumerate() {
+        if index == 0 {
+            out.push_str(&word.to_lowercase());
+        } else {
+            out.push_str(&capitalize(word));
+        }
+    }
+    out
+}
+
+/// `HttpServerUrl`; acronyms are capitalized like any other word.
+pub fn to_pascal_case(s: &str) -> String {
+    split_words(s).iter().map(|word| capitalize(word)).collect()
+}
+
+fn join_lower(s: &str, separator: &str) -> String {
+    split_words(s)
+        .iter()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+/// Uppercase the first character of `word` and lowercase the rest.
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+/// Split an identifier into its words, as described in the module docs.
+pub(crate) fn split_words(s: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    for part in s.split(|c: char| !c.is_alphanumeric()) {
+        let chars: Vec<(usize, char)> = part.char_indices().collect();
+        let mut start = 0;
+        for i in 1..chars.len() {
+            let (offset, c) = chars[i];
+            let previous = chars[i - 1].1;
+            let next_is_lower = chars
+                .get(i + 1)
+                .is_some_and(|(_, next)| next.is_lowercase());
+            let boundary = c.is_uppercase()
+                && (previous.is_lowercase()
+                    || previous.is_numeric()
+                    || (previous.is_uppercase() && next_is_lower));
+            if boundary {
+                words.push(&part[start..offset]);
+                start = offset;
+            }
+        }
+        if start < part.len() {
+            words.push(&part[start..]);
+        }
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_words() {
+        assert_eq!(split_words("fooBarBaz"), ["foo", "Bar", "Baz"]);
+        assert_eq!(split_words("XMLHttpRequest"), ["XML", "Http", "Request"]);
+        assert_eq!(
+            split_words("getHTTPResponseCode"),
+            ["get", "HTTP", "Response", "Code"]
+        );
+        assert_eq!(split_words("  --max__retries-- "), ["max", "retries"]);
+        assert_eq!(split_words("v2Beta"), ["v2", "Beta"]);
+        assert_eq!(split_words("utf8_decoder"), ["utf8", "decoder"]);
+        assert_eq!(split_words("ÉtéÀParis"), ["Été", "À", "Paris"]);
+        assert!(split_words("").is_empty());
+    }
+
+    #[test]
+    fn test_conversions() {
+        let inputs = ["userID", "UserId", "user_id", "USER-ID", "user id"];
+        for input in inputs {
+            assert_eq!(to_snake_case(input), "user_id", "{}", input);
+            assert_eq!(to_kebab_case(input), "user-id", "{}", input);
+            assert_eq!(to_screaming_snake_case(input), "USER_ID", "{}", input);
+            assert_eq!(to_camel_case(input), "userId", "{}", input);
+            assert_eq!(to_pascal_case(input), "UserId", "{}", input);
+        }
+        assert_eq!(to_pascal_case("http_server"), "HttpServer");
+        assert_eq!(to_camel_case("HTTPServer"), "httpServer");
+        assert_eq!(to_snake_case("Base64Encode"), "base64_encode");
+        assert_eq!(to_kebab_case("page2Title"), "page2-title");
+        assert_eq!(to_camel_case(""), "");
+    }
+}
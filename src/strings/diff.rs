@@ -0,0 +1,226 @@
+//! Character-level differences between two strings, e.g. for test
+//! failures and config changes.
+
+/// One run of a [`diff`]: text both strings share, or text only the old
+/// string has (`Delete`) or only the new one has (`Insert`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffSpan<'a> {
+    Equal(&'a str),
+    Delete(&'a str),
+    Insert(&'a str),
+}
+
+/// The shortest edit from `old` to `new`, as spans in order.
+///
+/// Uses Myers' algorithm over `char`s, whose memory grows with the square
+/// of the number of changed chars, so it suits values and test output
+/// rather than whole files. Within each change, the deleted text
+/// comes before the inserted text, and adjacent spans never have the same
+/// kind.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::{diff, DiffSpan};
+/// assert_eq!(
+///     diff("timeout=30s", "timeout=45s"),
+///     [
+///         DiffSpan::Equal("timeout="),
+///         DiffSpan::Delete("30"),
+///         DiffSpan::Insert("45"),
+///         DiffSpan::Equal("s"),
+///     ]
+/// );
+/// ```
+pub fn diff<'a>(old: &'a str, new: &'a str) -> Vec<DiffSpan<'a>> {
+    let a: Vec<(usize, char)> = old.char_indices().collect();
+    let b: Vec<(usize, char)> = new.char_indices().collect();
+    let a_offset = |i: usize| a.get(i).map_or(old.len(), |(offset, _)| *offset);
+    let b_offset = |i: usize| b.get(i).map_or(new.len(), |(offset, _)| *offset);
+
+    // Runs of consecutive common chars as (start in a, start in b, length).
+    let mut runs: Vec<(usize, usize, usize)> = Vec::new();
+    for (x, y) in common_chars(&a, &b) {
+        match runs.last_mut() {
+            Some((i, j, len)) if *i + *len == x && *j + *len == y => *len += 1,
+            _ => runs.push((x, y, 1)),
+        }
+    }
+
+    let mut spans = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    for (x, y, len) in runs.into_iter().chain([(a.len(), b.len(), 0)]) {
+        if x > i {
+            spans.push(DiffSpan::Delete(&old[a_offset(i)..a_offset(x)]));
+        }
+        if y > j {
+            spans.push(DiffSpan::Insert(&new[b_offset(j)..b_offset(y)]));
+        }
+        if len > 0 {
+            spans.push(DiffSpan::Equal(&old[a_offset(x)..a_offset(x + len)]));
+        }
+        (i, j) = (x + len, y + len);
+    }
+    spans
+}
+
+/// `spans` as one line of text, marking deletions as `[-old-]` and
+/// insertions as `{+new+}`, like `git diff --word-diff`.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::{diff, render_diff};
+/// assert_eq!(render_diff(&diff("the cat sat", "the hat sat")), "the [-c-]{+h+}at sat");
+/// ```
+pub fn render_diff(spans: &[DiffSpan<'_>]) -> String {
+    let mut out = String::new();
+    for span in spans {
+        match span {
+            DiffSpan::Equal(text) => out.push_str(text),
+            DiffSpan::Delete(text) => {
+                out.push_str("[-");
+                out.push_str(text);
+                out.push_str("-]");
+            }
+            DiffSpan::Insert(text) => {
+                out.push_str("{+");
+                out.push_str(text);
+                out.push_str("+}");
+            }
+        }
+    }
+    out
+}
+
+/// The index pairs of the chars kept by a shortest edit script from `a` to
+/// `b`, in order (Myers, "An O(ND) Difference Algorithm").
+fn common_chars(a: &[(usize, char)], b: &[(usize, char)]) -> Vec<(usize, usize)> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max = (n + m) as usize;
+    let index = |k: isize| (k + max as isize) as usize;
+
+    // The furthest x reached on each diagonal k = x - y. Before each round
+    // d, diagonals -d..=d are saved for the walk back.
+    let mut v = vec![0isize; 2 * max + 2];
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+    'search: for d in 0..=max as isize {
+        trace.push(v[index(-d)..=index(d)].to_vec());
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d || (k != d && v[index(k - 1)] < v[index(k + 1)]) {
+                v[index(k + 1)]
+            } else {
+                v[index(k - 1)] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize].1 == b[y as usize].1 {
+                x += 1;
+                y += 1;
+            }
+            v[index(k)] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+        }
+    }
+
+    let mut common = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, saved) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let v = |k: isize| saved[(k + d) as usize];
+        let k = x - y;
+        let (previous_x, previous_y) = if d == 0 {
+            (0, 0)
+        } else {
+            let previous_k = if k == -d || (k != d && v(k - 1) < v(k + 1)) {
+                k + 1
+            } else {
+                k - 1
+            };
+            (v(previous_k), v(previous_k) - previous_k)
+        };
+        while x > previous_x && y > previous_y {
+            x -= 1;
+            y -= 1;
+            common.push((x as usize, y as usize));
+        }
+        (x, y) = (previous_x, previous_y);
+    }
+    common.reverse();
+    common
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use DiffSpan::*;
+
+    /// Rebuild both sides from the spans.
+    fn sides(spans: &[DiffSpan<'_>]) -> (String, String) {
+        let (mut old, mut new) = (String::new(), String::new());
+        for span in spans {
+            match span {
+                Equal(text) => {
+                    old.push_str(text);
+                    new.push_str(text);
+                }
+                Delete(text) => old.push_str(text),
+                Insert(text) => new.push_str(text),
+            }
+        }
+        (old, new)
+    }
+
+    #[test]
+    fn test_diff() {
+        assert!(diff("", "").is_empty());
+        assert_eq!(diff("same", "same"), [Equal("same")]);
+        assert_eq!(diff("", "new"), [Insert("new")]);
+        assert_eq!(diff("old", ""), [Delete("old")]);
+        assert_eq!(diff("abc", "xyz"), [Delete("abc"), Insert("xyz")]);
+        assert_eq!(diff("host=a", "host=ab"), [Equal("host=a"), Insert("b")]);
+        assert_eq!(
+            diff("naïve café", "naive café!"),
+            [
+                Equal("na"),
+                Delete("ï"),
+                Insert("i"),
+                Equal("ve café"),
+                Insert("!")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_is_minimal_and_rebuilds_both_sides() {
+        let cases = [
+            ("ABCABBA", "CBABAC", 5),
+            ("kitten", "sitting", 5),
+            ("a\nb\nc\n", "a\nc\nd\n", 4),
+            ("🦀🦀x", "x🦀", 3),
+        ];
+        for (old, new, edits) in cases {
+            let spans = diff(old, new);
+            assert_eq!(sides(&spans), (old.to_string(), new.to_string()));
+            let changed: usize = spans
+                .iter()
+                .map(|span| match span {
+                    Equal(_) => 0,
+                    Delete(text) | Insert(text) => text.chars().count(),
+                })
+                .sum();
+            assert_eq!(changed, edits, "{:?} -> {:?}", old, new);
+            assert!(spans.windows(2).all(|pair| {
+                std::mem::discriminant(&pair[0]) != std::mem::discriminant(&pair[1])
+            }));
+        }
+    }
+
+    #[test]
+    fn test_render_diff() {
+        assert_eq!(render_diff(&[]), "");
+        assert_eq!(
+            render_diff(&diff("PORT=8080", "PORT=9090")),
+            "PORT=[-8-]{+9+}0[-8-]{+9+}0"
+        );
+    }
+}
@@ -0,0 +1,280 @@
+//! Escaping for HTML, JSON string literals and POSIX shells.
+use thiserror::Error;
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[error("Invalid escape at byte {offset}: {message}")]
+pub struct UnescapeError {
+    pub offset: usize,
+    pub message: String,
+}
+
+/// `s` with `&`, `<`, `>`, `"` and `'` replaced by entities, safe in HTML
+/// text and quoted attribute values.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::escape_html;
+/// assert_eq!(escape_html(r#"<a href="x">Tom & Jerry's</a>"#),
+///     "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&#39;s&lt;/a&gt;");
+/// ```
+pub fn escape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// `s` with HTML entities decoded: `&amp;`, `&lt;`, `&gt;`, `&quot;`,
+/// `&apos;`, `&nbsp;` and numeric references (`&#39;`, `&#x1F980;`).
+///
+/// Anything else, including unknown entities, is kept as written. Numeric
+/// references to invalid code points become U+FFFD.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::unescape_html;
+/// assert_eq!(unescape_html("Tom &amp; Jerry&#39;s &copy;"), "Tom & Jerry's &copy;");
+/// ```
+pub fn unescape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest.find(';').and_then(|end| {
+            let c = match &rest[1..end] {
+                "amp" => '&',
+                "lt" => '<',
+                "gt" => '>',
+                "quot" => '"',
+                "apos" => '\'',
+                "nbsp" => '\u{a0}',
+                entity => {
+                    let number = entity.strip_prefix('#')?;
+                    let code = match number.strip_prefix(['x', 'X']) {
+                        Some(hex) => u32::from_str_radix(hex, 16),
+                        None => number.parse(),
+                    }
+                    .ok()?;
+                    char::from_u32(code)
+                        .filter(|c| *c != '\0')
+                        .unwrap_or(char::REPLACEMENT_CHARACTER)
+                }
+            };
+            Some((c, end + 1))
+        });
+        match decoded {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// `s` escaped for use inside a JSON string literal (without the
+/// surrounding quotes).
+///
+/// `"` and `\` are escaped, as are all control characters: the common ones
+/// as `\n`, `\r`, `\t`, `\b` and `\f`, the rest as `\u00XX`. U+2028 and
+/// U+2029 are escaped too, so the result is also safe inside JavaScript.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::escape_json_string;
+/// assert_eq!(escape_json_string("say \"hi\"\n\u{1}"), r#"say \"hi\"\n\u0001"#);
+/// ```
+pub fn escape_json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            c if c.is_control() || c == '\u{2028}' || c == '\u{2029}' => {
+                out.push_str(&format!("\\u{:04x}", c as u32));
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// The inverse of [`escape_json_string`]: decode the escapes of a JSON
+/// string literal's contents, including surrogate pairs (`🦀`).
+///
+/// Unknown escapes, lone surrogates and unescaped control characters are
+/// errors, as they are in JSON.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::unescape_json_string;
+/// assert_eq!(unescape_json_string(r#"tab\there é 🦀"#).unwrap(), "tab\there é 🦀");
+/// assert!(unescape_json_string(r"\q").is_err());
+/// ```
+pub fn unescape_json_string(s: &str) -> Result<String, UnescapeError> {
+    let err = |offset: usize, message: &str| UnescapeError {
+        offset,
+        message: message.to_string(),
+    };
+    let hex4 = |offset: usize| -> Result<u32, UnescapeError> {
+        s.get(offset..offset + 4)
+            .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .ok_or_else(|| err(offset - 2, "expected 4 hex digits after `\\u`"))
+    };
+
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.char_indices();
+    while let Some((offset, c)) = chars.next() {
+        if c != '\\' {
+            if (c as u32) < 0x20 {
+                return Err(err(offset, "unescaped control character"));
+            }
+            out.push(c);
+            continue;
+        }
+        let escaped = match chars.next() {
+            Some((_, '"')) => '"',
+            Some((_, '\\')) => '\\',
+            Some((_, '/')) => '/',
+            Some((_, 'n')) => '\n',
+            Some((_, 'r')) => '\r',
+            Some((_, 't')) => '\t',
+            Some((_, 'b')) => '\u{8}',
+            Some((_, 'f')) => '\u{c}',
+            Some((_, 'u')) => {
+                let high = hex4(offset + 2)?;
+                chars.nth(3);
+                let code = if (0xd800..0xdc00).contains(&high) {
+                    if s.get(offset + 6..offset + 8) != Some("\\u") {
+                        return Err(err(offset, "unpaired surrogate"));
+                    }
+                    let low = hex4(offset + 8)?;
+                    if !(0xdc00..0xe000).contains(&low) {
+                        return Err(err(offset, "unpaired surrogate"));
+                    }
+                    chars.nth(5);
+                    0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+                } else {
+                    high
+                };
+                char::from_u32(code).ok_or_else(|| err(offset, "unpaired surrogate"))?
+            }
+            Some(_) => return Err(err(offset, "unknown escape")),
+            None => return Err(err(offset, "unexpected end after `\\`")),
+        };
+        out.push(escaped);
+    }
+    Ok(out)
+}
+
+/// `s` quoted as a single argument for a POSIX shell.
+///
+/// Words made only of letters, digits and `_@%+=:,./-` are returned as
+/// they are; anything else is wrapped in single quotes, inside which every
+/// character, including newlines and control characters, is literal.
+/// [`split_quoted`](super::split_quoted) is the inverse.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::{shell_quote, split_quoted};
+/// assert_eq!(shell_quote("--config=/etc/app.toml"), "--config=/etc/app.toml");
+/// assert_eq!(shell_quote("it's here"), r"'it'\''s here'");
+/// assert_eq!(split_quoted(&shell_quote("it's here")).unwrap(), ["it's here"]);
+/// ```
+pub fn shell_quote(s: &str) -> String {
+    let safe = |c: char| c.is_ascii_alphanumeric() || "_@%+=:,./-".contains(c);
+    if !s.is_empty() && s.chars().all(safe) {
+        return s.to_string();
+    }
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strings::split_quoted;
+
+    #[test]
+    fn test_html_round_trip() {
+        let text = "<script>alert('x & y')</script> \"ok\" ünï";
+        assert_eq!(unescape_html(&escape_html(text)), text);
+        assert_eq!(escape_html("plain"), "plain");
+    }
+
+    #[test]
+    fn test_unescape_html() {
+        assert_eq!(unescape_html("&lt;&gt;&quot;&apos;&nbsp;"), "<>\"'\u{a0}");
+        assert_eq!(unescape_html("&#65;&#x42;&#X43;"), "ABC");
+        assert_eq!(
+            unescape_html("&#xD800; &#0; &#99999999;"),
+            "\u{fffd} \u{fffd} \u{fffd}"
+        );
+        assert_eq!(
+            unescape_html("AT&T; a & b; &#xZZ; &"),
+            "AT&T; a & b; &#xZZ; &"
+        );
+        assert_eq!(unescape_html("&amp;lt;"), "&lt;");
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let text = "quote \" slash \\ / \u{0}\u{1f}\u{7f} \u{2028} tab\t 🦀";
+        let escaped = escape_json_string(text);
+        assert!(!escaped.chars().any(char::is_control));
+        assert_eq!(unescape_json_string(&escaped).unwrap(), text);
+        assert_eq!(escape_json_string("\u{7f}"), "\\u007f");
+        assert_eq!(unescape_json_string(r"\/").unwrap(), "/");
+    }
+
+    #[test]
+    fn test_unescape_json_string_errors() {
+        let message = |s: &str| unescape_json_string(s).unwrap_err().message;
+        assert_eq!(message(r"ab\x"), "unknown escape");
+        assert_eq!(message("a\\"), "unexpected end after `\\`");
+        assert_eq!(message(r"\u12"), "expected 4 hex digits after `\\u`");
+        assert_eq!(message(r"\u+123"), "expected 4 hex digits after `\\u`");
+        assert_eq!(message(r"\ud83e"), "unpaired surrogate");
+        assert_eq!(message(r"\ud83eA"), "unpaired surrogate");
+        assert_eq!(message(r"\udd80"), "unpaired surrogate");
+        assert_eq!(message("new\nline"), "unescaped control character");
+        assert_eq!(unescape_json_string(r"ab\x").unwrap_err().offset, 2);
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote(""), "''");
+        assert_eq!(shell_quote("a b"), "'a b'");
+        assert_eq!(shell_quote("$HOME"), "'$HOME'");
+        for arg in [
+            "",
+            "it's",
+            "two\nlines",
+            "\"double\"",
+            "back\\slash",
+            "ünï",
+            "'",
+        ] {
+            assert_eq!(split_quoted(&shell_quote(arg)).unwrap(), [arg]);
+        }
+    }
+}
@@ -0,0 +1,164 @@
+//! fzf-style fuzzy matching for interactive filtering.
+
+const MATCH: i64 = 16;
+const BOUNDARY_BONUS: i64 = 8;
+const CAMEL_BONUS: i64 = 7;
+const CONSECUTIVE_BONUS: i64 = 4;
+const FIRST_CHAR_BONUS: i64 = 8;
+const GAP_START: i64 = 3;
+const GAP_EXTENSION: i64 = 1;
+
+/// How well a needle matched, returned by [`fuzzy_match`].
+///
+/// Scores order by `value` first, so sorting candidates by their score puts
+/// the best match last.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Score {
+    /// Higher is better. Only comparable between matches of the same needle.
+    pub value: i64,
+    /// The `char` index in the haystack matched by each needle character, in
+    /// order, e.g. for highlighting.
+    pub positions: Vec<usize>,
+}
+
+/// Match the characters of `needle` in order anywhere in `haystack`,
+/// allowing gaps (`cfg` matches `config.toml`).
+///
+/// Matching is case-insensitive unless `needle` contains an uppercase
+/// letter. Matches score higher when they are consecutive, start a word
+/// (after a separator or at a camelCase hump) or start the haystack, and
+/// lower for every skipped character between them. Of all ways to match,
+/// the best-scoring one is returned.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::fuzzy_match;
+/// let score = fuzzy_match("gcm", "git commit --amend").unwrap();
+/// assert_eq!(score.positions, [0, 4, 6]);
+/// assert!(fuzzy_match("gcm", "src/config_manager.rs") < fuzzy_match("gcm", "get_config_map"));
+/// assert_eq!(fuzzy_match("xyz", "git commit"), None);
+/// ```
+pub fn fuzzy_match(needle: &str, haystack: &str) -> Option<Score> {
+    let case_sensitive = needle.chars().any(char::is_uppercase);
+    let fold = |c: char| {
+        if case_sensitive {
+            c
+        } else {
+            c.to_lowercase().next().unwrap_or(c)
+        }
+    };
+    let needle: Vec<char> = needle.chars().map(fold).collect();
+    let original: Vec<char> = haystack.chars().collect();
+    let haystack: Vec<char> = original.iter().copied().map(fold).collect();
+    let (n, m) = (needle.len(), haystack.len());
+    if n == 0 {
+        return Some(Score {
+            value: 0,
+            positions: Vec::new(),
+        });
+    }
+    if n > m {
+        return None;
+    }
+
+    let bonus: Vec<i64> = (0..m).map(|j| position_bonus(&original, j)).collect();
+    // best[i][j]: best score with needle[i] matched at haystack[j];
+    // from[i][j]: where needle[i - 1] was matched on that path.
+    let mut best = vec![vec![None::<i64>; m]; n];
+    let mut from = vec![vec![0usize; m]; n];
+    for i in 0..n {
+        // Best way to reach column j with a gap before it: (score, index).
+        let mut gapped: Option<(i64, usize)> = None;
+        for j in i..m {
+            if i > 0 && j >= 2 {
+                let extended = gapped.map(|(score, k)| (score - GAP_EXTENSION, k));
+                let opened = best[i - 1][j - 2].map(|score| (score - GAP_START, j - 2));
+                gapped = match (extended, opened) {
+                    (Some(a), Some(b)) => Some(if b.0 >= a.0 { b } else { a }),
+                    (a, b) => a.or(b),
+                };
+            }
+            if haystack[j] != needle[i] {
+                continue;
+            }
+            let here = MATCH + bonus[j];
+            if i == 0 {
+                best[0][j] = Some(here);
+                continue;
+            }
+            let adjacent = best[i - 1][j - 1].map(|score| (score + CONSECUTIVE_BONUS, j - 1));
+            let chosen = match (adjacent, gapped) {
+                (Some(a), Some(g)) => Some(if a.0 >= g.0 { a } else { g }),
+                (a, g) => a.or(g),
+            };
+            if let Some((score, k)) = chosen {
+                best[i][j] = Some(score + here);
+                from[i][j] = k;
+            }
+        }
+    }
+
+    let (value, mut j) = (0..m)
+        .filter_map(|j| Some((best[n - 1][j]?, j)))
+        .max_by_key(|&(score, j)| (score, std::cmp::Reverse(j)))?;
+    let mut positions = vec![0; n];
+    for i in (0..n).rev() {
+        positions[i] = j;
+        j = from[i][j];
+    }
+    Some(Score { value, positions })
+}
+
+/// Extra score for a match at `haystack[j]`.
+fn position_bonus(haystack: &[char], j: usize) -> i64 {
+    let Some(&previous) = j.checked_sub(1).and_then(|p| haystack.get(p)) else {
+        return BOUNDARY_BONUS + FIRST_CHAR_BONUS;
+    };
+    let current = haystack[j];
+    if !previous.is_alphanumeric() && current.is_alphanumeric() {
+        BOUNDARY_BONUS
+    } else if previous.is_lowercase() && current.is_uppercase() {
+        CAMEL_BONUS
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn positions(needle: &str, haystack: &str) -> Option<Vec<usize>> {
+        fuzzy_match(needle, haystack).map(|score| score.positions)
+    }
+
+    #[test]
+    fn test_fuzzy_match_positions() {
+        assert_eq!(positions("abc", "abc"), Some(vec![0, 1, 2]));
+        assert_eq!(positions("cfg", "config.toml"), Some(vec![0, 3, 5]));
+        // Prefers word starts over the first occurrence of each letter.
+        assert_eq!(positions("fb", "foo_bar_fb"), Some(vec![0, 4]));
+        assert_eq!(positions("gm", "getMessage"), Some(vec![0, 3]));
+        assert_eq!(positions("ab", "xaxab"), Some(vec![3, 4]));
+        assert_eq!(positions("ba", "abc"), None);
+        assert_eq!(positions("abcd", "abc"), None);
+        assert_eq!(positions("", "abc"), Some(vec![]));
+        assert_eq!(positions("ö", "Köln"), Some(vec![1]));
+    }
+
+    #[test]
+    fn test_fuzzy_match_smart_case() {
+        assert!(fuzzy_match("readme", "README.md").is_some());
+        assert!(fuzzy_match("ReadMe", "README.md").is_none());
+        assert!(fuzzy_match("ReadMe", "ReadMe.md").is_some());
+    }
+
+    #[test]
+    fn test_fuzzy_match_ranking() {
+        let score = |haystack| fuzzy_match("main", haystack).unwrap().value;
+        assert!(score("main.rs") > score("src/domain.rs"));
+        assert!(score("main.rs") > score("src/main.rs"));
+        assert!(score("src/main.rs") > score("src/domain.rs"));
+        assert!(score("m_a_i_n") > score("mxxaxxixxn"));
+    }
+}
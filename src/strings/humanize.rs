@@ -0,0 +1,126 @@
+//! Compact numbers and byte sizes for dashboards and CLI output.
+
+const NUMBER_SUFFIXES: [&str; 7] = ["", "K", "M", "B", "T", "Q", "Qi"];
+const BINARY_UNITS: [&str; 7] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+const DECIMAL_UNITS: [&str; 7] = ["B", "kB", "MB", "GB", "TB", "PB", "EB"];
+
+/// Which multiples [`humanize_bytes`] uses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ByteUnits {
+    /// Powers of 1024 (`KiB`, `MiB`, `GiB`), as shown by `ByteSize`.
+    #[default]
+    Binary,
+    /// Powers of 1000 (`kB`, `MB`, `GB`), as on disk labels and in SI.
+    Decimal,
+}
+
+/// `n` with a thousands suffix (`K`, `M`, `B`, `T`) and one decimal place.
+///
+/// Numbers below 1000 are printed as they are, and a trailing `.0` is
+/// dropped.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::humanize_number;
+/// assert_eq!(humanize_number(1_234_567), "1.2M");
+/// assert_eq!(humanize_number(-4_000), "-4K");
+/// assert_eq!(humanize_number(999), "999");
+/// ```
+pub fn humanize_number(n: i64) -> String {
+    humanize_number_with(n, 1)
+}
+
+/// Like [`humanize_number`], with `precision` decimal places.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::humanize_number_with;
+/// assert_eq!(humanize_number_with(1_234_567, 2), "1.23M");
+/// ```
+pub fn humanize_number_with(n: i64, precision: usize) -> String {
+    let sign = if n < 0 { "-" } else { "" };
+    let (value, suffix) = scale(n.unsigned_abs(), 1000, &NUMBER_SUFFIXES, precision);
+    format!("{}{}{}", sign, value, suffix)
+}
+
+/// `bytes` in the largest unit that keeps the value at least 1, with one
+/// decimal place: the display counterpart to `parse_memory_size`.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::{humanize_bytes, ByteUnits};
+/// assert_eq!(humanize_bytes(1_572_864, ByteUnits::Binary), "1.5 MiB");
+/// assert_eq!(humanize_bytes(1_500_000, ByteUnits::Decimal), "1.5 MB");
+/// assert_eq!(humanize_bytes(512, ByteUnits::Binary), "512 B");
+/// ```
+pub fn humanize_bytes(bytes: u64, units: ByteUnits) -> String {
+    humanize_bytes_with(bytes, units, 1)
+}
+
+/// Like [`humanize_bytes`], with `precision` decimal places.
+pub fn humanize_bytes_with(bytes: u64, units: ByteUnits, precision: usize) -> String {
+    let (value, unit) = match units {
+        ByteUnits::Binary => scale(bytes, 1024, &BINARY_UNITS, precision),
+        ByteUnits::Decimal => scale(bytes, 1000, &DECIMAL_UNITS, precision),
+    };
+    format!("{} {}", value, unit)
+}
+
+/// `n` divided down to the largest unit that keeps it at least 1, formatted
+/// with `precision` decimals and without trailing zeros.
+fn scale(n: u64, base: u64, units: &[&'static str], precision: usize) -> (String, &'static str) {
+    let mut unit = 0;
+    let mut divisor = 1u64;
+    while unit < units.len() - 1 && n / divisor >= base {
+        divisor *= base;
+        unit += 1;
+    }
+    if unit == 0 {
+        return (n.to_string(), units[0]);
+    }
+    let value = n as f64 / divisor as f64;
+    let mut formatted = format!("{:.*}", precision, value);
+    // 999_950 rounds to "1000.0K"; show it as "1M" instead.
+    if unit < units.len() - 1 && formatted.parse::<f64>().is_ok_and(|v| v >= base as f64) {
+        unit += 1;
+        formatted = format!("{:.*}", precision, value / base as f64);
+    }
+    if formatted.contains('.') {
+        formatted = formatted
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string();
+    }
+    (formatted, units[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_humanize_number() {
+        assert_eq!(humanize_number(0), "0");
+        assert_eq!(humanize_number(1_000), "1K");
+        assert_eq!(humanize_number(1_050), "1.1K");
+        assert_eq!(humanize_number(999_950), "1M");
+        assert_eq!(humanize_number(2_500_000_000), "2.5B");
+        assert_eq!(humanize_number(i64::MIN), "-9.2Qi");
+        assert_eq!(humanize_number_with(1_234_567, 0), "1M");
+        assert_eq!(humanize_number_with(1_500, 3), "1.5K");
+    }
+
+    #[test]
+    fn test_humanize_bytes() {
+        assert_eq!(humanize_bytes(0, ByteUnits::Binary), "0 B");
+        assert_eq!(humanize_bytes(1024, ByteUnits::Binary), "1 KiB");
+        assert_eq!(humanize_bytes(1000, ByteUnits::Binary), "1000 B");
+        assert_eq!(humanize_bytes(1000, ByteUnits::Decimal), "1 kB");
+        assert_eq!(humanize_bytes(1_048_575, ByteUnits::Binary), "1 MiB");
+        assert_eq!(humanize_bytes(u64::MAX, ByteUnits::Binary), "16 EiB");
+        assert_eq!(
+            humanize_bytes_with(1_234_567_890, ByteUnits::Decimal, 2),
+            "1.23 GB"
+        );
+    }
+}
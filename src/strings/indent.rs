@@ -0,0 +1,92 @@
+//! Indenting and dedenting blocks of text, e.g. embedded SQL or generated
+//! code.
+use super::affix::common_prefix;
+
+/// `text` with `prefix` added to the start of every line that isn't blank.
+///
+/// Line endings, including `\r\n`, are kept as they are.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::indent;
+/// assert_eq!(indent("fn main() {}\n\nmod a;\n", "    "), "    fn main() {}\n\n    mod a;\n");
+/// ```
+pub fn indent(text: &str, prefix: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for line in text.split_inclusive('\n') {
+        if !line.trim().is_empty() {
+            out.push_str(prefix);
+        }
+        out.push_str(line);
+    }
+    out
+}
+
+/// `text` with the leading whitespace common to all non-blank lines
+/// removed, like Python's `textwrap.dedent`.
+///
+/// Tabs and spaces are not treated as equal, so lines indented with a tab
+/// and lines indented with spaces have no common indentation. Lines holding
+/// only whitespace are emptied and don't count towards the margin.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::dedent;
+/// let sql = "
+///     SELECT id
+///       FROM users
+///     WHERE active
+/// ";
+/// assert_eq!(dedent(sql), "\nSELECT id\n  FROM users\nWHERE active\n");
+/// ```
+pub fn dedent(text: &str) -> String {
+    let margin = common_prefix(
+        text.split_inclusive('\n')
+            .filter(|line| !line.trim().is_empty())
+            .map(leading_whitespace),
+    );
+
+    let mut out = String::with_capacity(text.len());
+    for line in text.split_inclusive('\n') {
+        if line.trim().is_empty() {
+            out.push_str(&line[line.trim_end_matches(['\r', '\n']).len()..]);
+        } else {
+            out.push_str(&line[margin.len()..]);
+        }
+    }
+    out
+}
+
+fn leading_whitespace(line: &str) -> &str {
+    &line[..line.len() - line.trim_start().len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_indent() {
+        assert_eq!(indent("a\nb", "> "), "> a\n> b");
+        assert_eq!(indent("a\r\n  \r\nb\r\n", "\t"), "\ta\r\n  \r\n\tb\r\n");
+        assert_eq!(indent("", "  "), "");
+    }
+
+    #[test]
+    fn test_dedent() {
+        assert_eq!(dedent("  a\n    b\n  c"), "a\n  b\nc");
+        assert_eq!(dedent("    a\n  \n    b\n"), "a\n\nb\n");
+        assert_eq!(dedent("  a\r\n   \r\n  b\r\n"), "a\r\n\r\nb\r\n");
+        assert_eq!(dedent("\ta\n    b\n"), "\ta\n    b\n");
+        assert_eq!(dedent("\t\ta\n\tb\n"), "\ta\nb\n");
+        assert_eq!(dedent("no indent\n  here"), "no indent\n  here");
+        assert_eq!(dedent("   \n  "), "\n");
+        assert_eq!(dedent(""), "");
+    }
+
+    #[test]
+    fn test_dedent_then_indent_round_trips() {
+        let block = "if x:\n    y()\n";
+        assert_eq!(dedent(&indent(block, "        ")), block);
+    }
+}
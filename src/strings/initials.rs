@@ -0,0 +1,71 @@
+//! Initials and acronyms, e.g. for avatars and abbreviated labels.
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::case::split_words;
+
+/// The uppercased first letter of each word of a name.
+///
+/// Words are found by Unicode word segmentation, so hyphenated names give
+/// one initial per part and apostrophes stay inside a word (`O'Brien` is
+/// one word). Words that don't start with a letter, such as numbers, are
+/// skipped.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::initials;
+/// assert_eq!(initials("Ada Lovelace"), "AL");
+/// assert_eq!(initials("jean-luc picard"), "JLP");
+/// assert_eq!(initials("Émile O'Brien"), "ÉO");
+/// ```
+pub fn initials(name: &str) -> String {
+    name.unicode_words()
+        .filter_map(|word| word.chars().next())
+        .filter(|c| c.is_alphabetic())
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+/// The uppercased first letter of each word of a phrase, where capitals
+/// inside a word start a new one (`HyperText` is `Hyper` + `Text`).
+///
+/// Words are split as in [`to_snake_case`](super::to_snake_case), so
+/// existing acronyms are kept as a single letter.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::acronym;
+/// assert_eq!(acronym("HyperText Transfer Protocol"), "HTTP");
+/// assert_eq!(acronym("portable network graphics"), "PNG");
+/// assert_eq!(acronym("Self-Contained Underwater Breathing Apparatus"), "SCUBA");
+/// ```
+pub fn acronym(phrase: &str) -> String {
+    split_words(phrase)
+        .iter()
+        .filter_map(|word| word.chars().next())
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_initials() {
+        assert_eq!(initials(""), "");
+        assert_eq!(initials("  cher  "), "C");
+        assert_eq!(initials("Grace B. Hopper"), "GBH");
+        assert_eq!(initials("Louis XIV 1638"), "LX");
+        assert_eq!(initials("McDonald"), "M");
+        assert_eq!(initials("ärger über"), "ÄÜ");
+    }
+
+    #[test]
+    fn test_acronym() {
+        assert_eq!(acronym(""), "");
+        assert_eq!(acronym("JavaScript Object Notation"), "JSON");
+        assert_eq!(acronym("XML Parser"), "XP");
+        assert_eq!(acronym("as soon as possible"), "ASAP");
+        assert_eq!(acronym("Web 2 point 0"), "W2P0");
+    }
+}
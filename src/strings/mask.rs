@@ -0,0 +1,97 @@
+//! Partial masking of identifying values, so logs can carry a hint without
+//! the whole value.
+//!
+//! Every masked character becomes one `*`, so the length stays visible.
+//! Values too short to keep the requested characters and still hide
+//! anything are masked completely.
+
+const MASK: char = '*';
+
+/// Keep the first `keep_start` and last `keep_end` characters of `s` and
+/// mask the rest.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::mask_middle;
+/// assert_eq!(mask_middle("4111111111111111", 4, 4), "4111********1111");
+/// assert_eq!(mask_middle("short", 4, 4), "*****");
+/// ```
+pub fn mask_middle(s: &str, keep_start: usize, keep_end: usize) -> String {
+    let len = s.chars().count();
+    if keep_start + keep_end >= len {
+        return MASK.to_string().repeat(len);
+    }
+    s.chars()
+        .enumerate()
+        .map(|(i, c)| {
+            if i < keep_start || i >= len - keep_end {
+                c
+            } else {
+                MASK
+            }
+        })
+        .collect()
+}
+
+/// Mask all of `s` except its last `n` characters, e.g. for account numbers.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::mask_all_but_last;
+/// assert_eq!(mask_all_but_last("DE89370400440532013000", 4), "******************3000");
+/// ```
+pub fn mask_all_but_last(s: &str, n: usize) -> String {
+    mask_middle(s, 0, n)
+}
+
+/// Mask the local part of an email address except its first character,
+/// keeping the domain.
+///
+/// Anything without exactly one `@` and text on both sides is masked
+/// completely.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::mask_email;
+/// assert_eq!(mask_email("ada.lovelace@example.com"), "a***********@example.com");
+/// ```
+pub fn mask_email(email: &str) -> String {
+    match email.split_once('@') {
+        Some((local, domain))
+            if !local.is_empty() && !domain.is_empty() && !domain.contains('@') =>
+        {
+            format!("{}@{}", mask_middle(local, 1, 0), domain)
+        }
+        _ => mask_middle(email, 0, 0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask_middle() {
+        assert_eq!(mask_middle("secret-token", 2, 2), "se********en");
+        assert_eq!(mask_middle("secret", 0, 0), "******");
+        assert_eq!(mask_middle("abcd", 2, 2), "****");
+        assert_eq!(mask_middle("abcde", 2, 2), "ab*de");
+        assert_eq!(mask_middle("", 1, 1), "");
+        assert_eq!(mask_middle("pässwörd", 1, 1), "p******d");
+    }
+
+    #[test]
+    fn test_mask_all_but_last() {
+        assert_eq!(mask_all_but_last("5555444433331111", 4), "************1111");
+        assert_eq!(mask_all_but_last("1234", 4), "****");
+    }
+
+    #[test]
+    fn test_mask_email() {
+        assert_eq!(mask_email("bob@example.com"), "b**@example.com");
+        assert_eq!(mask_email("x@example.com"), "*@example.com");
+        assert_eq!(mask_email("not-an-email"), "************");
+        assert_eq!(mask_email("a@b@c"), "*****");
+        assert_eq!(mask_email("@example.com"), "************");
+    }
+}
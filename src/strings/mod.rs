@@ -0,0 +1,84 @@
+//! String utilities (`strings` feature).
+//!
+//! Features:
+//! - `strip_ansi` / `display_width` (terminal columns, ignoring escape sequences)
+//! - `to_snake_case` / `to_kebab_case` / `to_screaming_snake_case` / `to_camel_case` / `to_pascal_case`
+//! - `common_prefix` / `common_suffix` / `strip_common_prefix` (shortening paths, grouping keys)
+//! - `diff` / `render_diff` / `DiffSpan` (character-level changes)
+//! - `escape_html` / `unescape_html` / `escape_json_string` / `unescape_json_string` / `shell_quote`
+//! - `fuzzy_match` / `Score` (fzf-style subsequence matching with match positions)
+//! - `humanize_number` / `humanize_bytes` (`1.2M`, `1.5 GiB`, with `_with` variants for precision)
+//! - `indent` / `dedent` (like Python's `textwrap`, for embedded SQL and generated code)
+//! - `initials` / `acronym` (`AL` for avatars, `HTTP` for labels)
+//! - `mask_middle` / `mask_all_but_last` / `mask_email` (identifying hints for logs)
+//! - `nfc` / `nfd` / `compare_normalized` (Unicode normalization, `unicode` feature)
+//! - `normalize_newlines` / `to_crlf` / `lines_any` (any of `\n`, `\r\n` and `\r`)
+//! - `pad_left` / `pad_right` / `center` (by display width, with `_with` variants for a fill character)
+//! - `pluralize` / `pluralize_with` / `count_of` ("1 item", "3 items")
+//! - `random` / `Charset` (tokens from a CSPRNG, `rand` feature)
+//! - `levenshtein` / `similarity` (Jaro-Winkler) / `closest_match` ("did you mean" suggestions) / `dedupe_similar`
+//! - `split_quoted` / `SplitError` (POSIX shell-style argument splitting)
+//! - `slugify` / `slugify_with` / `SlugOptions`
+//! - `interpolate` / `interpolate_with` / `interpolate_env` (`{name}` templates; `interpolate_env` needs the `env` feature)
+//! - `title_case` / `title_case_with` / `SMALL_WORDS` (headline capitalization)
+//! - `truncate` (grapheme-aware, with an ellipsis) / `truncate_bytes` / `truncate_to_bytes` (on a char boundary, with an optional marker)
+//! - `unaccent` / `to_ascii_lossy` (`Łódź` → `Lodz`; `to_ascii_lossy` covers every script and needs the `transliterate` feature)
+//! - `wrap` / `fill` / `WrapOptions` (word wrapping by display width, with indents)
+//!
+//! Example:
+//! ```
+//! use common_utils_rs::strings::*;
+//! assert_eq!(truncate("Grüße aus Köln", 8, "…"), "Grüße a…");
+//! ```
+pub mod affix;
+pub mod ansi;
+mod ascii;
+pub mod case;
+pub mod diff;
+pub mod escape;
+pub mod fuzzy;
+pub mod humanize;
+pub mod indent;
+pub mod initials;
+pub mod mask;
+pub mod newline;
+#[cfg(feature = "unicode")]
+pub mod normalize;
+pub mod pad;
+pub mod plural;
+#[cfg(feature = "rand")]
+pub mod random;
+pub mod shlex;
+pub mod similarity;
+pub mod slug;
+pub mod template;
+pub mod title;
+pub mod truncate;
+pub mod unaccent;
+pub mod wrap;
+
+pub use affix::*;
+pub use ansi::*;
+pub use case::*;
+pub use diff::*;
+pub use escape::*;
+pub use fuzzy::*;
+pub use humanize::*;
+pub use indent::*;
+pub use initials::*;
+pub use mask::*;
+pub use newline::*;
+#[cfg(feature = "unicode")]
+pub use normalize::*;
+pub use pad::*;
+pub use plural::*;
+#[cfg(feature = "rand")]
+pub use random::*;
+pub use shlex::*;
+pub use similarity::*;
+pub use slug::*;
+pub use template::*;
+pub use title::*;
+pub use truncate::*;
+pub use unaccent::*;
+pub use wrap::*;
@@ -0,0 +1,115 @@
+//! Line-ending normalization, so text hashes and diffs the same on every
+//! platform.
+
+/// `s` with every `\r\n` and lone `\r` replaced by `\n`.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::normalize_newlines;
+/// assert_eq!(normalize_newlines("a\r\nb\rc\n"), "a\nb\nc\n");
+/// ```
+pub fn normalize_newlines(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for (index, line) in lines_any(s).enumerate() {
+        if index > 0 {
+            out.push('\n');
+        }
+        out.push_str(line);
+    }
+    if s.ends_with(['\r', '\n']) {
+        out.push('\n');
+    }
+    out
+}
+
+/// `s` with every line ending, whatever it was, written as `\r\n`.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::to_crlf;
+/// assert_eq!(to_crlf("a\nb\r\nc\r"), "a\r\nb\r\nc\r\n");
+/// ```
+pub fn to_crlf(s: &str) -> String {
+    normalize_newlines(s).replace('\n', "\r\n")
+}
+
+/// The lines of `s`, split at `\n`, `\r\n` or a lone `\r`, without their
+/// line endings.
+///
+/// Like [`str::lines`], a final line ending doesn't start an extra empty
+/// line.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::lines_any;
+/// let lines: Vec<&str> = lines_any("one\r\ntwo\rthree\n").collect();
+/// assert_eq!(lines, ["one", "two", "three"]);
+/// ```
+pub fn lines_any(s: &str) -> LinesAny<'_> {
+    LinesAny { rest: s }
+}
+
+/// An iterator over the lines of a string with any line endings, created by
+/// [`lines_any`].
+#[derive(Debug, Clone)]
+pub struct LinesAny<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Iterator for LinesAny<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        let Some(end) = self.rest.find(['\r', '\n']) else {
+            return Some(std::mem::take(&mut self.rest));
+        };
+        let line = &self.rest[..end];
+        let ending = if self.rest[end..].starts_with("\r\n") {
+            2
+        } else {
+            1
+        };
+        self.rest = &self.rest[end + ending..];
+        Some(line)
+    }
+}
+
+impl std::iter::FusedIterator for LinesAny<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines_any() {
+        let lines = |s| lines_any(s).collect::<Vec<_>>();
+        assert!(lines("").is_empty());
+        assert_eq!(lines("\n"), [""]);
+        assert_eq!(lines("a"), ["a"]);
+        assert_eq!(lines("a\n\nb"), ["a", "", "b"]);
+        assert_eq!(lines("a\r\rb\r\n"), ["a", "", "b"]);
+        assert_eq!(lines("a\n\rb"), ["a", "", "b"]);
+    }
+
+    #[test]
+    fn test_normalize_newlines() {
+        assert_eq!(normalize_newlines(""), "");
+        assert_eq!(normalize_newlines("no newline"), "no newline");
+        assert_eq!(normalize_newlines("\r\n\r\n"), "\n\n");
+        assert_eq!(normalize_newlines("a\r\r\nb"), "a\n\nb");
+        for s in ["x\r\ny\rz\n", "\r", "mixed\n\r\n\r"] {
+            let normalized = normalize_newlines(s);
+            assert_eq!(normalize_newlines(&to_crlf(s)), normalized);
+            assert_eq!(to_crlf(&normalized), to_crlf(s));
+        }
+    }
+
+    #[test]
+    fn test_to_crlf() {
+        assert_eq!(to_crlf("a\r\nb"), "a\r\nb");
+        assert_eq!(to_crlf("\n\n"), "\r\n\r\n");
+    }
+}
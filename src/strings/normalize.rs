@@ -0,0 +1,75 @@
+//! Unicode normalization (`unicode` feature), so text typed or stored in
+//! composed and decomposed forms compares equal.
+//!
+//! `é` can be one code point (U+00E9) or `e` followed by a combining accent
+//! (U+0065 U+0301); macOS filenames and some keyboards produce the second.
+use std::cmp::Ordering;
+
+use unicode_normalization::UnicodeNormalization;
+
+/// `s` in Normalization Form C (composed), the usual form for storage and
+/// comparison.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::nfc;
+/// assert_eq!(nfc("Cafe\u{301}"), "Caf\u{e9}");
+/// ```
+pub fn nfc(s: &str) -> String {
+    s.nfc().collect()
+}
+
+/// `s` in Normalization Form D (decomposed), with accents as separate
+/// combining marks.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::nfd;
+/// assert_eq!(nfd("Caf\u{e9}"), "Cafe\u{301}");
+/// ```
+pub fn nfd(s: &str) -> String {
+    s.nfd().collect()
+}
+
+/// Compare `a` and `b` by their NFC forms, without allocating, so
+/// `Ordering::Equal` means they are the same text however it was encoded.
+///
+/// Case is significant; lowercase both sides first for case-insensitive
+/// usernames.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::compare_normalized;
+/// assert!(compare_normalized("Zoë.txt", "Zoe\u{308}.txt").is_eq());
+/// assert!(compare_normalized("Zoë", "Zoe").is_gt());
+/// ```
+pub fn compare_normalized(a: &str, b: &str) -> Ordering {
+    a.nfc().cmp(b.nfc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nfc_nfd_round_trip() {
+        let composed = "Ångström ñ ệ";
+        let decomposed = nfd(composed);
+        assert_ne!(decomposed, composed);
+        assert!(decomposed.chars().count() > composed.chars().count());
+        assert_eq!(nfc(&decomposed), composed);
+        assert_eq!(nfd(&decomposed), decomposed);
+        assert_eq!(nfc("ascii only"), "ascii only");
+        // The Angstrom sign is canonically equivalent to `Å`.
+        assert_eq!(nfc("\u{212b}"), "\u{c5}");
+    }
+
+    #[test]
+    fn test_compare_normalized() {
+        assert_eq!(compare_normalized("", ""), Ordering::Equal);
+        assert_eq!(compare_normalized("e\u{301}", "\u{e9}"), Ordering::Equal);
+        assert_eq!(compare_normalized("a", "b"), Ordering::Less);
+        assert_eq!(compare_normalized("\u{e9}", "e"), Ordering::Greater);
+        assert_ne!(compare_normalized("É", "é"), Ordering::Equal);
+    }
+}
@@ -0,0 +1,114 @@
+//! Padding to a display width, for aligned CLI tables.
+//!
+//! Widths are measured with [`display_width`], so ANSI colors take no room
+//! and wide characters take two columns. Strings already at least `width`
+//! columns wide are returned unchanged.
+use super::ansi::display_width;
+
+/// `s` with spaces added on the left up to `width` columns, i.e. aligned
+/// right.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::pad_left;
+/// assert_eq!(pad_left("42", 5), "   42");
+/// assert_eq!(pad_left("日本", 5), " 日本");
+/// ```
+pub fn pad_left(s: &str, width: usize) -> String {
+    pad_left_with(s, width, ' ')
+}
+
+/// Like [`pad_left`], padding with `fill`.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::pad_left_with;
+/// assert_eq!(pad_left_with("7", 3, '0'), "007");
+/// ```
+pub fn pad_left_with(s: &str, width: usize, fill: char) -> String {
+    let gap = width.saturating_sub(display_width(s));
+    format!("{}{}", padding(gap, fill), s)
+}
+
+/// `s` with spaces added on the right up to `width` columns, i.e. aligned
+/// left.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::pad_right;
+/// assert_eq!(pad_right("\x1b[1mname\x1b[0m", 6), "\x1b[1mname\x1b[0m  ");
+/// ```
+pub fn pad_right(s: &str, width: usize) -> String {
+    pad_right_with(s, width, ' ')
+}
+
+/// Like [`pad_right`], padding with `fill`.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::pad_right_with;
+/// assert_eq!(pad_right_with("Total ", 12, '.'), "Total ......");
+/// ```
+pub fn pad_right_with(s: &str, width: usize, fill: char) -> String {
+    let gap = width.saturating_sub(display_width(s));
+    format!("{}{}", s, padding(gap, fill))
+}
+
+/// `s` centered in `width` columns with spaces; an odd column goes on the
+/// right.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::center;
+/// assert_eq!(center("ok", 7), "  ok   ");
+/// ```
+pub fn center(s: &str, width: usize) -> String {
+    center_with(s, width, ' ')
+}
+
+/// Like [`center`], padding with `fill`.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::center_with;
+/// assert_eq!(center_with(" Summary ", 21, '='), "====== Summary ======");
+/// ```
+pub fn center_with(s: &str, width: usize, fill: char) -> String {
+    let gap = width.saturating_sub(display_width(s));
+    let left = gap / 2;
+    format!("{}{}{}", padding(left, fill), s, padding(gap - left, fill))
+}
+
+/// `columns` columns of `fill`, topped up with spaces when a wide `fill`
+/// doesn't divide them evenly.
+fn padding(columns: usize, fill: char) -> String {
+    let fill_width = display_width(fill.encode_utf8(&mut [0; 4])).max(1);
+    let mut out = fill.to_string().repeat(columns / fill_width);
+    out.push_str(&" ".repeat(columns % fill_width));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pad() {
+        assert_eq!(pad_left("abc", 2), "abc");
+        assert_eq!(pad_right("abc", 3), "abc");
+        assert_eq!(center("abc", 0), "abc");
+        assert_eq!(pad_right("", 3), "   ");
+        assert_eq!(pad_right("日本", 5), "日本 ");
+        assert_eq!(center("🦀", 5), " 🦀  ");
+        assert_eq!(pad_left("\x1b[31m1\x1b[0m", 3), "  \x1b[31m1\x1b[0m");
+    }
+
+    #[test]
+    fn test_pad_with_fill() {
+        assert_eq!(pad_left_with("-5", 4, '0'), "00-5");
+        assert_eq!(center_with("x", 4, '*'), "*x**");
+        // A two-column fill can't fill an odd gap exactly.
+        assert_eq!(pad_right_with("a", 4, '＊'), "a＊ ");
+        assert_eq!(display_width(&pad_left_with("a", 6, '＊')), 6);
+    }
+}
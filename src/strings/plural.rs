@@ -0,0 +1,102 @@
+//! English plurals for counts in messages ("1 item", "3 items").
+
+/// `word` as it reads after `count`: unchanged for 1, otherwise its regular
+/// English plural.
+///
+/// Words ending in `s`, `x`, `z`, `ch` or `sh` take `es`, a consonant
+/// followed by `y` becomes `ies`, and everything else takes `s`. An
+/// all-uppercase word gets an uppercase suffix. Use [`pluralize_with`] for
+/// irregular words.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::pluralize;
+/// assert_eq!(pluralize(1, "retry"), "retry");
+/// assert_eq!(pluralize(2, "retry"), "retries");
+/// assert_eq!(pluralize(0, "match"), "matches");
+/// ```
+pub fn pluralize(count: usize, word: &str) -> String {
+    if count == 1 {
+        return word.to_string();
+    }
+    let lower = word.to_lowercase();
+    let consonant_y = lower
+        .strip_suffix('y')
+        .is_some_and(|stem| !stem.is_empty() && !stem.ends_with(['a', 'e', 'i', 'o', 'u']));
+    let (stem, suffix) =
+        if lower.ends_with(['s', 'x', 'z']) || lower.ends_with("ch") || lower.ends_with("sh") {
+            (word, "es")
+        } else if consonant_y {
+            (&word[..word.len() - 1], "ies")
+        } else {
+            (word, "s")
+        };
+    let shouting = word.chars().any(char::is_alphabetic) && !word.chars().any(char::is_lowercase);
+    if shouting {
+        format!("{}{}", stem, suffix.to_uppercase())
+    } else {
+        format!("{}{}", stem, suffix)
+    }
+}
+
+/// `singular` when `count` is 1, otherwise `plural`.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::pluralize_with;
+/// assert_eq!(pluralize_with(3, "child", "children"), "children");
+/// ```
+pub fn pluralize_with<'a>(count: usize, singular: &'a str, plural: &'a str) -> &'a str {
+    if count == 1 { singular } else { plural }
+}
+
+/// `count` followed by `word` in the matching form, as with [`pluralize`].
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::count_of;
+/// assert_eq!(count_of(1, "file"), "1 file");
+/// assert_eq!(count_of(3, "file"), "3 files");
+/// ```
+pub fn count_of(count: usize, word: &str) -> String {
+    format!("{} {}", count, pluralize(count, word))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pluralize() {
+        let cases = [
+            ("item", "items"),
+            ("bus", "buses"),
+            ("box", "boxes"),
+            ("waltz", "waltzes"),
+            ("branch", "branches"),
+            ("crash", "crashes"),
+            ("entry", "entries"),
+            ("key", "keys"),
+            ("day", "days"),
+            ("y", "ys"),
+            ("FILE", "FILES"),
+            ("ENTRY", "ENTRIES"),
+            ("Match", "Matches"),
+        ];
+        for (singular, plural) in cases {
+            assert_eq!(pluralize(2, singular), plural);
+            assert_eq!(pluralize(1, singular), singular);
+        }
+        assert_eq!(pluralize(0, "item"), "items");
+    }
+
+    #[test]
+    fn test_count_of() {
+        assert_eq!(count_of(0, "error"), "0 errors");
+        assert_eq!(count_of(1, "error"), "1 error");
+        assert_eq!(count_of(1, "query"), "1 query");
+        assert_eq!(count_of(12, "query"), "12 queries");
+        assert_eq!(pluralize_with(1, "person", "people"), "person");
+        assert_eq!(pluralize_with(0, "person", "people"), "people");
+    }
+}
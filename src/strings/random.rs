@@ -0,0 +1,101 @@
+//! Random strings from a CSPRNG (`rand` feature).
+use rand::RngExt;
+
+/// The characters [`random`] picks from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Charset {
+    /// `A-Z`, `a-z` and `0-9`: the base62 alphabet.
+    Alphanumeric,
+    /// `0-9` and `a-f`.
+    Hex,
+    /// The base64url alphabet (`A-Z`, `a-z`, `0-9`, `-` and `_`), safe in
+    /// URLs and filenames without escaping.
+    UrlSafe,
+    /// `0-9`, e.g. for one-time codes.
+    Digits,
+    /// Any set of ASCII characters. Repeated characters are picked more
+    /// often.
+    Custom(&'static str),
+}
+
+impl Charset {
+    /// The characters of this set.
+    pub fn chars(&self) -> &'static str {
+        match self {
+            Charset::Alphanumeric => {
+                "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789"
+            }
+            Charset::Hex => "0123456789abcdef",
+            Charset::UrlSafe => "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_",
+            Charset::Digits => "0123456789",
+            Charset::Custom(chars) => chars,
+        }
+    }
+}
+
+/// A string of `len` characters picked uniformly from `charset` by the
+/// thread-local CSPRNG, so it is suitable for tokens and secrets.
+///
+/// # Panics
+///
+/// Panics if a [`Charset::Custom`] set is empty or not ASCII.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::{random, Charset};
+/// let token = random(32, Charset::Alphanumeric);
+/// assert_eq!(token.len(), 32);
+/// assert!(token.chars().all(|c| c.is_ascii_alphanumeric()));
+/// ```
+pub fn random(len: usize, charset: Charset) -> String {
+    let chars = charset.chars().as_bytes();
+    assert!(
+        !chars.is_empty() && chars.is_ascii(),
+        "random strings need a non-empty ASCII charset"
+    );
+    let mut rng = rand::rng();
+    (0..len)
+        .map(|_| char::from(chars[rng.random_range(0..chars.len())]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_uses_charset() {
+        for charset in [
+            Charset::Alphanumeric,
+            Charset::Hex,
+            Charset::UrlSafe,
+            Charset::Digits,
+            Charset::Custom("ab"),
+        ] {
+            let value = random(64, charset);
+            assert_eq!(value.len(), 64);
+            assert!(
+                value.chars().all(|c| charset.chars().contains(c)),
+                "{}",
+                value
+            );
+        }
+        assert_eq!(random(0, Charset::Hex), "");
+    }
+
+    #[test]
+    fn test_random_covers_charset() {
+        let value = random(2000, Charset::Hex);
+        assert!(Charset::Hex.chars().chars().all(|c| value.contains(c)));
+        assert_ne!(
+            random(32, Charset::Alphanumeric),
+            random(32, Charset::Alphanumeric)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "non-empty ASCII charset")]
+    fn test_random_rejects_empty_charset() {
+        random(1, Charset::Custom(""));
+    }
+}
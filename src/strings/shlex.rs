@@ -0,0 +1,141 @@
+//! Splitting command-line-like text into arguments, following POSIX shell
+//! quoting.
+use thiserror::Error;
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum SplitError {
+    #[error("Unterminated {quote} quote starting at byte {offset}")]
+    UnterminatedQuote { quote: char, offset: usize },
+
+    #[error("Unexpected end of input after a backslash")]
+    TrailingBackslash,
+}
+
+/// Split `s` into arguments the way a POSIX shell would, without expanding
+/// variables or globs, e.g. for `EXTRA_ARGS="--name 'two words'"`.
+///
+/// Arguments are separated by whitespace. Inside single quotes every
+/// character is literal; inside double quotes a backslash escapes only `"`,
+/// `\`, `$`, `` ` `` and a newline; outside quotes it escapes any character,
+/// and a backslash before a newline joins the lines. Quoted and unquoted
+/// parts next to each other form one argument, and `""` is an empty one.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::split_quoted;
+/// assert_eq!(
+///     split_quoted(r#"run --opt "two words" 'it''s' a\ b"#).unwrap(),
+///     ["run", "--opt", "two words", "its", "a b"]
+/// );
+/// assert!(split_quoted("echo 'oops").is_err());
+/// ```
+pub fn split_quoted(s: &str) -> Result<Vec<String>, SplitError> {
+    let mut args = Vec::new();
+    let mut current: Option<String> = None;
+    let mut chars = s.char_indices();
+
+    while let Some((offset, c)) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                args.extend(current.take());
+            }
+            '\\' => match chars.next() {
+                Some((_, '\n')) => {}
+                Some((_, escaped)) => current.get_or_insert_default().push(escaped),
+                None => return Err(SplitError::TrailingBackslash),
+            },
+            '\'' => {
+                let arg = current.get_or_insert_default();
+                loop {
+                    match chars.next() {
+                        Some((_, '\'')) => break,
+                        Some((_, c)) => arg.push(c),
+                        None => {
+                            return Err(SplitError::UnterminatedQuote {
+                                quote: '\'',
+                                offset,
+                            });
+                        }
+                    }
+                }
+            }
+            '"' => {
+                let arg = current.get_or_insert_default();
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, '\n')) => {}
+                            Some((_, c @ ('"' | '\\' | '$' | '`'))) => arg.push(c),
+                            Some((_, c)) => {
+                                arg.push('\\');
+                                arg.push(c);
+                            }
+                            None => {
+                                return Err(SplitError::UnterminatedQuote { quote: '"', offset });
+                            }
+                        },
+                        Some((_, c)) => arg.push(c),
+                        None => return Err(SplitError::UnterminatedQuote { quote: '"', offset }),
+                    }
+                }
+            }
+            c => current.get_or_insert_default().push(c),
+        }
+    }
+    args.extend(current);
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_quoted() {
+        assert_eq!(split_quoted("  a  b\tc\n").unwrap(), ["a", "b", "c"]);
+        assert!(split_quoted("   ").unwrap().is_empty());
+        assert_eq!(split_quoted(r#"a "" ''"#).unwrap(), ["a", "", ""]);
+        assert_eq!(split_quoted(r#"--name="Ada L""#).unwrap(), ["--name=Ada L"]);
+        assert_eq!(split_quoted(r#"'a\b' "a\b""#).unwrap(), [r"a\b", r"a\b"]);
+        assert_eq!(
+            split_quoted(r#""say \"hi\" \$HOME""#).unwrap(),
+            [r#"say "hi" $HOME"#]
+        );
+        assert_eq!(
+            split_quoted("one\\\ntwo three").unwrap(),
+            ["onetwo", "three"]
+        );
+        assert_eq!(split_quoted(r#"\'\""#).unwrap(), [r#"'""#]);
+        assert_eq!(
+            split_quoted("naïve 'ünïcode'").unwrap(),
+            ["naïve", "ünïcode"]
+        );
+    }
+
+    #[test]
+    fn test_split_quoted_errors() {
+        assert_eq!(
+            split_quoted("run 'oops").unwrap_err(),
+            SplitError::UnterminatedQuote {
+                quote: '\'',
+                offset: 4
+            }
+        );
+        assert_eq!(
+            split_quoted(r#"a "b\"#).unwrap_err(),
+            SplitError::UnterminatedQuote {
+                quote: '"',
+                offset: 2
+            }
+        );
+        assert_eq!(
+            split_quoted("a\\").unwrap_err(),
+            SplitError::TrailingBackslash
+        );
+        assert_eq!(
+            split_quoted("echo \"unclosed").unwrap_err().to_string(),
+            "Unterminated \" quote starting at byte 5"
+        );
+    }
+}
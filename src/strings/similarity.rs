@@ -0,0 +1,216 @@
+//! Edit distance and similarity scores, e.g. for "did you mean" suggestions.
+//!
+//! All functions compare `char`s, so non-ASCII text is measured per
+//! character rather than per byte, and comparisons are case-sensitive
+//! except in [`dedupe_similar`].
+use super::unaccent::unaccent;
+
+/// The number of single-character insertions, deletions and substitutions
+/// that turn `a` into `b`.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::levenshtein;
+/// assert_eq!(levenshtein("kitten", "sitting"), 3);
+/// ```
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = Vec::with_capacity(b.len() + 1);
+        current.push(i + 1);
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// The Jaro-Winkler similarity of `a` and `b`, from 0.0 (nothing in common)
+/// to 1.0 (equal).
+///
+/// Strings sharing a prefix score higher, which suits identifiers and
+/// command names, where typos tend to be near the end.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::similarity;
+/// assert!(similarity("DATABASE_URL", "DATABSE_URL") > 0.95);
+/// assert!(similarity("DATABASE_URL", "PORT") < 0.5);
+/// ```
+pub fn similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let jaro = jaro(&a, &b);
+    let prefix = a.iter().zip(&b).take(4).take_while(|(x, y)| x == y).count();
+    jaro + prefix as f64 * 0.1 * (1.0 - jaro)
+}
+
+/// The candidate most similar to `input`, if any is similar enough
+/// (a [`similarity`] of at least 0.8) to be a plausible typo.
+///
+/// Ties go to the earlier candidate.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::closest_match;
+/// let commands = ["build", "bench", "check", "clean"];
+/// assert_eq!(closest_match("biuld", commands), Some("build"));
+/// assert_eq!(closest_match("deploy", commands), None);
+/// ```
+pub fn closest_match<'a>(
+    input: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let mut best: Option<(f64, &'a str)> = None;
+    for candidate in candidates {
+        let score = similarity(input, candidate);
+        if score >= 0.8 && best.is_none_or(|(best_score, _)| score > best_score) {
+            best = Some((score, candidate));
+        }
+    }
+    best.map(|(_, candidate)| candidate)
+}
+
+/// `items` without near-duplicates, keeping the first of each group and the
+/// original order, e.g. to tidy user-supplied tags.
+///
+/// Items are compared by [`similarity`] after normalizing case, accents,
+/// punctuation and whitespace, so `Rust-Lang` and `rust lang` are always
+/// duplicates. An item is dropped if it scores at least `threshold`
+/// against an item already kept; 0.9 catches most typos.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::dedupe_similar;
+/// let tags = ["Kubernetes", "postgres", "kubernetes", "Postgres ", "kubernets", "redis"];
+/// assert_eq!(dedupe_similar(tags, 0.9), ["Kubernetes", "postgres", "redis"]);
+/// ```
+pub fn dedupe_similar<'a>(
+    items: impl IntoIterator<Item = &'a str>,
+    threshold: f64,
+) -> Vec<&'a str> {
+    let mut kept: Vec<(&'a str, String)> = Vec::new();
+    for item in items {
+        let key = normalize(item);
+        if !kept
+            .iter()
+            .any(|(_, other)| similarity(&key, other) >= threshold)
+        {
+            kept.push((item, key));
+        }
+    }
+    kept.into_iter().map(|(item, _)| item).collect()
+}
+
+/// Lowercase, unaccented words separated by single spaces.
+fn normalize(s: &str) -> String {
+    unaccent(s)
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn jaro(a: &[char], b: &[char]) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let window = (a.len().max(b.len()) / 2).saturating_sub(1);
+    let mut b_matched = vec![false; b.len()];
+    let mut a_matches = Vec::new();
+    for (i, ca) in a.iter().enumerate() {
+        let start = i.saturating_sub(window);
+        let end = (i + window + 1).min(b.len());
+        for j in start..end {
+            if !b_matched[j] && b[j] == *ca {
+                b_matched[j] = true;
+                a_matches.push(*ca);
+                break;
+            }
+        }
+    }
+    if a_matches.is_empty() {
+        return 0.0;
+    }
+
+    let b_matches = b.iter().zip(&b_matched).filter(|(_, matched)| **matched);
+    let transpositions = a_matches
+        .iter()
+        .zip(b_matches)
+        .filter(|(x, (y, _))| x != y)
+        .count();
+    let m = a_matches.len() as f64;
+    (m / a.len() as f64 + m / b.len() as f64 + (m - transpositions as f64 / 2.0) / m) / 3.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("", ""), 0);
+        assert_eq!(levenshtein("abc", ""), 3);
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert_eq!(levenshtein("flaw", "lawn"), 2);
+        assert_eq!(levenshtein("TIMEOUTT", "TIMEOUT"), 1);
+        assert_eq!(levenshtein("café", "cafe"), 1);
+    }
+
+    #[test]
+    fn test_similarity() {
+        let close = |a, b, expected: f64| {
+            let score = similarity(a, b);
+            assert!((score - expected).abs() < 1e-3, "{} vs {}: {}", a, b, score);
+        };
+        close("MARTHA", "MARHTA", 0.961);
+        close("DWAYNE", "DUANE", 0.84);
+        close("DIXON", "DICKSONX", 0.813);
+        close("same", "same", 1.0);
+        close("", "", 1.0);
+        close("abc", "", 0.0);
+        close("abc", "xyz", 0.0);
+    }
+
+    #[test]
+    fn test_closest_match() {
+        let keys: Vec<String> = ["DATABASE_URL", "DATABASE_POOL"].map(String::from).to_vec();
+        assert_eq!(
+            closest_match("DATABASE_ULR", keys.iter().map(String::as_str)),
+            Some("DATABASE_URL")
+        );
+        assert_eq!(closest_match("anything", []), None);
+        assert_eq!(closest_match("check", ["check", "check"]), Some("check"));
+    }
+
+    #[test]
+    fn test_dedupe_similar() {
+        assert_eq!(
+            dedupe_similar(
+                ["Rust-Lang", "rust lang", "RUST_LANG!", "Crème", "creme"],
+                1.0
+            ),
+            ["Rust-Lang", "Crème"]
+        );
+        assert_eq!(
+            dedupe_similar(["error", "errors", "warning"], 0.95),
+            ["error", "warning"]
+        );
+        assert_eq!(
+            dedupe_similar(["error", "errors"], 1.0),
+            ["error", "errors"]
+        );
+        assert_eq!(dedupe_similar(["a", "b", "a"], 0.0), ["a"]);
+        assert!(dedupe_similar([], 0.9).is_empty());
+        // Symbols-only items normalize to the same empty key.
+        assert_eq!(dedupe_similar(["--", "??"], 1.0), ["--"]);
+    }
+}
@@ -0,0 +1,134 @@
+//! URL-safe slugs from free-form titles.
+use super::ascii::fold;
+
+/// How [`slugify_with`] builds a slug.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::{slugify_with, SlugOptions};
+/// let options = SlugOptions { separator: "_", max_len: Some(12) };
+/// assert_eq!(slugify_with("Quarterly Report: Q3 2024", &options), "quarterly_re");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlugOptions<'a> {
+    /// Placed between words.
+    pub separator: &'a str,
+    /// Longest slug in bytes. Words are ASCII, but a non-ASCII separator
+    /// takes several bytes; a slug is only cut at a character boundary and
+    /// never ends with all or part of the separator.
+    pub max_len: Option<usize>,
+}
+
+impl Default for SlugOptions<'_> {
+    fn default() -> Self {
+        Self {
+            separator: "-",
+            max_len: None,
+        }
+    }
+}
+
+/// A lowercase, hyphen-separated slug of `s`, e.g. for IDs and filenames.
+///
+/// Words are ASCII: accented Latin letters lose their accents (`é`
+/// becomes `e`, `ß` becomes `ss`). Every run of other characters, including
+/// letters outside the Latin alphabet, becomes a single separator.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::slugify;
+/// assert_eq!(slugify("Crème Brûlée: A How-To!"), "creme-brulee-a-how-to");
+/// ```
+pub fn slugify(s: &str) -> String {
+    slugify_with(s, &SlugOptions::default())
+}
+
+/// Like [`slugify`], with a custom separator and length limit.
+pub fn slugify_with(s: &str, options: &SlugOptions) -> String {
+    let mut slug = String::new();
+    // Where each separator starts, to drop a separator cut by `max_len`.
+    let mut separators = Vec::new();
+    let mut pending_separator = false;
+    for c in s.chars() {
+        let mut push = |text: &str| {
+            if pending_separator && !slug.is_empty() {
+                separators.push(slug.len());
+                slug.push_str(options.separator);
+            }
+            pending_separator = false;
+            slug.push_str(&text.to_ascii_lowercase());
+        };
+        if c.is_ascii_alphanumeric() {
+            push(c.encode_utf8(&mut [0; 4]));
+        } else if let Some(folded) = fold(c) {
+            push(folded);
+        } else {
+            pending_separator = true;
+        }
+    }
+
+    if let Some(max_len) = options.max_len.filter(|max_len| slug.len() > *max_len) {
+        let mut cut = max_len;
+        while !slug.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        let separator_len = options.separator.len();
+        if let Some(start) = separators
+            .iter()
+            .find(|start| **start < cut && cut <= **start + separator_len)
+        {
+            cut = *start;
+        }
+        slug.truncate(cut);
+    }
+    slug
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Hello, World!"), "hello-world");
+        assert_eq!(slugify("  --Already-a-slug--  "), "already-a-slug");
+        assert_eq!(slugify("Łódź & Kraków 2024"), "lodz-krakow-2024");
+        assert_eq!(slugify("Straße"), "strasse");
+        // Decomposed accents are dropped too.
+        assert_eq!(slugify("Cafe\u{301}"), "cafe");
+        assert_eq!(slugify("日本語 title"), "title");
+        assert_eq!(slugify("!!!"), "");
+    }
+
+    #[test]
+    fn test_slugify_with() {
+        let options = SlugOptions {
+            separator: "_",
+            max_len: Some(10),
+        };
+        assert_eq!(slugify_with("Hello big World", &options), "hello_big");
+        assert_eq!(slugify_with("Hi there", &options), "hi_there");
+        let empty = SlugOptions {
+            separator: "",
+            max_len: None,
+        };
+        assert_eq!(slugify_with("Hello World", &empty), "helloworld");
+    }
+
+    #[test]
+    fn test_slugify_with_non_ascii_separator() {
+        let options = |max_len| SlugOptions {
+            separator: "·",
+            max_len: Some(max_len),
+        };
+        assert_eq!(slugify_with("ab cd", &options(3)), "ab");
+        assert_eq!(slugify_with("ab cd", &options(4)), "ab");
+        assert_eq!(slugify_with("ab cd", &options(5)), "ab·c");
+        assert_eq!(slugify_with("ab cd", &options(6)), "ab·cd");
+        let dashes = SlugOptions {
+            separator: "--",
+            max_len: Some(3),
+        };
+        assert_eq!(slugify_with("ab cd", &dashes), "ab");
+    }
+}
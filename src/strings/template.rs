@@ -0,0 +1,219 @@
+//! `{name}` placeholders filled from a map, a closure or the environment.
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+
+use thiserror::Error;
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    #[error("No value for template placeholder `{{{0}}}`")]
+    Missing(String),
+
+    #[error("Invalid template at byte {offset}: {message}")]
+    Syntax { offset: usize, message: String },
+}
+
+/// What to do with a placeholder that has no value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MissingPolicy {
+    /// Fail with [`TemplateError::Missing`].
+    #[default]
+    Error,
+    /// Substitute an empty string.
+    Empty,
+    /// Leave the placeholder untouched, e.g. `{name}`.
+    Keep,
+}
+
+/// Replace each `{name}` in `template` with its value in `values`.
+///
+/// `{{` and `}}` produce literal braces, and whitespace inside a placeholder
+/// is ignored (`{ name }`). Placeholders without a value are an error; see
+/// [`interpolate_with`] to change that.
+///
+/// Example:
+/// ```
+/// use std::collections::HashMap;
+/// use common_utils_rs::strings::interpolate;
+/// let values = HashMap::from([("name", "Ada"), ("count", "3")]);
+/// assert_eq!(
+///     interpolate("Hello {name}, you have {count} new {{messages}}", &values).unwrap(),
+///     "Hello Ada, you have 3 new {messages}"
+/// );
+/// ```
+pub fn interpolate<K, V, S>(
+    template: &str,
+    values: &HashMap<K, V, S>,
+) -> Result<String, TemplateError>
+where
+    K: Borrow<str> + Hash + Eq,
+    V: AsRef<str>,
+    S: BuildHasher,
+{
+    interpolate_with(template, MissingPolicy::Error, |name| {
+        values.get(name).map(|value| value.as_ref().to_string())
+    })
+}
+
+/// Replace each `{name}` in `template` with `lookup(name)`, handling
+/// placeholders without a value according to `missing`.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::{interpolate_with, MissingPolicy};
+/// let lookup = |name: &str| (name == "user").then(|| "ada".to_string());
+/// let out = interpolate_with("{user} in {team}", MissingPolicy::Keep, lookup).unwrap();
+/// assert_eq!(out, "ada in {team}");
+/// ```
+pub fn interpolate_with(
+    template: &str,
+    missing: MissingPolicy,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<String, TemplateError> {
+    let syntax = |offset: usize, message: &str| TemplateError::Syntax {
+        offset,
+        message: message.to_string(),
+    };
+
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(pos) = rest.find(['{', '}']) {
+        let offset = template.len() - rest.len() + pos;
+        out.push_str(&rest[..pos]);
+        let brace = &rest[pos..pos + 1];
+        let after = &rest[pos + 1..];
+        if let Some(after_escape) = after.strip_prefix(brace) {
+            out.push_str(brace);
+            rest = after_escape;
+            continue;
+        }
+        if brace == "}" {
+            return Err(syntax(
+                offset,
+                "unmatched `}` (use `}}` for a literal brace)",
+            ));
+        }
+
+        let end = after
+            .find(['{', '}'])
+            .filter(|end| after[*end..].starts_with('}'))
+            .ok_or_else(|| syntax(offset, "unclosed `{` (use `{{` for a literal brace)"))?;
+        let name = after[..end].trim();
+        if name.is_empty() {
+            return Err(syntax(offset, "empty placeholder `{}`"));
+        }
+        match (lookup(name), missing) {
+            (Some(value), _) => out.push_str(&value),
+            (None, MissingPolicy::Error) => return Err(TemplateError::Missing(name.to_string())),
+            (None, MissingPolicy::Empty) => {}
+            (None, MissingPolicy::Keep) => out.push_str(&rest[pos..pos + end + 2]),
+        }
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Replace each `{NAME}` in `template` with the environment variable `NAME`
+/// (`env` feature), seen through [`overrides`](crate::env::overrides) like
+/// every other getter.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::env::test::with_vars;
+/// use common_utils_rs::strings::interpolate_env;
+/// with_vars(&[("TEMPLATE_DOC_HOST", "db.internal")], || {
+///     let url = interpolate_env("postgres://{TEMPLATE_DOC_HOST}:5432/app").unwrap();
+///     assert_eq!(url, "postgres://db.internal:5432/app");
+/// });
+/// ```
+#[cfg(feature = "env")]
+pub fn interpolate_env(template: &str) -> Result<String, TemplateError> {
+    use crate::env::{EnvSource, ProcessEnv};
+
+    interpolate_with(template, MissingPolicy::Error, |name| ProcessEnv.get(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values() -> HashMap<String, String> {
+        HashMap::from([
+            ("name".to_string(), "Ada".to_string()),
+            ("lang".to_string(), "Rust".to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_interpolate() {
+        let values = values();
+        assert_eq!(
+            interpolate("{name} writes {lang}", &values).unwrap(),
+            "Ada writes Rust"
+        );
+        assert_eq!(interpolate("{ name }!", &values).unwrap(), "Ada!");
+        assert_eq!(
+            interpolate("{{name}} is {{{name}}}", &values).unwrap(),
+            "{name} is {Ada}"
+        );
+        assert_eq!(
+            interpolate("no placeholders", &values).unwrap(),
+            "no placeholders"
+        );
+        assert_eq!(interpolate("Größe: {name}", &values).unwrap(), "Größe: Ada");
+    }
+
+    #[test]
+    fn test_interpolate_missing_policies() {
+        let values = values();
+        let lookup = |name: &str| values.get(name).cloned();
+        assert_eq!(
+            interpolate("{name} in {team}", &values),
+            Err(TemplateError::Missing("team".to_string()))
+        );
+        assert_eq!(
+            interpolate_with("{name} in {team}", MissingPolicy::Empty, lookup).unwrap(),
+            "Ada in "
+        );
+        assert_eq!(
+            interpolate_with("{name} in { team }", MissingPolicy::Keep, lookup).unwrap(),
+            "Ada in { team }"
+        );
+    }
+
+    #[test]
+    fn test_interpolate_syntax_errors() {
+        let values = values();
+        let offset = |template| match interpolate(template, &values) {
+            Err(TemplateError::Syntax { offset, .. }) => offset,
+            other => panic!("unexpected result for {:?}: {:?}", template, other),
+        };
+        assert_eq!(offset("Hi {name"), 3);
+        assert_eq!(offset("Hi {na{me}"), 3);
+        assert_eq!(offset("Hi name}"), 7);
+        assert_eq!(offset("Hi {}"), 3);
+        assert_eq!(
+            interpolate("ü {", &values).unwrap_err().to_string(),
+            "Invalid template at byte 3: unclosed `{` (use `{{` for a literal brace)"
+        );
+    }
+
+    #[cfg(feature = "env")]
+    #[test]
+    fn test_interpolate_env() {
+        use crate::env::test::with_vars;
+
+        with_vars(&[("TEMPLATE_T_USER", "ada")], || {
+            assert_eq!(
+                interpolate_env("user={TEMPLATE_T_USER}").unwrap(),
+                "user=ada"
+            );
+            assert_eq!(
+                interpolate_env("{TEMPLATE_T_MISSING}"),
+                Err(TemplateError::Missing("TEMPLATE_T_MISSING".to_string()))
+            );
+        });
+    }
+}
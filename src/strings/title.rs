@@ -0,0 +1,118 @@
+//! Headline-style title case ("The Lord of the Rings").
+
+/// Words [`title_case`] keeps lowercase unless they start or end the title:
+/// articles, coordinating conjunctions and short prepositions.
+pub const SMALL_WORDS: &[&str] = &[
+    "a", "an", "and", "as", "at", "but", "by", "en", "for", "if", "in", "nor", "of", "on", "or",
+    "per", "the", "to", "v", "via", "vs",
+];
+
+/// `s` with every word capitalized except [`SMALL_WORDS`] in the middle.
+///
+/// The first and last words, and a word after a colon, are always
+/// capitalized. Each part of a hyphenated word is treated as a word
+/// (`State-of-the-Art`), only the first letter of a word changes, so
+/// `don't` becomes `Don't`, and words with capitals after their first
+/// letter (`iPhone`, `NASA`, `OF`) are left alone. Whitespace is preserved.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::title_case;
+/// assert_eq!(title_case("the lord of the rings"), "The Lord of the Rings");
+/// assert_eq!(title_case("a state-of-the-art iPhone app"), "A State-of-the-Art iPhone App");
+/// assert_eq!(title_case("what it's for"), "What It's For");
+/// ```
+pub fn title_case(s: &str) -> String {
+    title_case_with(s, SMALL_WORDS)
+}
+
+/// Like [`title_case`], keeping `small_words` lowercase instead of
+/// [`SMALL_WORDS`]. Words are compared case-insensitively.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::title_case_with;
+/// assert_eq!(title_case_with("war and peace", &[]), "War And Peace");
+/// assert_eq!(title_case_with("notes from the field", &["from", "the"]), "Notes from the Field");
+/// ```
+pub fn title_case_with(s: &str, small_words: &[&str]) -> String {
+    let is_small = |part: &str| {
+        let core = part.trim_matches(|c: char| !c.is_alphanumeric());
+        !core.is_empty()
+            && !core.chars().skip(1).any(char::is_uppercase)
+            && small_words.iter().any(|w| w.eq_ignore_ascii_case(core))
+    };
+    let word_count = s.split_whitespace().count();
+
+    let mut out = String::with_capacity(s.len());
+    let mut index = 0;
+    let mut after_colon = false;
+    for piece in s.split_inclusive(char::is_whitespace) {
+        let word = piece.trim_end_matches(char::is_whitespace);
+        if !word.is_empty() {
+            let first = index == 0 || after_colon;
+            let last = index + 1 == word_count;
+            let parts: Vec<&str> = word.split('-').collect();
+            for (i, part) in parts.iter().enumerate() {
+                if i > 0 {
+                    out.push('-');
+                }
+                let forced = (first && i == 0) || (last && i + 1 == parts.len());
+                if !forced && is_small(part) {
+                    out.push_str(&part.to_lowercase());
+                } else {
+                    capitalize_into(&mut out, part);
+                }
+            }
+            after_colon = word.ends_with(':');
+            index += 1;
+        }
+        out.push_str(&piece[word.len()..]);
+    }
+    out
+}
+
+/// Uppercase the first letter or digit of `word`, unless it has capitals
+/// after that and is presumably already styled.
+fn capitalize_into(out: &mut String, word: &str) {
+    let Some(start) = word.find(char::is_alphanumeric) else {
+        out.push_str(word);
+        return;
+    };
+    let mut rest = word[start..].chars();
+    let first = rest.next().unwrap_or_default();
+    if rest.as_str().chars().any(char::is_uppercase) {
+        out.push_str(word);
+        return;
+    }
+    out.push_str(&word[..start]);
+    out.extend(first.to_uppercase());
+    out.push_str(rest.as_str());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_title_case_small_words() {
+        assert_eq!(title_case("a tale of two cities"), "A Tale of Two Cities");
+        assert_eq!(title_case("THE END OF THE ROAD"), "THE END OF THE ROAD");
+        assert_eq!(title_case("A Tale Of Two Cities"), "A Tale of Two Cities");
+        assert_eq!(title_case("The End Of The Road"), "The End of the Road");
+        assert_eq!(title_case("what are you looking at"), "What Are You Looking At");
+        assert_eq!(title_case("star wars: a new hope"), "Star Wars: A New Hope");
+        assert_eq!(title_case("the one to go with"), "The One to Go With");
+    }
+
+    #[test]
+    fn test_title_case_punctuation_and_whitespace() {
+        assert_eq!(title_case("rock 'n' roll"), "Rock 'N' Roll");
+        assert_eq!(title_case("(the) good  part\n"), "(The) Good  Part\n");
+        assert_eq!(title_case("o’brien’s pub"), "O’brien’s Pub");
+        assert_eq!(title_case("end-to-end tests"), "End-to-End Tests");
+        assert_eq!(title_case("über café"), "Über Café");
+        assert_eq!(title_case(""), "");
+        assert_eq!(title_case("the"), "The");
+    }
+}
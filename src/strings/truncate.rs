@@ -0,0 +1,124 @@
+//! Truncation that never splits a character.
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Shorten `s` to at most `max` grapheme clusters, ending with `ellipsis`
+/// when anything was cut.
+///
+/// Grapheme clusters are what a reader sees as one character, so accents,
+/// emoji with modifiers and flags are never split. The ellipsis counts
+/// towards `max`; if it doesn't fit, `s` is cut to `max` clusters without
+/// one.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::truncate;
+/// assert_eq!(truncate("Hello, world", 8, "…"), "Hello, …");
+/// assert_eq!(truncate("short", 8, "…"), "short");
+/// assert_eq!(truncate("👍🏽👍🏽👍🏽", 2, "…"), "👍🏽…");
+/// ```
+pub fn truncate(s: &str, max: usize, ellipsis: &str) -> String {
+    if s.graphemes(true).nth(max).is_none() {
+        return s.to_string();
+    }
+    let ellipsis_len = ellipsis.graphemes(true).count();
+    if ellipsis_len > max {
+        return s[..cluster_offset(s, max)].to_string();
+    }
+    let mut out = s[..cluster_offset(s, max - ellipsis_len)].to_string();
+    out.push_str(ellipsis);
+    out
+}
+
+/// The longest prefix of `s` that is at most `max_bytes` long and ends on a
+/// char boundary.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::truncate_bytes;
+/// assert_eq!(truncate_bytes("héllo", 2), "h");
+/// assert_eq!(truncate_bytes("héllo", 3), "hé");
+/// ```
+pub fn truncate_bytes(s: &str, max_bytes: usize) -> &str {
+    if s.len() <= max_bytes {
+        return s;
+    }
+    let mut end = max_bytes;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+/// Like [`truncate_bytes`], ending with `marker` when anything was cut,
+/// for fields with hard byte limits such as DNS labels or Kafka headers.
+///
+/// The marker counts towards `max_bytes`; if it doesn't fit, `s` is cut to
+/// `max_bytes` without one.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::truncate_to_bytes;
+/// assert_eq!(truncate_to_bytes("payload-überlang", 13, "…"), "payload-ü…");
+/// assert_eq!(truncate_to_bytes("payload-überlang", 12, "…"), "payload-…");
+/// assert_eq!(truncate_to_bytes("short", 12, "…"), "short");
+/// ```
+pub fn truncate_to_bytes(s: &str, max_bytes: usize, marker: &str) -> String {
+    if s.len() <= max_bytes {
+        return s.to_string();
+    }
+    if marker.len() > max_bytes {
+        return truncate_bytes(s, max_bytes).to_string();
+    }
+    let mut out = truncate_bytes(s, max_bytes - marker.len()).to_string();
+    out.push_str(marker);
+    out
+}
+
+/// The byte offset of the `count`-th grapheme cluster of `s`.
+fn cluster_offset(s: &str, count: usize) -> usize {
+    s.grapheme_indices(true)
+        .nth(count)
+        .map_or(s.len(), |(index, _)| index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("Hello, world", 12, "…"), "Hello, world");
+        assert_eq!(truncate("Hello, world", 11, "…"), "Hello, wor…");
+        assert_eq!(truncate("Hello, world", 8, "..."), "Hello...");
+        assert_eq!(truncate("Hello, world", 5, ""), "Hello");
+        assert_eq!(truncate("Hello, world", 2, "..."), "He");
+        assert_eq!(truncate("Hello, world", 0, "…"), "");
+        assert_eq!(truncate("", 0, "…"), "");
+        // `e` followed by a combining acute accent is one cluster.
+        assert_eq!(truncate("cafe\u{301} au lait", 5, "…"), "cafe\u{301}…");
+        assert_eq!(truncate("🇩🇪🇫🇷🇮🇹", 2, "…"), "🇩🇪…");
+    }
+
+    #[test]
+    fn test_truncate_bytes() {
+        assert_eq!(truncate_bytes("hello", 10), "hello");
+        assert_eq!(truncate_bytes("hello", 0), "");
+        assert_eq!(truncate_bytes("日本語", 5), "日");
+        assert_eq!(truncate_bytes("日本語", 6), "日本");
+        assert_eq!(truncate_bytes("🦀", 3), "");
+    }
+
+    #[test]
+    fn test_truncate_to_bytes() {
+        assert_eq!(truncate_to_bytes("hello world", 11, "..."), "hello world");
+        assert_eq!(truncate_to_bytes("hello world", 10, "..."), "hello w...");
+        assert_eq!(truncate_to_bytes("hello world", 4, ""), "hell");
+        assert_eq!(truncate_to_bytes("hello world", 2, "..."), "he");
+        assert_eq!(truncate_to_bytes("日本語", 8, "~"), "日本~");
+        assert_eq!(truncate_to_bytes("日本語", 6, "~"), "日~");
+        assert_eq!(truncate_to_bytes("🦀🦀", 4, "~"), "~");
+        for max in 0..20 {
+            assert!(truncate_to_bytes("ünïcödé strïng", max, "…").len() <= max);
+        }
+    }
+}
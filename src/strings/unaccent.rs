@@ -0,0 +1,81 @@
+//! Diacritics removal and ASCII transliteration for slugs and search keys.
+use super::ascii::fold;
+
+/// `s` with accents removed from Latin letters (`é` becomes `e`) and Latin
+/// ligatures spelled out (`ß` becomes `ss`, `Œ` becomes `OE`).
+///
+/// Combining marks are dropped, so decomposed input gives the same result.
+/// Everything else, including other scripts, is kept as it is; use
+/// [`to_ascii_lossy`] (`transliterate` feature) to go all the way to ASCII.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::unaccent;
+/// assert_eq!(unaccent("Łódź"), "Lodz");
+/// assert_eq!(unaccent("Straße nach Zürich"), "Strasse nach Zurich");
+/// assert_eq!(unaccent("Ελλάδα"), "Ελλάδα");
+/// ```
+pub fn unaccent(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match fold(c) {
+            Some(folded) => out.push_str(folded),
+            None => out.push(c),
+        }
+    }
+    out
+}
+
+/// `s` transliterated to ASCII (`transliterate` feature): Latin letters as
+/// [`unaccent`] spells them, other scripts by their usual romanization, and
+/// symbols by a close ASCII equivalent. Characters with no equivalent become
+/// `?`.
+///
+/// The result is meant for slugs, filenames and search keys, not for
+/// display: romanization is by character, without any language rules.
+/// Combine it with [`slugify`](super::slugify) to get slugs for any script.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::{slugify, to_ascii_lossy};
+/// assert_eq!(to_ascii_lossy("Łódź"), "Lodz");
+/// assert_eq!(to_ascii_lossy("Москва"), "Moskva");
+/// assert_eq!(slugify(&to_ascii_lossy("Ελλάδα 2024")), "ellada-2024");
+/// ```
+#[cfg(feature = "transliterate")]
+pub fn to_ascii_lossy(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match fold(c) {
+            Some(folded) => out.push_str(folded),
+            None if c.is_ascii() => out.push(c),
+            None => out.push_str(deunicode::deunicode_char(c).unwrap_or("?")),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unaccent() {
+        assert_eq!(unaccent("Crème Brûlée"), "Creme Brulee");
+        assert_eq!(unaccent("Ærøskøbing"), "AEroskobing");
+        assert_eq!(unaccent("Cafe\u{301}"), "Cafe");
+        assert_eq!(unaccent("plain ascii 123!"), "plain ascii 123!");
+        assert_eq!(unaccent("Tōkyō 東京"), "Tokyo 東京");
+    }
+
+    #[cfg(feature = "transliterate")]
+    #[test]
+    fn test_to_ascii_lossy() {
+        assert_eq!(to_ascii_lossy("Crème Brûlée"), "Creme Brulee");
+        assert_eq!(to_ascii_lossy("Ærøskøbing"), "AEroskobing");
+        assert_eq!(to_ascii_lossy("Привет"), "Privet");
+        assert_eq!(to_ascii_lossy("“quotes” – dash"), "\"quotes\" - dash");
+        assert!(to_ascii_lossy("東京 🚀").is_ascii());
+        assert_eq!(to_ascii_lossy("\u{10ffff}"), "?");
+    }
+}
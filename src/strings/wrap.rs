@@ -0,0 +1,158 @@
+//! Word wrapping by display width, for help text and terminal reports.
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// How [`wrap_with`] and [`fill_with`] lay out lines.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::{fill_with, WrapOptions};
+/// let options = WrapOptions { width: 22, initial_indent: "- ", subsequent_indent: "  " };
+/// assert_eq!(
+///     fill_with("Retries failed requests with exponential backoff", &options),
+///     "- Retries failed\n  requests with\n  exponential backoff"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WrapOptions<'a> {
+    /// Widest line in terminal columns, including the indent.
+    pub width: usize,
+    /// Put before the first line of each paragraph.
+    pub initial_indent: &'a str,
+    /// Put before every other line.
+    pub subsequent_indent: &'a str,
+}
+
+impl Default for WrapOptions<'_> {
+    fn default() -> Self {
+        Self {
+            width: 80,
+            initial_indent: "",
+            subsequent_indent: "",
+        }
+    }
+}
+
+/// Break `text` into lines at most `width` columns wide.
+///
+/// Widths are terminal columns, so CJK characters and most emoji count as
+/// two. Lines break between words, never inside one, unless a word is wider
+/// than a whole line; it is then split between characters, without a
+/// hyphen. Each line of `text` is wrapped as its own paragraph, so existing
+/// line breaks and blank lines are kept, while runs of spaces collapse to
+/// one.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::wrap;
+/// assert_eq!(wrap("The quick brown fox", 10), ["The quick", "brown fox"]);
+/// ```
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    wrap_with(
+        text,
+        &WrapOptions {
+            width,
+            ..WrapOptions::default()
+        },
+    )
+}
+
+/// Like [`wrap`], with indents for the first and following lines.
+pub fn wrap_with(text: &str, options: &WrapOptions) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = options.initial_indent.to_string();
+        let mut empty = true;
+        for word in paragraph.split_whitespace() {
+            let needed = word.width() + usize::from(!empty);
+            if !empty && line.width() + needed > options.width {
+                lines.push(line);
+                line = options.subsequent_indent.to_string();
+                empty = true;
+            }
+            if !empty {
+                line.push(' ');
+            }
+            // A word too wide for any line fills lines piece by piece.
+            for grapheme in word.graphemes(true) {
+                let full = line.width() + grapheme.width() > options.width;
+                if full && !line.trim_start().is_empty() && word.width() > line_room(options) {
+                    lines.push(line);
+                    line = options.subsequent_indent.to_string();
+                }
+                line.push_str(grapheme);
+            }
+            empty = false;
+        }
+        lines.push(if empty { String::new() } else { line });
+    }
+    lines
+}
+
+/// [`wrap`] joined into one string with `\n`.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::strings::fill;
+/// assert_eq!(fill("日本語のテキスト", 6), "日本語\nのテキ\nスト");
+/// ```
+pub fn fill(text: &str, width: usize) -> String {
+    wrap(text, width).join("\n")
+}
+
+/// [`wrap_with`] joined into one string with `\n`.
+pub fn fill_with(text: &str, options: &WrapOptions) -> String {
+    wrap_with(text, options).join("\n")
+}
+
+/// Columns left for text on a continuation line.
+fn line_room(options: &WrapOptions) -> usize {
+    options
+        .width
+        .saturating_sub(options.subsequent_indent.width())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(
+            wrap("one two three four five", 9),
+            ["one two", "three", "four five"]
+        );
+        assert_eq!(wrap("one  two\n\nthree", 80), ["one two", "", "three"]);
+        assert_eq!(wrap("", 10), Vec::<String>::new());
+        assert_eq!(wrap("exactly10!", 10), ["exactly10!"]);
+    }
+
+    #[test]
+    fn test_wrap_long_words() {
+        assert_eq!(
+            wrap("see https://example.com/a/long/path now", 12),
+            ["see", "https://exam", "ple.com/a/lo", "ng/path now"]
+        );
+        assert_eq!(wrap("abc", 0), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_wrap_display_width() {
+        // Each of these characters is two columns wide.
+        assert_eq!(wrap("漢字 漢字漢字", 6), ["漢字", "漢字漢", "字"]);
+        assert_eq!(wrap("naïve café", 5), ["naïve", "café"]);
+    }
+
+    #[test]
+    fn test_wrap_with_indents() {
+        let options = WrapOptions {
+            width: 12,
+            initial_indent: "  * ",
+            subsequent_indent: "    ",
+        };
+        assert_eq!(
+            wrap_with("first item text\nsecond", &options),
+            ["  * first", "    item", "    text", "  * second"]
+        );
+    }
+}
@@ -0,0 +1,280 @@
+//! Delays between retries.
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// How much randomness [`Backoff`] adds to each delay, so that clients
+/// retrying the same failure don't all retry at the same moment.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Jitter {
+    /// Exactly the scheduled delay.
+    #[default]
+    None,
+    /// Anywhere between zero and the scheduled delay.
+    Full,
+    /// At least half the scheduled delay, plus up to the other half.
+    Equal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Strategy {
+    Fixed,
+    Linear,
+    Exponential(u32),
+}
+
+/// The delays to sleep between attempts of a retried operation.
+///
+/// Each call to `next` returns the delay before the next retry, or `None`
+/// once [`max_attempts`](Self::max_attempts) is used up, so the same
+/// schedule works in sync and async retry loops alike. Without a bound the
+/// iterator never ends.
+///
+/// Example:
+/// ```
+/// use std::time::Duration;
+/// use common_utils_rs::time::{Backoff, Jitter};
+///
+/// let delays: Vec<Duration> = Backoff::exponential(Duration::from_millis(100))
+///     .max_delay(Duration::from_millis(500))
+///     .max_attempts(6)
+///     .collect();
+/// assert_eq!(delays, [100, 200, 400, 500, 500].map(Duration::from_millis));
+///
+/// let mut backoff = Backoff::fixed(Duration::from_secs(1)).jitter(Jitter::Full).max_attempts(3);
+/// let result = loop {
+///     match "not a number".parse::<u32>() {
+///         Ok(value) => break Ok(value),
+///         Err(e) => match backoff.next() {
+///             Some(delay) => assert!(delay <= Duration::from_secs(1)), // sleep(delay)
+///             None => break Err(e),
+///         },
+///     }
+/// };
+/// assert!(result.is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    strategy: Strategy,
+    initial: Duration,
+    max_delay: Option<Duration>,
+    jitter: Jitter,
+    max_attempts: Option<u32>,
+    retries: u32,
+    rng: u64,
+}
+
+impl Backoff {
+    /// `delay` before every retry.
+    pub fn fixed(delay: Duration) -> Self {
+        Self::new(Strategy::Fixed, delay)
+    }
+
+    /// `initial`, then `2 * initial`, `3 * initial` and so on.
+    pub fn linear(initial: Duration) -> Self {
+        Self::new(Strategy::Linear, initial)
+    }
+
+    /// `initial`, doubling after every retry.
+    pub fn exponential(initial: Duration) -> Self {
+        Self::new(Strategy::Exponential(2), initial)
+    }
+
+    fn new(strategy: Strategy, initial: Duration) -> Self {
+        Self {
+            strategy,
+            initial,
+            max_delay: None,
+            jitter: Jitter::None,
+            max_attempts: None,
+            retries: 0,
+            rng: RandomState::new().build_hasher().finish(),
+        }
+    }
+
+    /// Multiply an [`exponential`](Self::exponential) delay by `factor`
+    /// instead of 2 after every retry. Other strategies ignore it.
+    pub fn factor(mut self, factor: u32) -> Self {
+        if let Strategy::Exponential(_) = self.strategy {
+            self.strategy = Strategy::Exponential(factor);
+        }
+        self
+    }
+
+    /// Never schedule a delay longer than `max_delay`, before jitter.
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = Some(max_delay);
+        self
+    }
+
+    pub fn jitter(mut self, jitter: Jitter) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Allow `attempts` attempts in total, i.e. `attempts - 1` retries and
+    /// as many delays.
+    pub fn max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = Some(attempts);
+        self
+    }
+
+    /// Seed the jitter, e.g. for reproducible tests. Backoffs are seeded
+    /// randomly otherwise.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.rng = seed;
+        self
+    }
+
+    /// How many delays have been handed out so far.
+    pub fn retries(&self) -> u32 {
+        self.retries
+    }
+
+    /// Start the schedule over, e.g. after a success.
+    pub fn reset(&mut self) {
+        self.retries = 0;
+    }
+
+    /// The delay before retry number `retry` (from zero), before jitter.
+    fn scheduled(&self, retry: u32) -> Duration {
+        let delay = match self.strategy {
+            Strategy::Fixed => Some(self.initial),
+            Strategy::Linear => retry
+                .checked_add(1)
+                .and_then(|n| self.initial.checked_mul(n)),
+            Strategy::Exponential(factor) => factor
+                .checked_pow(retry)
+                .and_then(|n| self.initial.checked_mul(n)),
+        }
+        .unwrap_or(Duration::MAX);
+        match self.max_delay {
+            Some(max_delay) => delay.min(max_delay),
+            None => delay,
+        }
+    }
+
+    /// A uniformly random fraction in `[0, 1)` (SplitMix64).
+    fn random_fraction(&mut self) -> f64 {
+        self.rng = self.rng.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.rng;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn scale(delay: Duration, fraction: f64) -> Duration {
+        Duration::try_from_secs_f64(delay.as_secs_f64() * fraction)
+            .map_or(delay, |scaled| scaled.min(delay))
+    }
+}
+
+impl Iterator for Backoff {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        if let Some(attempts) = self.max_attempts
+            && self.retries.saturating_add(1) >= attempts
+        {
+            return None;
+        }
+        let delay = self.scheduled(self.retries);
+        self.retries = self.retries.saturating_add(1);
+        Some(match self.jitter {
+            Jitter::None => delay,
+            Jitter::Full => Self::scale(delay, self.random_fraction()),
+            Jitter::Equal => {
+                let half = delay / 2;
+                half.saturating_add(Self::scale(delay - half, self.random_fraction()))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(backoff: Backoff) -> Vec<u128> {
+        backoff.map(|delay| delay.as_millis()).collect()
+    }
+
+    #[test]
+    fn test_strategies() {
+        let ms = Duration::from_millis;
+        assert_eq!(millis(Backoff::fixed(ms(50)).max_attempts(4)), [50, 50, 50]);
+        assert_eq!(
+            millis(Backoff::linear(ms(100)).max_attempts(5)),
+            [100, 200, 300, 400]
+        );
+        assert_eq!(
+            millis(Backoff::exponential(ms(10)).max_attempts(5)),
+            [10, 20, 40, 80]
+        );
+        assert_eq!(
+            millis(Backoff::exponential(ms(1)).factor(10).max_attempts(4)),
+            [1, 10, 100]
+        );
+        assert_eq!(
+            millis(Backoff::linear(ms(100)).factor(10).max_attempts(3)),
+            [100, 200]
+        );
+    }
+
+    #[test]
+    fn test_max_attempts_and_reset() {
+        let ms = Duration::from_millis;
+        assert!(millis(Backoff::fixed(ms(1)).max_attempts(0)).is_empty());
+        assert!(millis(Backoff::fixed(ms(1)).max_attempts(1)).is_empty());
+
+        let mut backoff = Backoff::exponential(ms(10)).max_attempts(3);
+        assert_eq!(backoff.next(), Some(ms(10)));
+        assert_eq!(backoff.next(), Some(ms(20)));
+        assert_eq!(backoff.next(), None);
+        assert_eq!(backoff.retries(), 2);
+        backoff.reset();
+        assert_eq!(backoff.next(), Some(ms(10)));
+
+        let mut unbounded = Backoff::fixed(ms(1));
+        assert_eq!(unbounded.nth(10_000), Some(ms(1)));
+    }
+
+    #[test]
+    fn test_max_delay_and_overflow() {
+        let mut backoff = Backoff::exponential(Duration::from_secs(1));
+        assert_eq!(backoff.nth(100), Some(Duration::MAX));
+        let mut capped =
+            Backoff::exponential(Duration::from_secs(1)).max_delay(Duration::from_secs(30));
+        assert_eq!(capped.nth(100), Some(Duration::from_secs(30)));
+        let mut linear = Backoff::linear(Duration::MAX);
+        assert_eq!(linear.nth(1), Some(Duration::MAX));
+    }
+
+    #[test]
+    fn test_jitter() {
+        let delay = Duration::from_secs(10);
+        let full: Vec<Duration> = Backoff::fixed(delay)
+            .jitter(Jitter::Full)
+            .take(200)
+            .collect();
+        assert!(full.iter().all(|d| *d <= delay));
+        assert!(full.iter().any(|d| *d < delay / 2));
+        let equal: Vec<Duration> = Backoff::fixed(delay)
+            .jitter(Jitter::Equal)
+            .take(200)
+            .collect();
+        assert!(equal.iter().all(|d| *d >= delay / 2 && *d <= delay));
+        assert!(equal.windows(2).any(|pair| pair[0] != pair[1]));
+
+        let seeded = || {
+            Backoff::exponential(delay)
+                .jitter(Jitter::Full)
+                .seed(7)
+                .take(5)
+        };
+        assert!(seeded().eq(seeded()));
+        let mut huge = Backoff::fixed(Duration::MAX).jitter(Jitter::Equal);
+        assert!(huge.next().unwrap() >= Duration::MAX / 2);
+    }
+}
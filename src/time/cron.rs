@@ -0,0 +1,349 @@
+//! Cron schedules and their upcoming fire times.
+use std::fmt;
+use std::iter::FusedIterator;
+use std::str::FromStr;
+use std::time::SystemTime;
+
+use thiserror::Error;
+
+use super::calendar::{civil_from_days, days_in_month, to_system_time, unix_seconds};
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[error("Invalid cron expression `{input}`: {reason}")]
+pub struct ParseCronError {
+    pub input: String,
+    pub reason: String,
+}
+
+const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const WEEKDAYS: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// A parsed five-field cron schedule (`minute hour day-of-month month
+/// day-of-week`), evaluated in UTC.
+///
+/// Each field accepts `*`, values, ranges (`1-5`), steps (`*/15`, `0-30/10`)
+/// and comma-separated lists. Months and weekdays may be written as names
+/// (`JAN`, `MON`), and Sunday is either `0` or `7`. The macros `@yearly`,
+/// `@annually`, `@monthly`, `@weekly`, `@daily`, `@midnight` and `@hourly` are
+/// also accepted. As in classic cron, when both day fields are restricted a
+/// day matches if either does.
+///
+/// Example:
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use common_utils_rs::time::Cron;
+///
+/// let cron = Cron::parse("*/5 * * * *").unwrap();
+/// let next: Vec<_> = cron.upcoming(UNIX_EPOCH).take(3).collect();
+/// assert_eq!(next, [300, 600, 900].map(|s| UNIX_EPOCH + Duration::from_secs(s)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cron {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    day_of_month_restricted: bool,
+    day_of_week_restricted: bool,
+}
+
+impl Cron {
+    /// Parse a cron expression, rejecting schedules that can never fire
+    /// (e.g. `0 0 30 2 *`).
+    pub fn parse(input: &str) -> Result<Self, ParseCronError> {
+        let expression = input.trim();
+        let err = |reason: String| ParseCronError {
+            input: expression.to_string(),
+            reason,
+        };
+
+        let expanded = match expression.to_ascii_lowercase().as_str() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            other if other.starts_with('@') => {
+                return Err(err(format!("unknown macro `{}`", expression)));
+            }
+            _ => expression,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+            return Err(err(format!(
+                "expected 5 fields (minute hour day-of-month month day-of-week), got {}",
+                fields.len()
+            )));
+        };
+
+        let mut days_of_week =
+            parse_field(day_of_week, "day-of-week", 0, 7, &WEEKDAYS).map_err(err)?;
+        if has(days_of_week, 7) {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+        let cron = Cron {
+            expression: expression.to_string(),
+            minutes: parse_field(minute, "minute", 0, 59, &[]).map_err(err)?,
+            hours: parse_field(hour, "hour", 0, 23, &[]).map_err(err)?,
+            days_of_month: parse_field(day_of_month, "day-of-month", 1, 31, &[]).map_err(err)?,
+            months: parse_field(month, "month", 1, 12, &MONTHS).map_err(err)?,
+            days_of_week,
+            day_of_month_restricted: !day_of_month.starts_with('*'),
+            day_of_week_restricted: !day_of_week.starts_with('*'),
+        };
+
+        let reachable = cron.day_of_week_restricted
+            || (1..=12).any(|month| {
+                has(cron.months, month)
+                    && (1..=days_in_month(2000, month)).any(|day| has(cron.days_of_month, day))
+            });
+        if !reachable {
+            return Err(err(
+                "the day-of-month never occurs in the selected months".to_string()
+            ));
+        }
+        Ok(cron)
+    }
+
+    /// The expression as written.
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// The first fire time strictly after `time`, or `None` if the schedule
+    /// never fires again within the representable range.
+    pub fn next_after(&self, time: SystemTime) -> Option<SystemTime> {
+        let start = unix_seconds(time).div_euclid(60) + 1;
+        let first_day = start.div_euclid(1440);
+
+        // Every pattern repeats within 400 years of the Gregorian calendar.
+        for days in first_day..first_day + 146_097 {
+            if !self.matches_day(days) {
+                continue;
+            }
+            let first_minute = if days == first_day {
+                start.rem_euclid(1440)
+            } else {
+                0
+            };
+            let found = (first_minute..1440).find(|minute| {
+                has(self.hours, (minute / 60) as u32) && has(self.minutes, (minute % 60) as u32)
+            });
+            if let Some(minute) = found {
+                return to_system_time((days * 1440 + minute) * 60, 0);
+            }
+        }
+        None
+    }
+
+    /// The first fire time after now.
+    pub fn next(&self) -> Option<SystemTime> {
+        self.next_after(SystemTime::now())
+    }
+
+    /// Every fire time strictly after `time`, in order.
+    ///
+    /// Example:
+    /// ```
+    /// use std::time::{Duration, UNIX_EPOCH};
+    /// use common_utils_rs::time::Cron;
+    /// let daily = Cron::parse("@daily").unwrap();
+    /// let third = daily.upcoming(UNIX_EPOCH).nth(2).unwrap();
+    /// assert_eq!(third, UNIX_EPOCH + Duration::from_secs(3 * 86_400));
+    /// ```
+    pub fn upcoming(&self, time: SystemTime) -> Upcoming<'_> {
+        Upcoming {
+            cron: self,
+            after: Some(time),
+        }
+    }
+
+    fn matches_day(&self, days: i64) -> bool {
+        let (_, month, day) = civil_from_days(days);
+        if !has(self.months, month) {
+            return false;
+        }
+        let day_of_month = has(self.days_of_month, day);
+        let day_of_week = has(self.days_of_week, (days + 4).rem_euclid(7) as u32);
+        match (self.day_of_month_restricted, self.day_of_week_restricted) {
+            (true, true) => day_of_month || day_of_week,
+            _ => day_of_month && day_of_week,
+        }
+    }
+}
+
+impl fmt::Display for Cron {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+impl FromStr for Cron {
+    type Err = ParseCronError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Cron::parse(s)
+    }
+}
+
+/// The fire times of a [`Cron`], from [`Cron::upcoming`].
+#[derive(Debug, Clone)]
+pub struct Upcoming<'a> {
+    cron: &'a Cron,
+    after: Option<SystemTime>,
+}
+
+impl Iterator for Upcoming<'_> {
+    type Item = SystemTime;
+
+    fn next(&mut self) -> Option<SystemTime> {
+        let next = self.cron.next_after(self.after?);
+        self.after = next;
+        next
+    }
+}
+
+impl FusedIterator for Upcoming<'_> {}
+
+fn has(mask: u64, value: u32) -> bool {
+    mask & (1 << value) != 0
+}
+
+/// Parse one field into a bit mask of the values it selects.
+fn parse_field(field: &str, name: &str, min: u32, max: u32, names: &[&str]) -> Result<u64, String> {
+    let value = |text: &str| -> Result<u32, String> {
+        let parsed = match names.iter().position(|n| n.eq_ignore_ascii_case(text)) {
+            Some(index) => index as u32 + min,
+            None => text
+                .parse()
+                .map_err(|_| format!("invalid {} `{}`", name, text))?,
+        };
+        if !(min..=max).contains(&parsed) {
+            return Err(format!(
+                "{} `{}` is not between {} and {}",
+                name, text, min, max
+            ));
+        }
+        Ok(parsed)
+    };
+
+    let mut mask = 0;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .ok_or_else(|| format!("invalid {} step `{}`", name, step))?;
+                (range, Some(step))
+            }
+            None => (part, None),
+        };
+        let (start, end) = match range.split_once('-') {
+            _ if range == "*" => (min, max),
+            Some((start, end)) => (value(start)?, value(end)?),
+            None if step.is_some() => (value(range)?, max),
+            None => {
+                let single = value(range)?;
+                (single, single)
+            }
+        };
+        if start > end {
+            return Err(format!("{} range `{}` starts after it ends", name, range));
+        }
+        for v in (start..=end).step_by(step.unwrap_or(1) as usize) {
+            mask |= 1 << v;
+        }
+    }
+    Ok(mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::calendar::days_from_civil;
+    use std::time::Duration;
+
+    /// A UTC date and time.
+    fn utc(year: i64, month: u32, day: u32, hour: i64, minute: i64) -> SystemTime {
+        let days = days_from_civil(year, month, day);
+        to_system_time(days * 86_400 + hour * 3600 + minute * 60, 0).unwrap()
+    }
+
+    fn next(expression: &str, after: SystemTime) -> SystemTime {
+        Cron::parse(expression).unwrap().next_after(after).unwrap()
+    }
+
+    #[test]
+    fn test_next_after() {
+        let start = utc(2024, 5, 1, 12, 30);
+        assert_eq!(next("*/15 * * * *", start), utc(2024, 5, 1, 12, 45));
+        assert_eq!(next("0 3 * * *", start), utc(2024, 5, 2, 3, 0));
+        assert_eq!(next("30 12 * * *", start), utc(2024, 5, 2, 12, 30));
+        assert_eq!(next("30 12 * * *", start - Duration::from_secs(1)), start);
+        assert_eq!(
+            next("0 9 * * MON-FRI", utc(2024, 5, 3, 10, 0)),
+            utc(2024, 5, 6, 9, 0)
+        );
+        assert_eq!(next("0 0 29 FEB *", start), utc(2028, 2, 29, 0, 0));
+        assert_eq!(next("@monthly", start), utc(2024, 6, 1, 0, 0));
+        assert_eq!(next("0 0 * * 7", start), utc(2024, 5, 5, 0, 0));
+        // Both day fields restricted: the 13th or any Friday.
+        assert_eq!(next("0 0 13 * 5", start), utc(2024, 5, 3, 0, 0));
+        assert_eq!(
+            next("0 0 1 1 *", utc(1969, 6, 1, 0, 0)),
+            utc(1970, 1, 1, 0, 0)
+        );
+    }
+
+    #[test]
+    fn test_upcoming() {
+        let cron: Cron = "0 9,17 * * MON-FRI".parse().unwrap();
+        let times: Vec<_> = cron.upcoming(utc(2024, 5, 3, 12, 0)).take(4).collect();
+        assert_eq!(
+            times,
+            [
+                utc(2024, 5, 3, 17, 0),
+                utc(2024, 5, 6, 9, 0),
+                utc(2024, 5, 6, 17, 0),
+                utc(2024, 5, 7, 9, 0),
+            ]
+        );
+        let hourly = Cron::parse("@HOURLY").unwrap();
+        assert_eq!(hourly.to_string(), "@HOURLY");
+        assert_eq!(
+            hourly.upcoming(utc(2024, 5, 1, 23, 0)).next(),
+            Some(utc(2024, 5, 2, 0, 0))
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for input in [
+            "",
+            "* * * *",
+            "* * * * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * * 13 *",
+            "* * * * 8",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "* * * FOO *",
+            "@fortnightly",
+            "0 0 30 2 *",
+        ] {
+            assert!(Cron::parse(input).is_err(), "{:?} should not parse", input);
+        }
+        assert_eq!(
+            Cron::parse(" 0 25 * * * ").unwrap_err().to_string(),
+            "Invalid cron expression `0 25 * * *`: hour `25` is not between 0 and 23"
+        );
+    }
+}
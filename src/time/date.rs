@@ -0,0 +1,320 @@
+//! Calendar dates and business-day arithmetic, e.g. for billing periods
+//! and SLA due dates.
+use std::collections::{BTreeSet, HashSet};
+use std::fmt;
+use std::hash::BuildHasher;
+use std::time::SystemTime;
+
+use super::calendar::{
+    civil_from_days, days_from_civil, days_in_month, to_system_time, unix_seconds,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+/// A proleptic Gregorian calendar date, without a time zone.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::time::{Date, Weekday};
+/// let date = Date::new(2024, 2, 29).unwrap();
+/// assert_eq!(date.weekday(), Weekday::Thursday);
+/// assert_eq!(date.add_days(1).unwrap().to_string(), "2024-03-01");
+/// assert_eq!(Date::new(2023, 2, 29), None);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    /// Days since 1970-01-01.
+    days: i64,
+}
+
+impl Date {
+    /// The date `year-month-day`, or `None` if there is no such day.
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Self> {
+        let leap_cycle_year = year.rem_euclid(400) as u32;
+        if !(1..=12).contains(&month) || day == 0 || day > days_in_month(leap_cycle_year, month) {
+            return None;
+        }
+        Some(Self {
+            days: days_from_civil(i64::from(year), month, day),
+        })
+    }
+
+    /// The UTC date of `time`.
+    pub fn from_system_time(time: SystemTime) -> Self {
+        Self {
+            days: unix_seconds(time).div_euclid(86_400),
+        }
+    }
+
+    /// Today's date in UTC.
+    pub fn today() -> Self {
+        Self::from_system_time(SystemTime::now())
+    }
+
+    /// Midnight UTC at the start of this date, or `None` if `SystemTime`
+    /// can't represent it.
+    pub fn to_system_time(&self) -> Option<SystemTime> {
+        to_system_time(self.days.checked_mul(86_400)?, 0)
+    }
+
+    pub fn year(&self) -> i32 {
+        civil_from_days(self.days).0 as i32
+    }
+
+    pub fn month(&self) -> u32 {
+        civil_from_days(self.days).1
+    }
+
+    pub fn day(&self) -> u32 {
+        civil_from_days(self.days).2
+    }
+
+    pub fn weekday(&self) -> Weekday {
+        // 1970-01-01 was a Thursday.
+        match (self.days.rem_euclid(7) + 3) % 7 {
+            0 => Weekday::Monday,
+            1 => Weekday::Tuesday,
+            2 => Weekday::Wednesday,
+            3 => Weekday::Thursday,
+            4 => Weekday::Friday,
+            5 => Weekday::Saturday,
+            _ => Weekday::Sunday,
+        }
+    }
+
+    /// The date `days` calendar days later, or earlier if negative, or
+    /// `None` if its year doesn't fit in an `i32`.
+    pub fn add_days(&self, days: i64) -> Option<Self> {
+        let days = self.days.checked_add(days)?;
+        let first = days_from_civil(i64::from(i32::MIN), 1, 1);
+        let last = days_from_civil(i64::from(i32::MAX), 12, 31);
+        (first..=last).contains(&days).then_some(Self { days })
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (year, month, day) = civil_from_days(self.days);
+        write!(f, "{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+/// Which dates are holidays, for [`add_business_days`] and
+/// [`is_business_day`].
+///
+/// Implemented for lists and sets of [`Date`]s and for closures, so a
+/// calendar can be a fixed list from config or computed rules.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::time::{is_business_day, Date};
+/// let christmas = |date: Date| date.month() == 12 && date.day() == 25;
+/// assert!(!is_business_day(Date::new(2024, 12, 25).unwrap(), &christmas));
+/// ```
+pub trait HolidayCalendar {
+    fn is_holiday(&self, date: Date) -> bool;
+}
+
+impl<F: Fn(Date) -> bool> HolidayCalendar for F {
+    fn is_holiday(&self, date: Date) -> bool {
+        self(date)
+    }
+}
+
+impl HolidayCalendar for [Date] {
+    fn is_holiday(&self, date: Date) -> bool {
+        self.contains(&date)
+    }
+}
+
+impl<const N: usize> HolidayCalendar for [Date; N] {
+    fn is_holiday(&self, date: Date) -> bool {
+        self.contains(&date)
+    }
+}
+
+impl HolidayCalendar for Vec<Date> {
+    fn is_holiday(&self, date: Date) -> bool {
+        self.contains(&date)
+    }
+}
+
+impl<S: BuildHasher> HolidayCalendar for HashSet<Date, S> {
+    fn is_holiday(&self, date: Date) -> bool {
+        self.contains(&date)
+    }
+}
+
+impl HolidayCalendar for BTreeSet<Date> {
+    fn is_holiday(&self, date: Date) -> bool {
+        self.contains(&date)
+    }
+}
+
+/// Whether `date` falls on a Saturday or Sunday.
+pub fn is_weekend(date: Date) -> bool {
+    matches!(date.weekday(), Weekday::Saturday | Weekday::Sunday)
+}
+
+/// Whether `date` is neither a weekend nor a holiday.
+pub fn is_business_day(date: Date, holidays: &(impl HolidayCalendar + ?Sized)) -> bool {
+    !is_weekend(date) && !holidays.is_holiday(date)
+}
+
+/// The date `n` business days after `date`, or before it if `n` is
+/// negative, skipping weekends and `holidays`. `date` itself is not
+/// counted, so a Friday plus one business day is the next Monday, and
+/// adding zero returns `date` unchanged.
+///
+/// Returns `None` if `holidays` leaves no business day in a whole year, as
+/// a misconfigured calendar might, or if the result is out of range.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::time::{add_business_days, Date};
+/// let friday = Date::new(2024, 12, 20).unwrap();
+/// let holidays = [Date::new(2024, 12, 25).unwrap(), Date::new(2024, 12, 26).unwrap()];
+/// assert_eq!(add_business_days(friday, 3, &holidays), Date::new(2024, 12, 27));
+/// assert_eq!(add_business_days(friday, 3, &[]), Date::new(2024, 12, 25));
+/// ```
+pub fn add_business_days(
+    date: Date,
+    n: i64,
+    holidays: &(impl HolidayCalendar + ?Sized),
+) -> Option<Date> {
+    let step = n.signum();
+    let mut date = date;
+    for _ in 0..n.unsigned_abs() {
+        date = (1..=366)
+            .scan(date, |day, _| {
+                *day = day.add_days(step)?;
+                Some(*day)
+            })
+            .find(|day| is_business_day(*day, holidays))?;
+    }
+    Some(date)
+}
+
+/// The number of calendar days from `start` to `end`, negative if `end`
+/// comes first.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::time::{days_between, Date};
+/// let start = Date::new(2024, 2, 1).unwrap();
+/// assert_eq!(days_between(start, Date::new(2024, 3, 1).unwrap()), 29);
+/// ```
+pub fn days_between(start: Date, end: Date) -> i64 {
+    end.days - start.days
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn date(year: i32, month: u32, day: u32) -> Date {
+        Date::new(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_date() {
+        let leap_day = date(2024, 2, 29);
+        assert_eq!(
+            (leap_day.year(), leap_day.month(), leap_day.day()),
+            (2024, 2, 29)
+        );
+        assert_eq!(date(1970, 1, 1).weekday(), Weekday::Thursday);
+        assert_eq!(date(1969, 12, 28).weekday(), Weekday::Sunday);
+        assert_eq!(date(2000, 2, 29).to_string(), "2000-02-29");
+        assert_eq!(Date::new(1900, 2, 29), None);
+        assert_eq!(Date::new(2024, 13, 1), None);
+        assert_eq!(Date::new(2024, 4, 0), None);
+        assert_eq!(date(2024, 12, 31).add_days(1), Some(date(2025, 1, 1)));
+        assert_eq!(date(i32::MAX, 12, 31).add_days(1), None);
+        assert_eq!(date(i32::MIN, 1, 1).add_days(-1), None);
+        assert_eq!(date(2024, 1, 1).add_days(i64::MAX), None);
+        assert_eq!(
+            date(i32::MIN, 1, 1).weekday(),
+            date(i32::MIN, 1, 8).weekday()
+        );
+        assert!(date(2024, 1, 1) < date(2024, 1, 2));
+
+        let evening = UNIX_EPOCH + Duration::from_secs(1_714_606_200); // 2024-05-01T23:30Z
+        assert_eq!(Date::from_system_time(evening), date(2024, 5, 1));
+        assert_eq!(
+            date(2024, 5, 1).to_system_time(),
+            Some(UNIX_EPOCH + Duration::from_secs(1_714_521_600))
+        );
+        assert_eq!(
+            Date::from_system_time(UNIX_EPOCH - Duration::from_secs(1)),
+            date(1969, 12, 31)
+        );
+    }
+
+    #[test]
+    fn test_add_business_days() {
+        let none: [Date; 0] = [];
+        let friday = date(2024, 5, 3);
+        let saturday = date(2024, 5, 4);
+        assert_eq!(add_business_days(friday, 1, &none), Some(date(2024, 5, 6)));
+        assert_eq!(add_business_days(friday, 0, &none), Some(friday));
+        assert_eq!(add_business_days(saturday, 0, &none), Some(saturday));
+        assert_eq!(
+            add_business_days(saturday, 1, &none),
+            Some(date(2024, 5, 6))
+        );
+        assert_eq!(
+            add_business_days(friday, 10, &none),
+            Some(date(2024, 5, 17))
+        );
+        assert_eq!(add_business_days(date(2024, 5, 6), -1, &none), Some(friday));
+        assert_eq!(add_business_days(saturday, -1, &none), Some(friday));
+
+        let holidays = vec![date(2024, 5, 6), date(2024, 5, 7)];
+        assert_eq!(
+            add_business_days(friday, 1, &holidays),
+            Some(date(2024, 5, 8))
+        );
+        assert_eq!(
+            add_business_days(date(2024, 5, 8), -1, holidays.as_slice()),
+            Some(friday)
+        );
+        let set: HashSet<Date> = holidays.into_iter().collect();
+        assert_eq!(add_business_days(friday, 2, &set), Some(date(2024, 5, 9)));
+        let first_of_month = |date: Date| date.day() == 1;
+        assert_eq!(
+            add_business_days(date(2024, 4, 30), 1, &first_of_month),
+            Some(date(2024, 5, 2))
+        );
+
+        let always = |_: Date| true;
+        assert_eq!(add_business_days(friday, 1, &always), None);
+        assert_eq!(add_business_days(friday, -1, &always), None);
+        let last = date(i32::MAX, 12, 31);
+        assert_eq!(add_business_days(last, 1, &none), None);
+    }
+
+    #[test]
+    fn test_is_weekend_and_days_between() {
+        assert!(is_weekend(date(2024, 5, 4)));
+        assert!(is_weekend(date(2024, 5, 5)));
+        assert!(!is_weekend(date(2024, 5, 6)));
+        assert!(!is_business_day(date(2024, 5, 6), &[date(2024, 5, 6)]));
+        assert!(is_business_day(date(2024, 5, 6), &BTreeSet::new()));
+
+        assert_eq!(days_between(date(2024, 1, 1), date(2025, 1, 1)), 366);
+        assert_eq!(days_between(date(2023, 1, 1), date(2024, 1, 1)), 365);
+        assert_eq!(days_between(date(2024, 5, 2), date(2024, 5, 1)), -1);
+        assert_eq!(days_between(date(1969, 12, 31), date(1970, 1, 1)), 1);
+    }
+}
@@ -0,0 +1,161 @@
+//! A single time budget shared by several operations.
+use std::time::{Duration, Instant};
+
+/// A point in time by which work must finish, or no limit at all.
+///
+/// Pass one deadline down through layered operations and derive each
+/// call's timeout from it with [`timeout`](Self::timeout), so retries and
+/// nested calls never exceed the overall budget.
+///
+/// Example:
+/// ```
+/// use std::time::Duration;
+/// use common_utils_rs::time::Deadline;
+///
+/// let deadline = Deadline::after(Duration::from_secs(10));
+/// for attempt in 0..3 {
+///     // Each attempt gets up to 4s, but never more than what is left.
+///     let Some(timeout) = deadline.timeout(Duration::from_secs(4)) else {
+///         break;
+///     };
+///     assert!(timeout <= Duration::from_secs(4));
+/// }
+/// assert!(!deadline.is_expired());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Deadline {
+    at: Option<Instant>,
+}
+
+impl Deadline {
+    /// A deadline `duration` from now. A duration too large to represent
+    /// never expires.
+    pub fn after(duration: Duration) -> Self {
+        Self {
+            at: Instant::now().checked_add(duration),
+        }
+    }
+
+    /// A deadline at `instant`.
+    pub fn at(instant: Instant) -> Self {
+        Self { at: Some(instant) }
+    }
+
+    /// No deadline: never expires, with [`Duration::MAX`] remaining.
+    pub fn never() -> Self {
+        Self { at: None }
+    }
+
+    /// When the deadline expires, or `None` if it never does.
+    pub fn instant(&self) -> Option<Instant> {
+        self.at
+    }
+
+    /// Time left before the deadline, zero once it has passed.
+    pub fn remaining(&self) -> Duration {
+        match self.at {
+            Some(at) => at.saturating_duration_since(Instant::now()),
+            None => Duration::MAX,
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.at.is_some_and(|at| Instant::now() >= at)
+    }
+
+    /// The timeout for one call: `per_call`, cut short to the time
+    /// remaining, or `None` once the deadline has passed.
+    pub fn timeout(&self, per_call: Duration) -> Option<Duration> {
+        let remaining = self.remaining();
+        (!remaining.is_zero()).then(|| remaining.min(per_call))
+    }
+
+    /// This deadline or `duration` from now, whichever comes first, e.g. for
+    /// a step that must also respect its own limit.
+    ///
+    /// Example:
+    /// ```
+    /// use std::time::Duration;
+    /// use common_utils_rs::time::Deadline;
+    /// let request = Deadline::after(Duration::from_secs(30));
+    /// let lookup = request.limit(Duration::from_secs(2));
+    /// assert!(lookup.remaining() <= Duration::from_secs(2));
+    /// ```
+    pub fn limit(self, duration: Duration) -> Self {
+        let other = Self::after(duration);
+        match (self.at, other.at) {
+            (Some(a), Some(b)) => Self::at(a.min(b)),
+            (Some(_), None) => self,
+            (None, _) => other,
+        }
+    }
+}
+
+impl From<Duration> for Deadline {
+    fn from(duration: Duration) -> Self {
+        Self::after(duration)
+    }
+}
+
+impl From<Instant> for Deadline {
+    fn from(instant: Instant) -> Self {
+        Self::at(instant)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: Duration = Duration::from_secs(3600);
+
+    #[test]
+    fn test_remaining_and_timeout() {
+        let deadline = Deadline::after(HOUR);
+        assert!(!deadline.is_expired());
+        assert!(deadline.remaining() <= HOUR);
+        assert!(deadline.remaining() > HOUR - Duration::from_secs(60));
+        assert_eq!(
+            deadline.timeout(Duration::from_secs(5)),
+            Some(Duration::from_secs(5))
+        );
+        assert!(deadline.timeout(2 * HOUR).unwrap() <= HOUR);
+    }
+
+    #[test]
+    fn test_expired() {
+        let deadline = Deadline::at(Instant::now() - Duration::from_secs(1));
+        assert!(deadline.is_expired());
+        assert_eq!(deadline.remaining(), Duration::ZERO);
+        assert_eq!(deadline.timeout(Duration::from_secs(5)), None);
+        assert!(Deadline::after(Duration::ZERO).is_expired());
+    }
+
+    #[test]
+    fn test_never() {
+        for deadline in [Deadline::never(), Deadline::after(Duration::MAX)] {
+            assert!(!deadline.is_expired());
+            assert_eq!(deadline.instant(), None);
+            assert_eq!(deadline.remaining(), Duration::MAX);
+            assert_eq!(deadline.timeout(HOUR), Some(HOUR));
+        }
+    }
+
+    #[test]
+    fn test_limit() {
+        let soon = Instant::now() + Duration::from_secs(10);
+        assert_eq!(Deadline::at(soon).limit(HOUR).instant(), Some(soon));
+        assert!(
+            Deadline::after(HOUR)
+                .limit(Duration::from_secs(1))
+                .remaining()
+                <= Duration::from_secs(1)
+        );
+        assert!(Deadline::never().limit(HOUR).instant().is_some());
+        assert_eq!(
+            Deadline::at(soon).limit(Duration::MAX).instant(),
+            Some(soon)
+        );
+        assert_eq!(Deadline::from(soon), Deadline::at(soon));
+    }
+}
@@ -0,0 +1,159 @@
+//! Human-readable durations for logs and CLI summaries.
+use std::time::Duration;
+
+/// `(compact suffix, long name, nanoseconds)`, largest first.
+const UNITS: [(&str, &str, u128); 7] = [
+    ("d", "day", 86_400_000_000_000),
+    ("h", "hour", 3_600_000_000_000),
+    ("m", "minute", 60_000_000_000),
+    ("s", "second", 1_000_000_000),
+    ("ms", "millisecond", 1_000_000),
+    ("µs", "microsecond", 1_000),
+    ("ns", "nanosecond", 1),
+];
+
+/// How [`format_duration_with`] writes units.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DurationStyle {
+    /// `2h 3m 5s`, which `parse_duration` reads back.
+    #[default]
+    Compact,
+    /// `2 hours, 3 minutes, 5 seconds`.
+    Long,
+}
+
+/// How [`format_duration_with`] formats a duration.
+///
+/// Example:
+/// ```
+/// use std::time::Duration;
+/// use common_utils_rs::time::{format_duration_with, DurationOptions, DurationStyle};
+/// let options = DurationOptions { style: DurationStyle::Long, max_units: Some(2) };
+/// assert_eq!(format_duration_with(Duration::from_secs(93_784), &options), "1 day, 2 hours");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DurationOptions {
+    pub style: DurationStyle,
+    /// Show at most this many units, starting from the largest, rounding
+    /// the last one. `None` shows every unit down to nanoseconds.
+    pub max_units: Option<usize>,
+}
+
+/// `duration` in days, hours, minutes, seconds and smaller units, e.g.
+/// `2h 3m 5s` or `1s 500ms`; units that are zero are left out.
+///
+/// Example:
+/// ```
+/// use std::time::Duration;
+/// use common_utils_rs::time::format_duration;
+/// assert_eq!(format_duration(Duration::from_secs(7385)), "2h 3m 5s");
+/// assert_eq!(format_duration(Duration::from_millis(250)), "250ms");
+/// assert_eq!(format_duration(Duration::ZERO), "0s");
+/// ```
+pub fn format_duration(duration: Duration) -> String {
+    format_duration_with(duration, &DurationOptions::default())
+}
+
+/// Like [`format_duration`], with a choice of style and precision.
+///
+/// Example:
+/// ```
+/// use std::time::Duration;
+/// use common_utils_rs::time::{format_duration_with, DurationOptions};
+/// let options = DurationOptions { max_units: Some(2), ..Default::default() };
+/// assert_eq!(format_duration_with(Duration::from_millis(5_025_700), &options), "1h 24m");
+/// ```
+pub fn format_duration_with(duration: Duration, options: &DurationOptions) -> String {
+    let mut nanos = duration.as_nanos();
+    if let Some(max_units) = options.max_units {
+        let largest = UNITS
+            .iter()
+            .position(|(_, _, size)| nanos >= *size)
+            .unwrap_or(UNITS.len() - 1);
+        let smallest = largest
+            .saturating_add(max_units.max(1) - 1)
+            .min(UNITS.len() - 1);
+        let size = UNITS[smallest].2;
+        nanos = (nanos + size / 2) / size * size;
+    }
+
+    let mut parts = Vec::new();
+    for (suffix, name, size) in UNITS {
+        let count = nanos / size;
+        nanos %= size;
+        if count == 0 {
+            continue;
+        }
+        parts.push(match options.style {
+            DurationStyle::Compact => format!("{}{}", count, suffix),
+            DurationStyle::Long if count == 1 => format!("1 {}", name),
+            DurationStyle::Long => format!("{} {}s", count, name),
+        });
+    }
+
+    match options.style {
+        DurationStyle::Compact if parts.is_empty() => "0s".to_string(),
+        DurationStyle::Long if parts.is_empty() => "0 seconds".to_string(),
+        DurationStyle::Compact => parts.join(" "),
+        DurationStyle::Long => parts.join(", "),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn long(max_units: Option<usize>) -> DurationOptions {
+        DurationOptions {
+            style: DurationStyle::Long,
+            max_units,
+        }
+    }
+
+    fn compact(max_units: usize) -> DurationOptions {
+        DurationOptions {
+            style: DurationStyle::Compact,
+            max_units: Some(max_units),
+        }
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_secs(60)), "1m");
+        assert_eq!(format_duration(Duration::from_secs(90_061)), "1d 1h 1m 1s");
+        assert_eq!(format_duration(Duration::from_millis(1500)), "1s 500ms");
+        assert_eq!(format_duration(Duration::from_nanos(1_001)), "1µs 1ns");
+        assert_eq!(
+            format_duration(Duration::MAX),
+            "213503982334601d 7h 15s 999ms 999µs 999ns"
+        );
+    }
+
+    #[test]
+    fn test_format_duration_long() {
+        let format = |secs, units| format_duration_with(Duration::from_secs(secs), &long(units));
+        assert_eq!(format(0, None), "0 seconds");
+        assert_eq!(format(1, None), "1 second");
+        assert_eq!(format(7385, None), "2 hours, 3 minutes, 5 seconds");
+        assert_eq!(format(86_400 * 2, Some(3)), "2 days");
+    }
+
+    #[test]
+    fn test_format_duration_rounds_to_max_units() {
+        let format =
+            |millis, units| format_duration_with(Duration::from_millis(millis), &compact(units));
+        assert_eq!(format(7_385_000, 1), "2h");
+        assert_eq!(format(5_399_999, 2), "1h 30m");
+        // Rounding can carry into a larger unit and drop the smaller ones.
+        assert_eq!(format(3_599_600, 2), "1h");
+        assert_eq!(format(59_999, 1), "1m");
+        assert_eq!(format(1_234, 1), "1s");
+        assert_eq!(format(1_234, 0), "1s");
+        assert_eq!(format(0, 1), "0s");
+        assert_eq!(format(3_600_000, usize::MAX), "1h");
+        assert_eq!(
+            format_duration_with(Duration::from_nanos(1_499), &compact(1)),
+            "1µs"
+        );
+    }
+}
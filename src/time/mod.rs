@@ -0,0 +1,44 @@
+//! Time utilities (`time` feature).
+//!
+//! Features:
+//! - `Backoff` / `Jitter` (fixed, linear and exponential retry delays, with caps and jitter)
+//! - `Cron` / `ParseCronError` (five-field cron schedules and `@hourly`-style macros, with `next_after` and `upcoming`)
+//! - `Date` / `add_business_days` / `is_weekend` / `days_between` / `HolidayCalendar` (billing and SLA date math)
+//! - `Deadline` (one time budget shared by layered operations, with per-call timeouts)
+//! - `format_duration` / `format_duration_with` / `DurationOptions` (`2h 3m 5s`, or `2 hours, 3 minutes, 5 seconds`)
+//! - `humanize_relative` / `humanize_relative_with` / `Granularity` ("3 hours ago", "in 2 days")
+//! - `Stopwatch` (laps, restarts, humanized `Display`) / `ScopedTimer` (reports elapsed time on drop)
+//! - `parse_duration` (`1d2h30m`, `1.5h`, `90`), shared with the env getters
+//! - `in_zone` / `local_midnight` / `midnight_in` / `utc_offset` / `format_offset` / `to_rfc3339_in` / `Tz` (IANA time zones, `tz` feature)
+//! - `unix_now` / `unix_now_millis` / `to_rfc3339` / `from_rfc3339` (timestamps without chrono or time)
+//!
+//! Example:
+//! ```
+//! use std::time::Duration;
+//! use common_utils_rs::time::*;
+//! assert_eq!(format_duration(Duration::from_secs(7385)), "2h 3m 5s");
+//! ```
+pub mod backoff;
+pub(crate) mod calendar;
+pub mod cron;
+pub mod date;
+pub mod deadline;
+pub mod format;
+pub mod parse;
+pub mod relative;
+pub mod stopwatch;
+pub mod timestamp;
+#[cfg(feature = "tz")]
+pub mod zone;
+
+pub use backoff::*;
+pub use cron::*;
+pub use date::*;
+pub use deadline::*;
+pub use format::*;
+pub use parse::*;
+pub use relative::*;
+pub use stopwatch::*;
+pub use timestamp::*;
+#[cfg(feature = "tz")]
+pub use zone::*;
@@ -0,0 +1,214 @@
+//! Parsing human-written durations from config files, CLI flags and the
+//! environment.
+use std::time::Duration;
+
+use thiserror::Error;
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[error("Invalid duration `{input}`: {reason}")]
+pub struct ParseDurationError {
+    pub input: String,
+    pub reason: String,
+}
+
+/// Parse a duration such as `250ms`, `1d2h30m`, `1.5h` or `90` (seconds).
+///
+/// A duration is one or more numbers, each followed by a unit; a single
+/// number on its own is in seconds. Numbers may have a fraction, and
+/// components may be separated by spaces or commas. Units are
+/// case-insensitive:
+///
+/// | Unit | Also written |
+/// |------|--------------|
+/// | `ns` | `nanosecond(s)` |
+/// | `us` | `µs`, `microsecond(s)` |
+/// | `ms` | `millisecond(s)` |
+/// | `s`  | `sec(s)`, `second(s)` |
+/// | `m`  | `min(s)`, `minute(s)` |
+/// | `h`  | `hr(s)`, `hour(s)` |
+/// | `d`  | `day(s)` |
+///
+/// so everything [`format_duration`](super::format_duration) writes, in
+/// either style, parses back.
+///
+/// Example:
+/// ```
+/// use std::time::Duration;
+/// use common_utils_rs::time::parse_duration;
+/// assert_eq!(parse_duration("1d2h30m").unwrap(), Duration::from_secs(95_400));
+/// assert_eq!(parse_duration("1.5h").unwrap(), Duration::from_secs(5400));
+/// assert_eq!(parse_duration("90").unwrap(), Duration::from_secs(90));
+/// assert_eq!(parse_duration("2 minutes, 5 seconds").unwrap(), Duration::from_secs(125));
+/// assert!(parse_duration("5 fortnights").is_err());
+/// ```
+pub fn parse_duration(input: &str) -> Result<Duration, ParseDurationError> {
+    let trimmed = input.trim();
+    let err = |reason: String| ParseDurationError {
+        input: trimmed.to_string(),
+        reason,
+    };
+    if trimmed.is_empty() {
+        return Err(err("empty".to_string()));
+    }
+    match parse_number(trimmed, 1_000_000_000) {
+        Ok(nanos) => return to_duration(nanos).ok_or_else(|| err("too large".to_string())),
+        Err(NumberError::TooLarge) => return Err(err("too large".to_string())),
+        Err(NumberError::Invalid) => {}
+    }
+
+    let mut total: u128 = 0;
+    let mut rest = trimmed;
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let number = &rest[..number_len];
+        rest = rest[number_len..].trim_start();
+        let unit_len = rest
+            .find(|c: char| !c.is_alphabetic())
+            .unwrap_or(rest.len());
+        let unit = &rest[..unit_len];
+        rest = rest[unit_len..].trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+
+        if number.is_empty() {
+            return Err(err(format!(
+                "expected a number before `{}`",
+                rest_or(unit, rest)
+            )));
+        }
+        if unit.is_empty() {
+            return Err(err(format!("missing unit after `{}`", number)));
+        }
+        let size = unit_nanos(unit).ok_or_else(|| err(format!("unknown unit `{}`", unit)))?;
+        let nanos = parse_number(number, size).map_err(|e| match e {
+            NumberError::Invalid => err(format!("invalid number `{}`", number)),
+            NumberError::TooLarge => err("too large".to_string()),
+        })?;
+        total = total
+            .checked_add(nanos)
+            .ok_or_else(|| err("too large".to_string()))?;
+    }
+    to_duration(total).ok_or_else(|| err("too large".to_string()))
+}
+
+/// What to quote when a component has no number: its unit, or else the
+/// text that couldn't be read.
+fn rest_or<'a>(unit: &'a str, rest: &'a str) -> &'a str {
+    if unit.is_empty() { rest } else { unit }
+}
+
+fn unit_nanos(unit: &str) -> Option<u128> {
+    let nanos = match unit.to_lowercase().as_str() {
+        "ns" | "nanosecond" | "nanoseconds" => 1,
+        "us" | "µs" | "microsecond" | "microseconds" => 1_000,
+        "ms" | "millisecond" | "milliseconds" => 1_000_000,
+        "s" | "sec" | "secs" | "second" | "seconds" => 1_000_000_000,
+        "m" | "min" | "mins" | "minute" | "minutes" => 60_000_000_000,
+        "h" | "hr" | "hrs" | "hour" | "hours" => 3_600_000_000_000,
+        "d" | "day" | "days" => 86_400_000_000_000,
+        _ => return None,
+    };
+    Some(nanos)
+}
+
+enum NumberError {
+    Invalid,
+    TooLarge,
+}
+
+/// `number` (digits with an optional fraction) times `unit` nanoseconds,
+/// with the fraction truncated to whole nanoseconds.
+fn parse_number(number: &str, unit: u128) -> Result<u128, NumberError> {
+    let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+    let digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if (whole.is_empty() && fraction.is_empty()) || !digits(whole) || !digits(fraction) {
+        return Err(NumberError::Invalid);
+    }
+    let mut nanos = match whole {
+        "" => 0,
+        whole => whole
+            .parse::<u128>()
+            .ok()
+            .and_then(|whole| whole.checked_mul(unit))
+            .ok_or(NumberError::TooLarge)?,
+    };
+    let mut scale = unit;
+    for digit in fraction.bytes() {
+        scale /= 10;
+        nanos = nanos
+            .checked_add(u128::from(digit - b'0') * scale)
+            .ok_or(NumberError::TooLarge)?;
+    }
+    Ok(nanos)
+}
+
+fn to_duration(nanos: u128) -> Option<Duration> {
+    let secs = u64::try_from(nanos / 1_000_000_000).ok()?;
+    Some(Duration::new(secs, (nanos % 1_000_000_000) as u32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::{DurationOptions, DurationStyle, format_duration, format_duration_with};
+
+    fn parse(input: &str) -> Duration {
+        parse_duration(input).unwrap()
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse("7ns"), Duration::from_nanos(7));
+        assert_eq!(parse("15us"), Duration::from_micros(15));
+        assert_eq!(parse("15µs"), Duration::from_micros(15));
+        assert_eq!(parse(" 30S "), Duration::from_secs(30));
+        assert_eq!(parse("1m30s500ms"), Duration::from_millis(90_500));
+        assert_eq!(parse("1 hour 1 min"), Duration::from_secs(3660));
+        assert_eq!(parse("0"), Duration::ZERO);
+        assert_eq!(parse("0.25"), Duration::from_millis(250));
+        assert_eq!(parse(".5s"), Duration::from_millis(500));
+        assert_eq!(parse("1.5ms"), Duration::from_micros(1500));
+        assert_eq!(parse("1.0000000009s"), Duration::from_secs(1));
+        assert_eq!(parse("5s 5s"), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_parse_duration_round_trips_format() {
+        for duration in [
+            Duration::ZERO,
+            Duration::from_nanos(1),
+            Duration::from_millis(90_061_001),
+            Duration::new(86_400 * 400 + 1, 999_999_999),
+            Duration::MAX,
+        ] {
+            assert_eq!(parse(&format_duration(duration)), duration);
+            let long = DurationOptions {
+                style: DurationStyle::Long,
+                max_units: None,
+            };
+            assert_eq!(parse(&format_duration_with(duration, &long)), duration);
+        }
+    }
+
+    #[test]
+    fn test_parse_duration_errors() {
+        let reason = |input: &str| parse_duration(input).unwrap_err().reason;
+        assert_eq!(reason(""), "empty");
+        assert_eq!(reason("5x"), "unknown unit `x`");
+        assert_eq!(reason("1h30"), "missing unit after `30`");
+        assert_eq!(reason("m5"), "expected a number before `m`");
+        assert_eq!(reason("-5s"), "expected a number before `-5s`");
+        assert_eq!(reason("1..5s"), "invalid number `1..5`");
+        assert_eq!(reason("."), "missing unit after `.`");
+        assert_eq!(reason("99999999999999999999999d"), "too large");
+        assert_eq!(
+            reason("999999999999999999999999999999999999999999"),
+            "too large"
+        );
+        assert_eq!(reason("213503982334602d"), "too large");
+        assert_eq!(
+            parse_duration(" soon ").unwrap_err().to_string(),
+            "Invalid duration `soon`: expected a number before `soon`"
+        );
+    }
+}
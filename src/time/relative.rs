@@ -0,0 +1,165 @@
+//! Relative times such as "3 hours ago" for CLI status and human-facing
+//! logs.
+use std::time::{Duration, SystemTime};
+
+/// The smallest unit [`humanize_relative_with`] reports; anything closer
+/// than one of it is "just now".
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Granularity {
+    #[default]
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Week,
+    /// 30 days.
+    Month,
+    /// 365 days.
+    Year,
+}
+
+impl Granularity {
+    const ALL: [Granularity; 7] = [
+        Granularity::Year,
+        Granularity::Month,
+        Granularity::Week,
+        Granularity::Day,
+        Granularity::Hour,
+        Granularity::Minute,
+        Granularity::Second,
+    ];
+
+    fn seconds(self) -> u64 {
+        match self {
+            Granularity::Second => 1,
+            Granularity::Minute => 60,
+            Granularity::Hour => 3600,
+            Granularity::Day => 86_400,
+            Granularity::Week => 7 * 86_400,
+            Granularity::Month => 30 * 86_400,
+            Granularity::Year => 365 * 86_400,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Granularity::Second => "second",
+            Granularity::Minute => "minute",
+            Granularity::Hour => "hour",
+            Granularity::Day => "day",
+            Granularity::Week => "week",
+            Granularity::Month => "month",
+            Granularity::Year => "year",
+        }
+    }
+}
+
+/// `then` relative to `now`: "just now", "3 hours ago" or "in 2 days".
+///
+/// The count is in the largest unit that fits and is rounded down, so 90
+/// minutes is "1 hour ago".
+///
+/// Example:
+/// ```
+/// use std::time::{Duration, SystemTime};
+/// use common_utils_rs::time::humanize_relative;
+/// let now = SystemTime::now();
+/// assert_eq!(humanize_relative(now - Duration::from_secs(3 * 3600), now), "3 hours ago");
+/// assert_eq!(humanize_relative(now + Duration::from_secs(2 * 86_400), now), "in 2 days");
+/// assert_eq!(humanize_relative(now, now), "just now");
+/// ```
+pub fn humanize_relative(then: SystemTime, now: SystemTime) -> String {
+    humanize_relative_with(then, now, Granularity::Second)
+}
+
+/// Like [`humanize_relative`], with anything closer than one `granularity`
+/// unit reported as "just now".
+///
+/// Example:
+/// ```
+/// use std::time::{Duration, SystemTime};
+/// use common_utils_rs::time::{humanize_relative_with, Granularity};
+/// let now = SystemTime::now();
+/// let then = now - Duration::from_secs(40);
+/// assert_eq!(humanize_relative_with(then, now, Granularity::Minute), "just now");
+/// assert_eq!(humanize_relative_with(then, now, Granularity::Second), "40 seconds ago");
+/// ```
+pub fn humanize_relative_with(
+    then: SystemTime,
+    now: SystemTime,
+    granularity: Granularity,
+) -> String {
+    let (distance, past) = match now.duration_since(then) {
+        Ok(ago) => (ago, true),
+        Err(ahead) => (ahead.duration(), false),
+    };
+    let Some((unit, count)) = largest_unit(distance, granularity) else {
+        return "just now".to_string();
+    };
+    let plural = if count == 1 { "" } else { "s" };
+    if past {
+        format!("{} {}{} ago", count, unit.name(), plural)
+    } else {
+        format!("in {} {}{}", count, unit.name(), plural)
+    }
+}
+
+/// The largest unit, no smaller than `granularity`, that `distance` holds at
+/// least once, with how many times it does.
+fn largest_unit(distance: Duration, granularity: Granularity) -> Option<(Granularity, u64)> {
+    let seconds = distance.as_secs();
+    Granularity::ALL
+        .into_iter()
+        .filter(|unit| *unit >= granularity)
+        .map(|unit| (unit, seconds / unit.seconds()))
+        .find(|(_, count)| *count > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::UNIX_EPOCH;
+
+    fn relative(offset: i64, granularity: Granularity) -> String {
+        let now = UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+        let then = if offset < 0 {
+            now - Duration::from_secs(offset.unsigned_abs())
+        } else {
+            now + Duration::from_secs(offset as u64)
+        };
+        humanize_relative_with(then, now, granularity)
+    }
+
+    #[test]
+    fn test_humanize_relative() {
+        let ago = |seconds: u64| relative(-(seconds as i64), Granularity::Second);
+        assert_eq!(ago(0), "just now");
+        assert_eq!(ago(1), "1 second ago");
+        assert_eq!(ago(59), "59 seconds ago");
+        assert_eq!(ago(60), "1 minute ago");
+        assert_eq!(ago(5399), "1 hour ago");
+        assert_eq!(ago(86_400), "1 day ago");
+        assert_eq!(ago(13 * 86_400), "1 week ago");
+        assert_eq!(ago(45 * 86_400), "1 month ago");
+        assert_eq!(ago(800 * 86_400), "2 years ago");
+        assert_eq!(relative(3 * 3600, Granularity::Second), "in 3 hours");
+        assert_eq!(relative(1, Granularity::Second), "in 1 second");
+    }
+
+    #[test]
+    fn test_humanize_relative_granularity() {
+        assert_eq!(relative(-59, Granularity::Minute), "just now");
+        assert_eq!(relative(-61, Granularity::Minute), "1 minute ago");
+        assert_eq!(relative(20 * 3600, Granularity::Day), "just now");
+        assert_eq!(relative(-10 * 86_400, Granularity::Day), "1 week ago");
+        assert_eq!(relative(-10 * 86_400, Granularity::Year), "just now");
+        assert!(Granularity::Second < Granularity::Year);
+    }
+
+    #[test]
+    fn test_sub_second_distances() {
+        let now = SystemTime::now();
+        let then = now - Duration::from_millis(999);
+        assert_eq!(humanize_relative(then, now), "just now");
+    }
+}
@@ -0,0 +1,181 @@
+//! Measuring elapsed time, with humanized output.
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use super::format::{DurationOptions, format_duration_with};
+
+/// Measures time since it was started, with laps.
+///
+/// `Display` prints the elapsed time with [`format_duration_with`], showing
+/// the two largest units by default (`1m 30s`) or as many as the precision
+/// says (`{:.1}` gives `2m`).
+///
+/// Example:
+/// ```
+/// use common_utils_rs::time::Stopwatch;
+/// let mut stopwatch = Stopwatch::start();
+/// // ... load the config
+/// let load = stopwatch.lap();
+/// // ... connect
+/// let connect = stopwatch.lap();
+/// assert!(stopwatch.elapsed() >= load + connect);
+/// println!("ready in {}", stopwatch);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stopwatch {
+    started: Instant,
+    lap_started: Instant,
+}
+
+impl Stopwatch {
+    /// A stopwatch running from now.
+    pub fn start() -> Self {
+        let now = Instant::now();
+        Self {
+            started: now,
+            lap_started: now,
+        }
+    }
+
+    /// Time since the stopwatch was started or last restarted.
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Time since the previous lap (or the start), starting a new lap.
+    pub fn lap(&mut self) -> Duration {
+        let now = Instant::now();
+        let lap = now - self.lap_started;
+        self.lap_started = now;
+        lap
+    }
+
+    /// Start again from zero, returning the time elapsed until now.
+    pub fn restart(&mut self) -> Duration {
+        let now = Instant::now();
+        let elapsed = now - self.started;
+        self.started = now;
+        self.lap_started = now;
+        elapsed
+    }
+}
+
+impl Default for Stopwatch {
+    fn default() -> Self {
+        Self::start()
+    }
+}
+
+impl fmt::Display for Stopwatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let options = DurationOptions {
+            max_units: Some(f.precision().unwrap_or(2)),
+            ..Default::default()
+        };
+        f.write_str(&format_duration_with(self.elapsed(), &options))
+    }
+}
+
+/// Reports how long a scope took when it is dropped.
+///
+/// The line `<label> took <elapsed>` goes to `log` and/or `tracing` at info
+/// level when those features are enabled, and to stderr otherwise.
+///
+/// Example:
+/// ```
+/// use common_utils_rs::time::ScopedTimer;
+/// fn migrate() {
+///     let _timer = ScopedTimer::new("migrations");
+///     // ... run them; "migrations took 1s 250ms" is reported on return
+/// }
+/// ```
+#[derive(Debug)]
+pub struct ScopedTimer {
+    label: String,
+    stopwatch: Stopwatch,
+}
+
+impl ScopedTimer {
+    /// A timer for `label`, running from now.
+    pub fn new(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            stopwatch: Stopwatch::start(),
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Time since the timer was created.
+    pub fn elapsed(&self) -> Duration {
+        self.stopwatch.elapsed()
+    }
+}
+
+impl Drop for ScopedTimer {
+    fn drop(&mut self) {
+        emit(&self.label, &self.stopwatch);
+    }
+}
+
+#[cfg(any(feature = "log", feature = "tracing"))]
+fn emit(label: &str, stopwatch: &Stopwatch) {
+    #[cfg(feature = "log")]
+    log::info!("{} took {}", label, stopwatch);
+    #[cfg(feature = "tracing")]
+    tracing::info!(label, elapsed = %stopwatch, "timer finished");
+}
+
+#[cfg(not(any(feature = "log", feature = "tracing")))]
+fn emit(label: &str, stopwatch: &Stopwatch) {
+    eprintln!("{} took {}", label, stopwatch);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A stopwatch started `ago` in the past.
+    fn started_ago(ago: Duration) -> Stopwatch {
+        let started = Instant::now() - ago;
+        Stopwatch {
+            started,
+            lap_started: started,
+        }
+    }
+
+    #[test]
+    fn test_laps_add_up_to_elapsed() {
+        let mut stopwatch = started_ago(Duration::from_secs(5));
+        let first = stopwatch.lap();
+        let second = stopwatch.lap();
+        assert!(first >= Duration::from_secs(5));
+        assert!(second < Duration::from_secs(5));
+        assert!(stopwatch.elapsed() >= first + second);
+    }
+
+    #[test]
+    fn test_restart() {
+        let mut stopwatch = started_ago(Duration::from_secs(60));
+        assert!(stopwatch.restart() >= Duration::from_secs(60));
+        assert!(stopwatch.elapsed() < Duration::from_secs(60));
+        assert!(stopwatch.lap() < Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_display() {
+        let stopwatch = started_ago(Duration::from_secs(3 * 3600 + 25 * 60 + 10));
+        assert_eq!(stopwatch.to_string(), "3h 25m");
+        assert_eq!(format!("{:.1}", stopwatch), "3h");
+        assert_eq!(format!("{:.3}", stopwatch), "3h 25m 10s");
+    }
+
+    #[test]
+    fn test_scoped_timer() {
+        let timer = ScopedTimer::new(String::from("load"));
+        assert_eq!(timer.label(), "load");
+        assert!(timer.elapsed() < Duration::from_secs(60));
+    }
+}
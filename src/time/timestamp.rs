@@ -0,0 +1,249 @@
+//! Unix timestamps and RFC 3339 strings, without chrono or time.
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use thiserror::Error;
+
+use super::calendar::{
+    civil_from_days, days_from_civil, days_in_month, to_system_time, unix_seconds,
+};
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[error("Invalid RFC 3339 timestamp `{input}`: {reason}")]
+pub struct ParseTimestampError {
+    pub input: String,
+    pub reason: String,
+}
+
+/// Whole seconds since the Unix epoch, or 0 if the clock is set before
+/// 1970.
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs())
+}
+
+/// Milliseconds since the Unix epoch, or 0 if the clock is set before 1970.
+pub fn unix_now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_millis() as u64)
+}
+
+/// `time` in UTC as RFC 3339 with millisecond precision, e.g.
+/// `2024-05-01T12:30:00.250Z`, the form JavaScript's `toISOString` uses.
+///
+/// Smaller fractions are truncated. Times outside the years 0000 to 9999
+/// can't be written in RFC 3339 and get a year with more digits or a sign.
+///
+/// Example:
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use common_utils_rs::time::{from_rfc3339, to_rfc3339};
+/// let time = UNIX_EPOCH + Duration::from_millis(1_714_566_600_250);
+/// assert_eq!(to_rfc3339(time), "2024-05-01T12:30:00.250Z");
+/// assert_eq!(from_rfc3339(&to_rfc3339(time)).unwrap(), time);
+/// ```
+pub fn to_rfc3339(time: SystemTime) -> String {
+    let seconds = unix_seconds(time);
+    let millis = to_system_time(seconds, 0)
+        .and_then(|whole| time.duration_since(whole).ok())
+        .map_or(0, |fraction| fraction.subsec_millis());
+    let (year, month, day) = civil_from_days(seconds.div_euclid(86_400));
+    let second_of_day = seconds.rem_euclid(86_400);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        second_of_day / 3600,
+        second_of_day / 60 % 60,
+        second_of_day % 60,
+        millis
+    )
+}
+
+/// Parse an RFC 3339 timestamp such as `2024-05-01T12:30:00Z` or
+/// `2024-05-01T14:30:00.250+02:00`; the inverse of [`to_rfc3339`].
+///
+/// A bare ISO 8601 date (`2024-05-01`) is read as midnight UTC. Timestamps
+/// with a time of day must include `Z` or an offset; fractional seconds beyond
+/// nanosecond precision are truncated.
+pub fn from_rfc3339(input: &str) -> Result<SystemTime, ParseTimestampError> {
+    let input = input.trim();
+    let err = |reason: &str| ParseTimestampError {
+        input: input.to_string(),
+        reason: reason.to_string(),
+    };
+
+    let mut rest = input;
+    let year = take_digits(&mut rest, 4).ok_or_else(|| err("expected a `YYYY-MM-DD` date"))?;
+    let month = take_separator(&mut rest, &['-'])
+        .and_then(|_| take_digits(&mut rest, 2))
+        .ok_or_else(|| err("expected a `YYYY-MM-DD` date"))?;
+    let day = take_separator(&mut rest, &['-'])
+        .and_then(|_| take_digits(&mut rest, 2))
+        .ok_or_else(|| err("expected a `YYYY-MM-DD` date"))?;
+    if !(1..=12).contains(&month) {
+        return Err(err("month must be between 01 and 12"));
+    }
+    if day == 0 || day > days_in_month(year, month) {
+        return Err(err("day is out of range for the month"));
+    }
+    let days = days_from_civil(i64::from(year), month, day);
+    if rest.is_empty() {
+        return to_system_time(days * 86_400, 0).ok_or_else(|| err("timestamp is out of range"));
+    }
+
+    take_separator(&mut rest, &['T', 't', ' '])
+        .ok_or_else(|| err("expected `T` after the date"))?;
+    let time = take_digits(&mut rest, 2).and_then(|hour| {
+        take_separator(&mut rest, &[':'])?;
+        let minute = take_digits(&mut rest, 2)?;
+        take_separator(&mut rest, &[':'])?;
+        Some((hour, minute, take_digits(&mut rest, 2)?))
+    });
+    let (hour, minute, second) = time.ok_or_else(|| err("expected an `HH:MM:SS` time"))?;
+    if hour > 23 || minute > 59 || second > 60 {
+        return Err(err("time of day is out of range"));
+    }
+
+    let mut nanos = 0;
+    if take_separator(&mut rest, &['.', ',']).is_some() {
+        let len = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(err("expected digits after the decimal point"));
+        }
+        let digits = &rest[..len.min(9)];
+        nanos = digits.parse::<u32>().unwrap_or(0) * 10u32.pow(9 - digits.len() as u32);
+        rest = &rest[len..];
+    }
+
+    let offset = match take_separator(&mut rest, &['Z', 'z', '+', '-']) {
+        Some('Z' | 'z') => 0,
+        Some(sign) => {
+            let hours = take_digits(&mut rest, 2);
+            let minutes = take_separator(&mut rest, &[':']).and_then(|_| take_digits(&mut rest, 2));
+            let (Some(hours), Some(minutes)) = (hours, minutes) else {
+                return Err(err("expected a `+HH:MM` offset"));
+            };
+            if hours > 23 || minutes > 59 {
+                return Err(err("offset is out of range"));
+            }
+            let offset = i64::from(hours * 3600 + minutes * 60);
+            if sign == '-' { -offset } else { offset }
+        }
+        None => return Err(err("missing `Z` or a UTC offset")),
+    };
+    if !rest.is_empty() {
+        return Err(err("unexpected characters after the timestamp"));
+    }
+
+    let seconds = days * 86_400 + i64::from(hour * 3600 + minute * 60 + second) - offset;
+    to_system_time(seconds, nanos).ok_or_else(|| err("timestamp is out of range"))
+}
+
+fn take_digits(rest: &mut &str, len: usize) -> Option<u32> {
+    let digits = rest.get(..len)?;
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    *rest = &rest[len..];
+    digits.parse().ok()
+}
+
+fn take_separator(rest: &mut &str, allowed: &[char]) -> Option<char> {
+    let c = rest.chars().next().filter(|c| allowed.contains(c))?;
+    *rest = &rest[c.len_utf8()..];
+    Some(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn at(seconds: u64, nanos: u32) -> SystemTime {
+        UNIX_EPOCH + Duration::new(seconds, nanos)
+    }
+
+    #[test]
+    fn test_unix_now() {
+        let seconds = unix_now();
+        let millis = unix_now_millis();
+        assert!(seconds > 1_700_000_000);
+        assert!(millis / 1000 >= seconds && millis / 1000 - seconds < 60);
+    }
+
+    #[test]
+    fn test_to_rfc3339() {
+        assert_eq!(to_rfc3339(UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+        assert_eq!(
+            to_rfc3339(at(1_709_164_800, 999_999_999)),
+            "2024-02-29T00:00:00.999Z"
+        );
+        assert_eq!(
+            to_rfc3339(UNIX_EPOCH - Duration::from_nanos(1)),
+            "1969-12-31T23:59:59.999Z"
+        );
+        assert_eq!(
+            to_rfc3339(UNIX_EPOCH - Duration::from_millis(86_400_001)),
+            "1969-12-30T23:59:59.999Z"
+        );
+        assert_eq!(
+            to_rfc3339(at(253_402_300_799, 0)),
+            "9999-12-31T23:59:59.000Z"
+        );
+    }
+
+    #[test]
+    fn test_from_rfc3339() {
+        assert_eq!(from_rfc3339("1970-01-01T00:00:00Z").unwrap(), UNIX_EPOCH);
+        assert_eq!(
+            from_rfc3339(" 2024-05-01t02:00:00.25+02:00 ").unwrap(),
+            at(1_714_521_600, 250_000_000)
+        );
+        assert_eq!(
+            from_rfc3339("2024-04-30 19:00:00.1234567891-05:00").unwrap(),
+            at(1_714_521_600, 123_456_789)
+        );
+        assert_eq!(from_rfc3339("2024-05-01").unwrap(), at(1_714_521_600, 0));
+        assert_eq!(
+            from_rfc3339("1969-12-31T23:59:59Z").unwrap(),
+            UNIX_EPOCH - Duration::from_secs(1)
+        );
+        for millis in [0, 1, 999, 1_714_566_600_250, 253_402_300_799_999] {
+            let time = UNIX_EPOCH + Duration::from_millis(millis);
+            assert_eq!(from_rfc3339(&to_rfc3339(time)).unwrap(), time);
+        }
+    }
+
+    #[test]
+    fn test_from_rfc3339_errors() {
+        let reason = |input: &str| from_rfc3339(input).unwrap_err().reason;
+        assert_eq!(reason(""), "expected a `YYYY-MM-DD` date");
+        assert_eq!(
+            reason("2024-13-01T00:00:00Z"),
+            "month must be between 01 and 12"
+        );
+        assert_eq!(
+            reason("2023-02-29T00:00:00Z"),
+            "day is out of range for the month"
+        );
+        assert_eq!(
+            reason("2024-05-01T24:00:00Z"),
+            "time of day is out of range"
+        );
+        assert_eq!(reason("2024-05-01T00:00Z"), "expected an `HH:MM:SS` time");
+        assert_eq!(reason("2024-05-01T00:00:00"), "missing `Z` or a UTC offset");
+        assert_eq!(
+            reason("2024-05-01T00:00:00+0200"),
+            "expected a `+HH:MM` offset"
+        );
+        assert_eq!(
+            from_rfc3339("soon").unwrap_err().to_string(),
+            "Invalid RFC 3339 timestamp `soon`: expected a `YYYY-MM-DD` date"
+        );
+    }
+}
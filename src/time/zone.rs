@@ -0,0 +1,217 @@
+//! Wall-clock times in IANA time zones (`tz` feature).
+//!
+//! Zoned times are chrono's `DateTime<Tz>`, with [`Tz`] re-exported from
+//! chrono-tz, so callers can name the zone type without depending on
+//! chrono-tz themselves. Both types are part of this module's public API.
+use std::time::SystemTime;
+
+use chrono::{DateTime, LocalResult, NaiveDate, NaiveTime, Offset, TimeZone, Utc};
+use thiserror::Error;
+
+use super::calendar::{to_system_time, unix_seconds};
+
+pub use chrono_tz::Tz;
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum TimeZoneError {
+    #[error("Unknown time zone `{0}`")]
+    Unknown(String),
+
+    #[error("Time is outside the range of supported dates")]
+    OutOfRange,
+}
+
+/// Look up an IANA time zone such as `Europe/Berlin` or `UTC`.
+pub fn parse_time_zone(name: &str) -> Result<Tz, TimeZoneError> {
+    name.trim()
+        .parse()
+        .map_err(|_| TimeZoneError::Unknown(name.to_string()))
+}
+
+/// `time` on the wall clock of the time zone `tz`.
+///
+/// Example:
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use common_utils_rs::time::in_zone;
+/// let noon_utc = UNIX_EPOCH + Duration::from_secs(1_714_564_800); // 2024-05-01T12:00:00Z
+/// let berlin = in_zone(noon_utc, "Europe/Berlin").unwrap();
+/// assert_eq!(berlin.to_rfc3339(), "2024-05-01T14:00:00+02:00");
+/// ```
+pub fn in_zone(time: SystemTime, tz: &str) -> Result<DateTime<Tz>, TimeZoneError> {
+    let tz = parse_time_zone(tz)?;
+    Ok(to_utc(time)?.with_timezone(&tz))
+}
+
+/// The start of today in the time zone `tz`, e.g. to schedule a daily job
+/// by local time.
+pub fn local_midnight(tz: &str) -> Result<SystemTime, TimeZoneError> {
+    midnight_in(SystemTime::now(), tz)
+}
+
+/// The start of the day containing `time` in the time zone `tz`.
+///
+/// Where a daylight saving change skips midnight, the day starts at the
+/// earliest wall-clock time that exists, e.g. 01:00.
+///
+/// Example:
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use common_utils_rs::time::midnight_in;
+/// let time = UNIX_EPOCH + Duration::from_secs(1_714_564_800); // 2024-05-01T12:00:00Z
+/// let midnight = midnight_in(time, "America/New_York").unwrap();
+/// assert_eq!(midnight, UNIX_EPOCH + Duration::from_secs(1_714_536_000)); // 04:00Z
+/// ```
+pub fn midnight_in(time: SystemTime, tz: &str) -> Result<SystemTime, TimeZoneError> {
+    let tz = parse_time_zone(tz)?;
+    let date = to_utc(time)?.with_timezone(&tz).date_naive();
+    let start = start_of_day(tz, date).ok_or(TimeZoneError::OutOfRange)?;
+    to_system_time(start.timestamp(), start.timestamp_subsec_nanos())
+        .ok_or(TimeZoneError::OutOfRange)
+}
+
+/// The UTC offset of the time zone `tz` at `time`, in seconds east of UTC.
+pub fn utc_offset(time: SystemTime, tz: &str) -> Result<i32, TimeZoneError> {
+    Ok(in_zone(time, tz)?.offset().fix().local_minus_utc())
+}
+
+/// A UTC offset in seconds as `+HH:MM`, with seconds only when nonzero
+/// (`+00:19:32`).
+///
+/// Example:
+/// ```
+/// use common_utils_rs::time::format_offset;
+/// assert_eq!(format_offset(7200), "+02:00");
+/// assert_eq!(format_offset(-12_600), "-03:30");
+/// assert_eq!(format_offset(0), "+00:00");
+/// ```
+pub fn format_offset(seconds: i32) -> String {
+    let sign = if seconds < 0 { '-' } else { '+' };
+    let seconds = seconds.unsigned_abs();
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if seconds == 0 {
+        format!("{}{:02}:{:02}", sign, hours, minutes)
+    } else {
+        format!("{}{:02}:{:02}:{:02}", sign, hours, minutes, seconds)
+    }
+}
+
+/// Like [`to_rfc3339`](super::to_rfc3339), in the local time of `tz` with
+/// its offset instead of `Z`.
+///
+/// Example:
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use common_utils_rs::time::to_rfc3339_in;
+/// let time = UNIX_EPOCH + Duration::from_millis(1_714_564_800_250);
+/// assert_eq!(
+///     to_rfc3339_in(time, "Asia/Kolkata").unwrap(),
+///     "2024-05-01T17:30:00.250+05:30"
+/// );
+/// ```
+pub fn to_rfc3339_in(time: SystemTime, tz: &str) -> Result<String, TimeZoneError> {
+    let local = in_zone(time, tz)?;
+    Ok(format!(
+        "{}{}",
+        local.format("%Y-%m-%dT%H:%M:%S%.3f"),
+        format_offset(local.offset().fix().local_minus_utc())
+    ))
+}
+
+/// `time` as a chrono UTC timestamp, or an error where chrono's range ends.
+fn to_utc(time: SystemTime) -> Result<DateTime<Utc>, TimeZoneError> {
+    let seconds = unix_seconds(time);
+    let nanos = match time.duration_since(SystemTime::UNIX_EPOCH) {
+        Ok(since) => since.subsec_nanos(),
+        Err(before) => (1_000_000_000 - before.duration().subsec_nanos()) % 1_000_000_000,
+    };
+    DateTime::from_timestamp(seconds, nanos).ok_or(TimeZoneError::OutOfRange)
+}
+
+/// The earliest instant of `date` in `tz`.
+fn start_of_day(tz: Tz, date: NaiveDate) -> Option<DateTime<Tz>> {
+    let midnight = date.and_time(NaiveTime::MIN);
+    match tz.from_local_datetime(&midnight) {
+        LocalResult::Single(start) | LocalResult::Ambiguous(start, _) => Some(start),
+        LocalResult::None => {
+            // Midnight falls in a gap, so the day starts at the transition:
+            // the first second whose local time is past midnight. Offsets
+            // stay within a day of UTC, which bounds the search.
+            let guess = midnight.and_utc().timestamp();
+            let reached = |seconds: i64| {
+                DateTime::from_timestamp(seconds, 0)
+                    .is_none_or(|t| t.with_timezone(&tz).naive_local() >= midnight)
+            };
+            let (mut low, mut high) = (guess - 86_400, guess + 86_400);
+            while low < high {
+                let mid = low + (high - low) / 2;
+                if reached(mid) {
+                    high = mid;
+                } else {
+                    low = mid + 1;
+                }
+            }
+            DateTime::from_timestamp(low, 0).map(|start| start.with_timezone(&tz))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn at(seconds: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(seconds)
+    }
+
+    #[test]
+    fn test_in_zone_and_offsets() {
+        // 2024-03-31T00:30:00Z and 01:30Z, around the Berlin DST change.
+        assert_eq!(utc_offset(at(1_711_845_000), "Europe/Berlin"), Ok(3600));
+        assert_eq!(utc_offset(at(1_711_848_600), "Europe/Berlin"), Ok(7200));
+        assert_eq!(utc_offset(at(0), " UTC "), Ok(0));
+        assert_eq!(
+            in_zone(at(0), "Europe/Atlantis").unwrap_err().to_string(),
+            "Unknown time zone `Europe/Atlantis`"
+        );
+        assert_eq!(
+            to_rfc3339_in(at(0), "America/Los_Angeles").unwrap(),
+            "1969-12-31T16:00:00.000-08:00"
+        );
+        assert_eq!(
+            to_rfc3339_in(UNIX_EPOCH - Duration::from_millis(250), "UTC").unwrap(),
+            "1969-12-31T23:59:59.750+00:00"
+        );
+        let far_future = UNIX_EPOCH + Duration::from_secs(i64::MAX as u64 / 2);
+        assert_eq!(in_zone(far_future, "UTC"), Err(TimeZoneError::OutOfRange));
+        assert_eq!(
+            midnight_in(far_future, "UTC"),
+            Err(TimeZoneError::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_midnight_in() {
+        // 2024-05-01T23:30:00Z is already May 2nd in Tokyo.
+        let time = at(1_714_606_200);
+        assert_eq!(midnight_in(time, "UTC"), Ok(at(1_714_521_600)));
+        assert_eq!(midnight_in(time, "Asia/Tokyo"), Ok(at(1_714_575_600)));
+        // Santiago skipped from 00:00 to 01:00 on 2024-09-08.
+        let santiago = midnight_in(at(1_725_811_200), "America/Santiago").unwrap();
+        assert_eq!(santiago, at(1_725_768_000));
+        // Havana skipped from 00:00 to 01:00 on 2024-03-10.
+        let havana = midnight_in(at(1_710_072_000), "America/Havana").unwrap();
+        assert_eq!(havana, at(1_710_046_800));
+        assert!(local_midnight("Europe/Berlin").unwrap() <= SystemTime::now());
+        assert!(local_midnight("Nowhere/Special").is_err());
+    }
+
+    #[test]
+    fn test_format_offset() {
+        assert_eq!(format_offset(19_800), "+05:30");
+        assert_eq!(format_offset(-3600), "-01:00");
+        assert_eq!(format_offset(1172), "+00:19:32");
+        assert_eq!(format_offset(-50_400), "-14:00");
+    }
+}